@@ -1,37 +1,180 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
-use web_sys::CanvasRenderingContext2d;
+use js_sys::Math;
+use neo_scene::arrange;
+use neo_scene::group;
+use neo_scene::order;
+use neo_scene::format::{self, FormatError, NeoFile, View};
+use neo_scene::history::History;
+use neo_scene::layer;
+use neo_scene::font::TextStyle;
+use neo_scene::plugin::{Input, Plugin, PluginContext, PluginHost, Viewport};
+use neo_scene::text::{self, Composition};
+use neo_scene::{Brush, Color, Document, EraseMode, Eraser, Object, ObjectId, Point, Rect, Shape, ShapeKind, Stroke, Style, Transform};
+use web_sys::{CanvasRenderingContext2d, FontFaceSetLoadStatus, HtmlTextAreaElement, WheelEvent};
 use wasm_bindgen::prelude::*;
 
-use crate::mouse::MouseInfo;
+use crate::camera::Camera;
+use crate::plugins::CanvasDraw;
+use crate::pointer::Pointers;
+use crate::text::{register_fonts, TextEdit, TextLayout, TEXT_BOX_HEIGHT, TEXT_BOX_WIDTH};
+use crate::text_input::create_text_area;
 
 #[wasm_bindgen]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum UserActionMode {
     Drag,
-    Draw
+    Draw,
+    Erase,
+    Text,
 }
 
+impl UserActionMode {
+    /// Stable name used when saving the view to a `.neo` file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserActionMode::Drag => "drag",
+            UserActionMode::Draw => "draw",
+            UserActionMode::Erase => "erase",
+            UserActionMode::Text => "text",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "drag" => Some(UserActionMode::Drag),
+            "draw" => Some(UserActionMode::Draw),
+            "erase" => Some(UserActionMode::Erase),
+            "text" => Some(UserActionMode::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Which edge or centre line [`WasmStateWrapper::align`] lines objects up on.
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl From<Alignment> for arrange::Alignment {
+    fn from(alignment: Alignment) -> Self {
+        match alignment {
+            Alignment::Left => arrange::Alignment::Left,
+            Alignment::Center => arrange::Alignment::Center,
+            Alignment::Right => arrange::Alignment::Right,
+            Alignment::Top => arrange::Alignment::Top,
+            Alignment::Middle => arrange::Alignment::Middle,
+            Alignment::Bottom => arrange::Alignment::Bottom,
+        }
+    }
+}
+
+/// Where [`WasmStateWrapper::reorder`] moves objects in draw order, among the others in the
+/// same group.
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum Reorder {
+    Forward,
+    Backward,
+    ToFront,
+    ToBack,
+}
+
+impl From<Reorder> for order::Reorder {
+    fn from(reorder: Reorder) -> Self {
+        match reorder {
+            Reorder::Forward => order::Reorder::Forward,
+            Reorder::Backward => order::Reorder::Backward,
+            Reorder::ToFront => order::Reorder::ToFront,
+            Reorder::ToBack => order::Reorder::ToBack,
+        }
+    }
+}
+
+/// World units left between objects tidied into a grid.
+const TIDY_GAP: f64 = 20.0;
+/// Canvas pixels around the viewport that are still drawn, so nothing pops in at the edges.
+const CULL_MARGIN: f64 = 64.0;
+/// Pixels scrolled per line when the browser reports wheel movement in lines.
+const WHEEL_LINE_HEIGHT: f64 = 16.0;
+/// Below this many canvas pixels, shape text is too small to read and isn't drawn.
+const MIN_READABLE_TEXT: f64 = 3.0;
+/// Range of eraser radii the page may set, in screen pixels.
+const ERASER_SIZES: std::ops::RangeInclusive<f64> = 1.0..=200.0;
+const SELECTION_COLOR: &str = "rgba(51,115,255,0.3)";
+
 pub struct State {
     context: Rc<CanvasRenderingContext2d>,
-    mouse_info: Rc<MouseInfo>,
-    user_action_mode: Cell<UserActionMode>
+    pointers: Rc<Pointers>,
+    user_action_mode: Cell<UserActionMode>,
+    document: RefCell<Document>,
+    history: RefCell<History>,
+    camera: RefCell<Camera>,
+    /// The stroke being drawn, kept out of the document until the button is released.
+    current_stroke: RefCell<Option<Stroke>>,
+    /// How new strokes look and how pointer input becomes one.
+    brush: Cell<Brush>,
+    eraser: Cell<Eraser>,
+    /// World position of the eraser while it is pressed.
+    erasing_at: Cell<Option<Point>>,
+    needs_redraw: Cell<bool>,
+    plugins: RefCell<PluginHost>,
+    /// Input since the last animation frame, collected from events for the plugins.
+    input: RefCell<Input>,
+    text_edit: RefCell<Option<TextEdit>>,
+    /// How the text of new text boxes is set.
+    text_style: RefCell<TextStyle>,
+    /// Fonts saved with the document are still loading, and text drawn with a stand-in font
+    /// has to be drawn again once they are ready.
+    fonts_loading: Cell<bool>,
+    /// Where typing goes while a shape's text is edited; see [`crate::text_input`].
+    text_area: HtmlTextAreaElement,
 }
 
 impl State {
     pub fn new(context: Rc<CanvasRenderingContext2d>) -> State {
+        let camera = match context.canvas() {
+            Some(canvas) => Camera::new(canvas.width() as f64, canvas.height() as f64),
+            None => Camera::new(0.0, 0.0),
+        };
+        let document = web_sys::window().unwrap().document().unwrap();
+        let text_area = create_text_area(&document).unwrap();
         State {
             context,
-            mouse_info: Rc::new(MouseInfo::new()),
-            user_action_mode: Cell::new(UserActionMode::Drag)
+            pointers: Rc::new(Pointers::new()),
+            user_action_mode: Cell::new(UserActionMode::Draw),
+            document: RefCell::new(Document::with_site((Math::random() * u32::MAX as f64) as u32)),
+            history: RefCell::new(History::default()),
+            camera: RefCell::new(camera),
+            current_stroke: RefCell::new(None),
+            brush: Cell::new(Brush::default()),
+            eraser: Cell::new(Eraser::default()),
+            erasing_at: Cell::new(None),
+            needs_redraw: Cell::new(true),
+            plugins: RefCell::new(PluginHost::new()),
+            input: RefCell::new(Input::default()),
+            text_edit: RefCell::new(None),
+            text_style: RefCell::new(TextStyle::default()),
+            fonts_loading: Cell::new(false),
+            text_area,
         }
     }
 
-    pub fn mouse_info(&self) -> Rc<MouseInfo> {
-        self.mouse_info.clone()
+    pub(crate) fn pointers(&self) -> Rc<Pointers> {
+        self.pointers.clone()
     }
 
     pub fn set_user_action_mode(&self, mode: UserActionMode) {
+        if mode != UserActionMode::Text {
+            self.finish_text();
+        }
         self.user_action_mode.set(mode);
     }
 
@@ -39,21 +182,591 @@ impl State {
         self.user_action_mode.get()
     }
 
-    pub fn draw_line(&self) {
-        let (x, y) = self.mouse_info.get_coords();
-        let (last_x, last_y) = self.mouse_info.get_last_coords();
-        
-        if let (Some(lx), Some(ly)) = (last_x, last_y) {
+    /// Pans the view while the canvas is dragged in Drag mode; `delta` is in canvas pixels.
+    pub fn drag(&self, delta: Point) {
+        self.camera.borrow_mut().pan(delta);
+        self.invalidate();
+    }
+
+    /// Follows a two-finger gesture whose midpoint moved from `from` to `to` while the distance
+    /// between the fingers was multiplied by `scale`.
+    pub fn pinch(&self, from: Point, to: Point, scale: f64) {
+        let mut camera = self.camera.borrow_mut();
+        camera.pan(to - from);
+        camera.zoom_by(to, scale);
+        self.invalidate();
+    }
+
+    /// Handles a wheel event at (`x`, `y`) on the canvas. Like the native engine, Ctrl + wheel
+    /// (also what browsers send for trackpad pinches) zooms around the cursor and the plain
+    /// wheel scrolls.
+    pub fn wheel(&self, x: f64, y: f64, dx: f64, dy: f64, zoom: bool) {
+        let mut camera = self.camera.borrow_mut();
+        if zoom {
+            camera.zoom_at(Point::new(x, y), dy);
+        } else {
+            camera.pan(Point::new(-dx, -dy));
+        }
+        self.invalidate();
+    }
+
+    /// Converts a wheel delta to pixels, whichever unit the browser reported it in.
+    pub fn wheel_delta_to_pixels(&self, delta: f64, delta_mode: u32) -> f64 {
+        match delta_mode {
+            WheelEvent::DOM_DELTA_LINE => delta * WHEEL_LINE_HEIGHT,
+            WheelEvent::DOM_DELTA_PAGE => delta * self.camera.borrow().height,
+            _ => delta,
+        }
+    }
+
+    /// Changes the brush used for strokes started from now on.
+    pub fn update_brush(&self, update: impl FnOnce(&mut Brush)) {
+        let mut brush = self.brush.get();
+        update(&mut brush);
+        self.brush.set(brush);
+    }
+
+    /// Adds a pointer sample, in canvas pixels, to the stroke in progress, starting one if
+    /// needed. `pressure` and `tilt` come from the pen; see [`Brush::width_factor`].
+    pub fn add_stroke_point(&self, screen_pos: Point, pressure: f64, tilt: f64) {
+        let sample = self.camera.borrow().screen_to_world(screen_pos);
+        let brush = self.brush.get();
+
+        let mut current = self.current_stroke.borrow_mut();
+        let stroke = current.get_or_insert_with(|| brush.begin());
+        let count = stroke.points.len();
+        brush.extend(stroke, sample, pressure, tilt);
+        if stroke.points.len() != count {
+            self.invalidate();
+        }
+    }
+
+    /// Commits the stroke in progress to the document as a single undo step.
+    pub fn end_stroke(&self) {
+        let Some(mut stroke) = self.current_stroke.take() else {
+            return;
+        };
+        self.brush.get().finish(&mut stroke, self.camera.borrow().zoom);
+        let mut document = self.document.borrow_mut();
+        let mut history = self.history.borrow_mut();
+        let id = document.push(stroke);
+        history.track_created(id);
+        history.commit(&document);
+        self.invalidate();
+    }
+
+    pub fn update_eraser(&self, update: impl FnOnce(&mut Eraser)) {
+        let mut eraser = self.eraser.get();
+        update(&mut eraser);
+        self.eraser.set(eraser);
+    }
+
+    /// Moves the pressed eraser to a position in canvas pixels, erasing whatever it passes over
+    /// since the last position.
+    pub fn erase_to(&self, screen_pos: Point) {
+        let camera = *self.camera.borrow();
+        let to = camera.screen_to_world(screen_pos);
+        let from = self.erasing_at.replace(Some(to)).unwrap_or(to);
+        let mut document = self.document.borrow_mut();
+        let mut history = self.history.borrow_mut();
+        self.eraser.get().erase(&mut document, &mut history, from, to, camera.zoom);
+        // redraw either way to move the eraser outline
+        self.invalidate();
+    }
+
+    /// Lifts the eraser, making everything erased since it was pressed a single undo step.
+    pub fn end_erase(&self) {
+        if self.erasing_at.take().is_some() {
+            self.history.borrow_mut().commit(&self.document.borrow());
+            self.invalidate();
+        }
+    }
+
+    /// Drops the stroke in progress without adding it to the document.
+    pub fn cancel_stroke(&self) {
+        if self.current_stroke.take().is_some() {
+            self.invalidate();
+        }
+    }
+
+    pub fn undo(&self) {
+        self.finish_text();
+        self.current_stroke.take();
+        if self.history.borrow_mut().undo(&mut self.document.borrow_mut()) {
+            self.invalidate();
+        }
+    }
+
+    pub fn redo(&self) {
+        self.finish_text();
+        self.current_stroke.take();
+        if self.history.borrow_mut().redo(&mut self.document.borrow_mut()) {
+            self.invalidate();
+        }
+    }
+
+    /// Runs one of the [`arrange`] commands on `ids` as an undo step of its own.
+    pub fn arrange(&self, ids: &[ObjectId], command: impl FnOnce(&mut Document, &mut History, &[ObjectId]) -> bool) {
+        self.finish_text();
+        let mut document = self.document.borrow_mut();
+        let mut history = self.history.borrow_mut();
+        history.commit(&document);
+        if command(&mut document, &mut history, ids) {
+            history.commit(&document);
+            self.invalidate();
+        }
+    }
+
+    /// Ids of the objects touching a rectangle in canvas pixels, back to front.
+    pub fn objects_in(&self, rect: Rect) -> Vec<ObjectId> {
+        let camera = self.camera.borrow();
+        let world = Rect::from_corners(camera.screen_to_world(rect.min()), camera.screen_to_world(rect.max()));
+        self.document.borrow().in_rect(world)
+    }
+
+    pub fn text_area(&self) -> HtmlTextAreaElement {
+        self.text_area.clone()
+    }
+
+    pub fn set_text_style(&self, style: TextStyle) {
+        *self.text_style.borrow_mut() = style;
+    }
+
+    pub fn is_editing_text(&self) -> bool {
+        self.text_edit.borrow().is_some()
+    }
+
+    pub fn is_composing(&self) -> bool {
+        self.text_edit.borrow().as_ref().is_some_and(|edit| edit.composition.is_some())
+    }
+
+    /// Presses in Text mode at a position in canvas pixels. Inside the text being edited this
+    /// places the caret, or with `extend` (Shift) grows the selection. Anywhere else it finishes
+    /// that edit and starts typing into the shape there, or into a new text box.
+    pub fn press_text(&self, screen_pos: Point, extend: bool) {
+        // blurring makes the input method commit what it was composing where the caret was
+        if self.is_composing() {
+            let _ = self.text_area.blur();
+        }
+        let point = self.camera.borrow().screen_to_world(screen_pos);
+        let inside = {
+            let edit = self.text_edit.borrow();
+            let document = self.document.borrow();
+            edit.as_ref().and_then(|edit| document.shape(edit.id)).is_some_and(|shape| shape.hit_test(point, 0.0))
+        };
+        if !inside {
+            self.finish_text();
+            self.begin_text(point);
+        }
+        self.move_caret_to(point, extend && inside);
+        let _ = self.text_area.focus();
+    }
+
+    /// Drags the caret to a position in canvas pixels, selecting from where the press started.
+    pub fn select_text_to(&self, screen_pos: Point) {
+        let point = self.camera.borrow().screen_to_world(screen_pos);
+        self.move_caret_to(point, true);
+    }
+
+    fn move_caret_to(&self, point: Point, extend: bool) {
+        let mut edit = self.text_edit.borrow_mut();
+        let document = self.document.borrow();
+        let Some((edit, shape)) = edit.as_mut().and_then(|edit| document.shape(edit.id).map(|shape| (edit, shape))) else {
+            return;
+        };
+        let point = shape.world_transform().inverse().map_or(point, |local| local.apply(point));
+        let offset = TextLayout::new(&self.context, &shape.text, shape.bounds, &shape.text_style).offset_at(point);
+        edit.cursor.move_to(offset, extend);
+        edit.goal_x = None;
+        self.invalidate();
+    }
+
+    /// Starts typing into the shape under a world point, or into a new text box there.
+    fn begin_text(&self, point: Point) {
+        let mut document = self.document.borrow_mut();
+        let mut history = self.history.borrow_mut();
+        let hit = document
+            .hits_at(point, 0.0)
+            .into_iter()
+            .find(|id| layer::is_pickable(&document, *id))
+            .filter(|id| matches!(document.get(*id), Some(Object::Shape(_))));
+        let id = match hit {
+            Some(id) => {
+                history.track(&document, id);
+                id
+            }
+            None => {
+                let bounds = Rect::new(point.x, point.y, TEXT_BOX_WIDTH, TEXT_BOX_HEIGHT);
+                let mut shape = Shape::new(ShapeKind::Rectangle, bounds, Style::filled(Color::TRANSPARENT));
+                shape.text_style = self.text_style.borrow().clone();
+                let id = document.push(shape);
+                history.track_created(id);
+                id
+            }
+        };
+        *self.text_edit.borrow_mut() = Some(TextEdit::new(id, hit.is_none()));
+        self.invalidate();
+    }
+
+    /// Ends the edit in progress as a single undo step, dropping a new text box left empty.
+    pub fn finish_text(&self) {
+        // commits whatever the input method is still composing first
+        let _ = self.text_area.blur();
+        let Some(edit) = self.text_edit.take() else {
+            return;
+        };
+        let mut document = self.document.borrow_mut();
+        if edit.created && document.shape(edit.id).is_some_and(|shape| shape.text.is_empty()) {
+            document.remove(edit.id);
+        }
+        self.history.borrow_mut().commit(&document);
+        self.invalidate();
+    }
+
+    /// Replaces the selection with typed, pasted or committed text.
+    pub fn type_text(&self, typed: &str) {
+        let mut edit = self.text_edit.borrow_mut();
+        if let Some(edit) = edit.as_mut() {
+            edit.apply(&mut self.document.borrow_mut(), |cursor, text| cursor.insert(text, typed));
+            self.invalidate();
+        }
+    }
+
+    /// Shows what the input method is composing at the caret, or stops showing it.
+    pub fn compose_text(&self, composition: Option<Composition>) {
+        if let Some(edit) = self.text_edit.borrow_mut().as_mut() {
+            edit.composition = composition;
+            self.invalidate();
+        }
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let edit = self.text_edit.borrow();
+        let document = self.document.borrow();
+        let edit = edit.as_ref().filter(|edit| edit.cursor.has_selection())?;
+        Some(edit.cursor.selected(&document.shape(edit.id)?.text).to_owned())
+    }
+
+    /// Reacts to a key pressed while typing; `key` is the browser's name for it. `command` is
+    /// Ctrl, or Cmd on a Mac, and `word` the modifier that moves by whole words. Returns
+    /// whether the key did anything, leaving everything else to the browser.
+    pub fn text_key(&self, key: &str, shift: bool, command: bool, word: bool) -> bool {
+        if key == "Escape" {
+            self.finish_text();
+            return true;
+        }
+        let mut edit = self.text_edit.borrow_mut();
+        let mut document = self.document.borrow_mut();
+        let Some(edit) = edit.as_mut() else {
+            return false;
+        };
+        let Some(shape) = document.shape(edit.id) else {
+            return false;
+        };
+        edit.cursor.clamp(&shape.text);
+        let content = shape.text.as_str();
+        let caret = edit.cursor.caret;
+        let layout = TextLayout::new(&self.context, content, shape.bounds, &shape.text_style);
+        let mut goal_x = None;
+        // moving without Shift collapses a selection to the side it moves towards
+        let collapse = edit.cursor.has_selection() && !shift;
+        let target = match key {
+            "ArrowLeft" if collapse => edit.cursor.range().start,
+            "ArrowRight" if collapse => edit.cursor.range().end,
+            "ArrowLeft" if word => text::previous_word(content, caret),
+            "ArrowLeft" => text::previous_grapheme(content, caret),
+            "ArrowRight" if word => text::next_word(content, caret),
+            "ArrowRight" => text::next_grapheme(content, caret),
+            "ArrowUp" | "ArrowDown" => {
+                let x = edit.goal_x.unwrap_or(layout.caret(caret).x);
+                goal_x = Some(x);
+                layout.vertical(caret, if key == "ArrowUp" { -1 } else { 1 }, x)
+            }
+            "Home" if command => 0,
+            "End" if command => content.len(),
+            "Home" => layout.line_bounds(caret).0,
+            "End" => layout.line_bounds(caret).1,
+            "a" | "A" if command => {
+                edit.cursor.select_all(content);
+                self.invalidate();
+                return true;
+            }
+            "Backspace" | "Delete" => {
+                let to = match (key, word) {
+                    ("Backspace", true) => text::previous_word(content, caret),
+                    ("Backspace", false) => text::previous_grapheme(content, caret),
+                    (_, true) => text::next_word(content, caret),
+                    (_, false) => text::next_grapheme(content, caret),
+                };
+                edit.apply(&mut document, |cursor, text| {
+                    cursor.delete_to(text, to);
+                });
+                self.invalidate();
+                return true;
+            }
+            "Enter" => {
+                edit.apply(&mut document, |cursor, text| cursor.insert(text, "\n"));
+                self.invalidate();
+                return true;
+            }
+            _ => return false,
+        };
+        edit.cursor.move_to(target, shift);
+        edit.goal_x = goal_x;
+        self.invalidate();
+        true
+    }
+
+    /// Serializes the drawing, camera and active mode as a `.neo` file, optionally with the
+    /// undo history so it survives a reload.
+    pub fn save(&self, include_history: bool) -> String {
+        let history = include_history.then(|| self.history.borrow().clone());
+        let camera = self.camera.borrow();
+        format::to_string(&NeoFile {
+            document: self.document.borrow().clone(),
+            view: View {
+                center: camera.position,
+                zoom: camera.zoom,
+                mode: self.get_user_action_mode().name().to_owned(),
+            },
+            history,
+        })
+    }
+
+    /// Replaces the drawing with the contents of a `.neo` file.
+    pub fn load(&self, contents: &str) -> Result<(), FormatError> {
+        let file = format::from_str(contents)?;
+        self.finish_text();
+        {
+            let mut document = self.document.borrow_mut();
+            let site = document.site();
+            *document = file.document;
+            document.set_site(site);
+            if !document.fonts().is_empty() {
+                register_fonts(document.fonts());
+                self.fonts_loading.set(true);
+            }
+        }
+        *self.history.borrow_mut() = file.history.unwrap_or_default();
+        if let Some(mode) = UserActionMode::from_name(&file.view.mode) {
+            self.set_user_action_mode(mode);
+        }
+        self.camera.borrow_mut().set_view(file.view.center, file.view.zoom);
+        self.current_stroke.take();
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Installs a plugin; its hooks run from the next animation frame on.
+    pub fn add_plugin(&self, plugin: Box<dyn Plugin>) {
+        let mut plugins = self.plugins.borrow_mut();
+        self.with_plugin_context(|ctx| plugins.add(plugin, ctx));
+    }
+
+    /// Records pointer or keyboard input for the plugins to see on the next animation frame.
+    pub fn update_input(&self, update: impl FnOnce(&mut Input)) {
+        update(&mut self.input.borrow_mut());
+    }
+
+    /// Runs `f` with a plugin context over the drawing, then follows whatever the plugins did
+    /// to the viewport and repaints if they asked for it.
+    fn with_plugin_context<R>(&self, f: impl FnOnce(&mut PluginContext) -> R) -> R {
+        let camera = *self.camera.borrow();
+        let mut viewport = Viewport {
+            center: camera.position,
+            zoom: camera.zoom,
+            width: camera.width,
+            height: camera.height,
+        };
+        let (result, redraw) = {
+            let mut document = self.document.borrow_mut();
+            let mut history = self.history.borrow_mut();
+            let input = self.input.borrow();
+            let mut ctx = PluginContext::new(&mut document, &mut history, &mut viewport, &input);
+            let result = f(&mut ctx);
+            (result, ctx.redraw_requested())
+        };
+        if viewport.center != camera.position || viewport.zoom != camera.zoom {
+            self.camera.borrow_mut().set_view(viewport.center, viewport.zoom);
+            self.invalidate();
+        }
+        if redraw {
+            self.invalidate();
+        }
+        result
+    }
+
+    /// Marks the canvas as out of date; it is repainted on the next animation frame.
+    pub fn invalidate(&self) {
+        self.needs_redraw.set(true);
+    }
+
+    /// Runs one frame: the plugins' frame hooks around a repaint of the canvas, if anything
+    /// changed since the last frame.
+    pub fn render(&self) {
+        if self.fonts_loading.get() && fonts_loaded() {
+            self.fonts_loading.set(false);
+            self.invalidate();
+        }
+        let mut plugins = self.plugins.borrow_mut();
+        self.with_plugin_context(|ctx| plugins.begin_frame(ctx));
+        if self.needs_redraw.replace(false) {
+            self.redraw();
+            self.with_plugin_context(|ctx| plugins.render(ctx, &mut CanvasDraw(&self.context)));
+        }
+        self.with_plugin_context(|ctx| plugins.end_frame(ctx));
+        // edits the plugins made are an undo step of their own, unless the eraser's or the text
+        // edit's step is still open
+        let open = self.erasing_at.get().is_some() || self.is_editing_text();
+        if !open && self.history.borrow_mut().commit(&self.document.borrow()) {
+            self.invalidate();
+        }
+        self.input.borrow_mut().end_frame();
+    }
+
+    /// Clears the canvas and paints the objects that intersect it again, then the stroke in
+    /// progress and the text being edited on top.
+    fn redraw(&self) {
+        let camera = *self.camera.borrow();
+        let _ = self.context.reset_transform();
+        self.context.clear_rect(0.0, 0.0, camera.width, camera.height);
+
+        // everything below is drawn in world coordinates
+        let Transform { a, b, c, d, e, f } = camera.transform();
+        let _ = self.context.set_transform(a, b, c, d, e, f);
+        let visible = camera.visible_world_bounds().expand(CULL_MARGIN / camera.zoom);
+        let editing = self.text_edit.borrow().as_ref().map(|edit| edit.id);
+        let document = self.document.borrow();
+        for (id, object) in document.iter_in(visible) {
+            if layer::is_hidden(&document, id) {
+                continue;
+            }
+            match object {
+                Object::Stroke(stroke) => neo_canvas::draw_stroke(&self.context, stroke),
+                Object::Shape(shape) => {
+                    self.context.save();
+                    neo_canvas::apply_transform(&self.context, shape.world_transform());
+                    neo_canvas::draw_shape(&self.context, shape);
+                    // the text being edited is drawn with the edit
+                    let readable = shape.text_style.size * camera.zoom >= MIN_READABLE_TEXT;
+                    if readable && editing != Some(id) {
+                        self.draw_text(&TextLayout::new(&self.context, &shape.text, shape.bounds, &shape.text_style));
+                    }
+                    self.context.restore();
+                }
+                Object::Connector(connector) => neo_canvas::draw_connector(&self.context, connector),
+                // drawn through its children
+                Object::Group(_) => {}
+            }
+        }
+        if let Some(stroke) = self.current_stroke.borrow().as_ref() {
+            neo_canvas::draw_stroke(&self.context, stroke);
+        }
+        self.draw_text_edit(&camera);
+        let _ = self.context.reset_transform();
+
+        if let Some(center) = self.erasing_at.get() {
+            let center = camera.world_to_screen(center);
+            self.context.set_stroke_style_str("rgba(0,0,0,0.5)");
+            self.context.set_line_width(1.0);
             self.context.begin_path();
-            self.context.move_to(lx, ly);
-            self.context.line_to(x, y);
+            let _ = self.context.arc(center.x, center.y, self.eraser.get().radius, 0.0, std::f64::consts::TAU);
             self.context.stroke();
         }
-        
-        self.mouse_info.update_last_coords(x, y);
+    }
+
+    fn draw_text(&self, layout: &TextLayout) {
+        self.context.set_fill_style_str("black");
+        for row in 0..layout.lines.len() {
+            let y = layout.origin.y + row as f64 * layout.line_height();
+            let _ = self.context.fill_text(layout.line_text(row), layout.origin.x, y);
+        }
+    }
+
+    /// Draws the text being edited with the selection, or with what the input method is
+    /// composing underlined at the caret, and moves the hidden textarea to the caret so the
+    /// input method's window opens there.
+    fn draw_text_edit(&self, camera: &Camera) {
+        let mut edit = self.text_edit.borrow_mut();
+        let document = self.document.borrow();
+        let Some((edit, shape)) = edit.as_mut().and_then(|edit| document.shape(edit.id).map(|shape| (edit, shape))) else {
+            return;
+        };
+        // the text may have been changed from elsewhere, e.g. by a plugin
+        edit.cursor.clamp(&shape.text);
+        let (shown, composed) = match &edit.composition {
+            Some(composition) => {
+                let (shown, range) = edit.cursor.preview(&shape.text, composition);
+                (shown, Some((range, composition.caret)))
+            }
+            None => (shape.text.clone(), None),
+        };
+        let layout = TextLayout::new(&self.context, &shown, shape.bounds, &shape.text_style);
+        let bounds = shape.bounds;
+        self.context.save();
+        neo_canvas::apply_transform(&self.context, shape.world_transform());
+        self.context.set_stroke_style_str("gray");
+        self.context.set_line_width(1.0 / camera.zoom);
+        self.context.stroke_rect(bounds.x, bounds.y, bounds.width, bounds.height);
+
+        let caret = match composed {
+            Some((range, caret)) => {
+                for rect in layout.highlight(range.clone()) {
+                    let y = rect.y + layout.ascent() + layout.size * 0.15;
+                    self.context.set_stroke_style_str("black");
+                    self.context.begin_path();
+                    self.context.move_to(rect.x, y);
+                    self.context.line_to(rect.x + rect.width, y);
+                    self.context.stroke();
+                }
+                range.start + caret
+            }
+            None => {
+                self.context.set_fill_style_str(SELECTION_COLOR);
+                for rect in layout.highlight(edit.cursor.range()) {
+                    self.context.fill_rect(rect.x, rect.y, rect.width, rect.height);
+                }
+                edit.cursor.caret
+            }
+        };
+        if layout.size * camera.zoom >= MIN_READABLE_TEXT {
+            self.draw_text(&layout);
+        }
+        let caret = layout.caret(caret);
+        self.context.set_stroke_style_str("black");
+        self.context.set_line_width(1.5 / camera.zoom);
+        self.context.begin_path();
+        self.context.move_to(caret.x, caret.y - layout.ascent());
+        self.context.line_to(caret.x, caret.y + layout.size * 0.2);
+        self.context.stroke();
+        self.context.restore();
+
+        let top_left = camera.world_to_screen(shape.world_transform().apply(caret - Point::new(0.0, layout.ascent())));
+        self.place_text_area(top_left, layout.line_height() * camera.zoom, layout.size * camera.zoom);
+    }
+
+    /// Puts the hidden textarea's top left corner at a position in canvas pixels, sized like a
+    /// line of the text being edited.
+    fn place_text_area(&self, top_left: Point, line_height: f64, font_size: f64) {
+        let Some(canvas) = self.context.canvas() else {
+            return;
+        };
+        let left = canvas.offset_left() as f64 + canvas.client_left() as f64 + top_left.x;
+        let top = canvas.offset_top() as f64 + canvas.client_top() as f64 + top_left.y;
+        let style = self.text_area.style();
+        let _ = style.set_property("left", &format!("{left}px"));
+        let _ = style.set_property("top", &format!("{top}px"));
+        let _ = style.set_property("height", &format!("{line_height}px"));
+        let _ = style.set_property("font-size", &format!("{font_size}px"));
     }
 }
 
+/// Whether every font the page asked for has finished loading.
+fn fonts_loaded() -> bool {
+    let document = web_sys::window().and_then(|window| window.document());
+    document.is_none_or(|document| document.fonts().status() == FontFaceSetLoadStatus::Loaded)
+}
+
 #[wasm_bindgen]
 pub struct WasmStateWrapper(Rc<State>);
 
@@ -72,7 +785,145 @@ impl WasmStateWrapper {
         self.0.get_user_action_mode()
     }
 
-    pub fn draw_line(&self) {
-        self.0.draw_line();
+    /// Colour of new strokes, with every channel between 0 and 1.
+    pub fn set_brush_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.0.update_brush(|brush| brush.color = Color::new(r, g, b, a));
     }
-}
\ No newline at end of file
+
+    /// Width at full pressure, in world units.
+    pub fn set_brush_width(&self, width: f64) {
+        self.0.update_brush(|brush| brush.width = width);
+    }
+
+    /// 0 ignores pen pressure, 1 lets a light touch thin the line right down.
+    pub fn set_brush_pressure_sensitivity(&self, sensitivity: f64) {
+        self.0.update_brush(|brush| brush.pressure_sensitivity = sensitivity);
+    }
+
+    /// 0 ignores pen tilt, 1 doubles the width with the pen lying flat.
+    pub fn set_brush_tilt_sensitivity(&self, sensitivity: f64) {
+        self.0.update_brush(|brush| brush.tilt_sensitivity = sensitivity);
+    }
+
+    /// Between 0 and 1; higher values steady a shaky hand at the cost of some lag.
+    pub fn set_brush_stabilizer(&self, stabilizer: f64) {
+        self.0.update_brush(|brush| brush.stabilizer = stabilizer);
+    }
+
+    /// Screen pixels of detail dropped when a stroke is committed; 0 keeps every sample.
+    pub fn set_brush_simplify_tolerance(&self, tolerance: f64) {
+        self.0.update_brush(|brush| brush.simplify_tolerance = tolerance);
+    }
+
+    /// Whether strokes are drawn as smooth curves rather than straight segments.
+    pub fn set_brush_smooth(&self, smooth: bool) {
+        self.0.update_brush(|brush| brush.smooth = smooth);
+    }
+
+    /// How the text of new text boxes is set: font families in fallback order, the size in
+    /// world units, a weight from 100 to 900, and the slant.
+    pub fn set_text_style(&self, families: Vec<String>, size: f64, weight: u16, italic: bool) {
+        self.0.set_text_style(TextStyle { families, size, weight, italic });
+    }
+
+    /// Ids of the objects touching a rectangle on the canvas, in canvas pixels, to pass to the
+    /// arranging commands.
+    pub fn objects_in(&self, x: f64, y: f64, width: f64, height: f64) -> Vec<String> {
+        self.0.objects_in(Rect::new(x, y, width, height)).into_iter().map(String::from).collect()
+    }
+
+    /// Lines the objects up on the left, right, top or bottom edge or a centre line of their
+    /// bounds together. Throws if an id isn't valid.
+    pub fn align(&self, ids: Vec<String>, alignment: Alignment) -> Result<(), JsValue> {
+        let ids = parse_ids(ids)?;
+        self.0.arrange(&ids, |document, history, ids| arrange::align(document, history, ids, alignment.into()));
+        Ok(())
+    }
+
+    /// Spaces the objects out with equal gaps, across the canvas or down it when `vertical`,
+    /// keeping the outermost two where they are.
+    pub fn distribute(&self, ids: Vec<String>, vertical: bool) -> Result<(), JsValue> {
+        let ids = parse_ids(ids)?;
+        let axis = if vertical { arrange::Axis::Vertical } else { arrange::Axis::Horizontal };
+        self.0.arrange(&ids, |document, history, ids| arrange::distribute(document, history, ids, axis));
+        Ok(())
+    }
+
+    /// Arranges the objects in a grid, in reading order.
+    pub fn tidy(&self, ids: Vec<String>) -> Result<(), JsValue> {
+        let ids = parse_ids(ids)?;
+        self.0.arrange(&ids, |document, history, ids| arrange::tidy(document, history, ids, TIDY_GAP));
+        Ok(())
+    }
+
+    /// Gathers the objects into a group, returning its id, or `undefined` when fewer than two
+    /// are given. Throws if an id isn't valid.
+    pub fn group(&self, ids: Vec<String>) -> Result<Option<String>, JsValue> {
+        let ids = parse_ids(ids)?;
+        let mut grouped = None;
+        self.0.arrange(&ids, |document, history, ids| {
+            grouped = group::group(document, history, ids);
+            grouped.is_some()
+        });
+        Ok(grouped.map(String::from))
+    }
+
+    /// Dissolves the groups among the objects, returning the ids of what they held.
+    pub fn ungroup(&self, ids: Vec<String>) -> Result<Vec<String>, JsValue> {
+        let ids = parse_ids(ids)?;
+        let mut children = Vec::new();
+        self.0.arrange(&ids, |document, history, ids| {
+            let mut changed = false;
+            for &id in ids {
+                changed |= document.group(id).is_some();
+                children.extend(group::ungroup(document, history, id));
+            }
+            changed
+        });
+        Ok(children.into_iter().map(String::from).collect())
+    }
+
+    /// Brings the objects forward or to the front, or sends them backward or to the back.
+    pub fn reorder(&self, ids: Vec<String>, reorder: Reorder) -> Result<(), JsValue> {
+        let ids = parse_ids(ids)?;
+        self.0.arrange(&ids, |document, history, ids| order::reorder(document, history, ids, reorder.into()));
+        Ok(())
+    }
+
+    /// `true` cuts strokes where the eraser passes, `false` deletes every stroke it touches.
+    pub fn set_eraser_partial(&self, partial: bool) {
+        let mode = if partial { EraseMode::Partial } else { EraseMode::Stroke };
+        self.0.update_eraser(|eraser| eraser.mode = mode);
+    }
+
+    /// Eraser radius in screen pixels, kept between 1 and 200. Anything but a number is ignored.
+    pub fn set_eraser_size(&self, radius: f64) {
+        if radius.is_finite() {
+            self.0.update_eraser(|eraser| eraser.radius = radius.clamp(*ERASER_SIZES.start(), *ERASER_SIZES.end()));
+        }
+    }
+
+    /// The drawing as the contents of a `.neo` file.
+    pub fn save(&self, include_history: bool) -> String {
+        self.0.save(include_history)
+    }
+
+    pub fn undo(&self) {
+        self.0.undo();
+    }
+
+    pub fn redo(&self) {
+        self.0.redo();
+    }
+
+    /// Opens the contents of a `.neo` file, throwing a readable message if it can't be read.
+    pub fn load(&self, contents: &str) -> Result<(), JsValue> {
+        self.0.load(contents).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+fn parse_ids(ids: Vec<String>) -> Result<Vec<ObjectId>, JsValue> {
+    ids.into_iter()
+        .map(|id| id.parse().map_err(|e: neo_scene::ParseObjectIdError| JsValue::from_str(&e.to_string())))
+        .collect()
+}
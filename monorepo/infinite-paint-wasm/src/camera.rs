@@ -0,0 +1,84 @@
+use neo_scene::{Point, Rect, Transform};
+
+pub const MIN_ZOOM: f64 = 0.1;
+pub const MAX_ZOOM: f64 = 8.0;
+/// Zoom change per pixel of wheel movement; a typical wheel notch is 100 pixels.
+const ZOOM_SPEED: f64 = 0.002;
+
+/// The browser counterpart of the native engine's camera: `position` is the world point shown
+/// at the centre of the canvas.
+#[derive(Clone, Copy)]
+pub struct Camera {
+    pub position: Point,
+    pub zoom: f64,
+    /// Canvas size in pixels.
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Camera {
+    pub fn new(width: f64, height: f64) -> Self {
+        Camera {
+            position: Point::ZERO,
+            zoom: 1.0,
+            width,
+            height,
+        }
+    }
+
+    fn screen_center(&self) -> Point {
+        Point::new(self.width, self.height) * 0.5
+    }
+
+    pub fn world_to_screen(&self, world_pos: Point) -> Point {
+        (world_pos - self.position) * self.zoom + self.screen_center()
+    }
+
+    pub fn screen_to_world(&self, screen_pos: Point) -> Point {
+        (screen_pos - self.screen_center()) / self.zoom + self.position
+    }
+
+    /// Looks at `position` at `zoom`, as read from a file or set by a plugin. The zoom is kept
+    /// between [`MIN_ZOOM`] and [`MAX_ZOOM`], and a value that isn't a finite number is ignored,
+    /// so a damaged one can't leave the canvas unusable.
+    pub fn set_view(&mut self, position: Point, zoom: f64) {
+        if position.x.is_finite() && position.y.is_finite() {
+            self.position = position;
+        }
+        if zoom.is_finite() {
+            self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        }
+    }
+
+    /// The world rectangle currently on the canvas.
+    pub fn visible_world_bounds(&self) -> Rect {
+        Rect::from_corners(
+            self.screen_to_world(Point::ZERO),
+            self.screen_to_world(Point::new(self.width, self.height)),
+        )
+    }
+
+    /// Moves the view by a distance in canvas pixels, as when dragging the canvas by hand.
+    pub fn pan(&mut self, screen_delta: Point) {
+        self.position -= screen_delta / self.zoom;
+    }
+
+    /// Zooms for a wheel movement of `delta` pixels, keeping the world point under `anchor`
+    /// (in canvas pixels) in place.
+    pub fn zoom_at(&mut self, anchor: Point, delta: f64) {
+        self.zoom_by(anchor, (-delta * ZOOM_SPEED).exp());
+    }
+
+    /// Multiplies the zoom by `factor`, keeping the world point under `anchor` in place.
+    pub fn zoom_by(&mut self, anchor: Point, factor: f64) {
+        let before = self.screen_to_world(anchor);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let after = self.screen_to_world(anchor);
+        self.position += before - after;
+    }
+
+    /// Maps world coordinates to canvas pixels, for the canvas `setTransform`.
+    pub fn transform(&self) -> Transform {
+        Transform::scaling(self.zoom, self.zoom).then(Transform::translation(self.world_to_screen(Point::ZERO)))
+    }
+}
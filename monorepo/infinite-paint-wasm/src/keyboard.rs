@@ -0,0 +1,76 @@
+use std::rc::Rc;
+
+use neo_scene::plugin::Input;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use web_sys::KeyboardEvent;
+
+use crate::appstate::{State, UserActionMode};
+
+// Event Handlers for keyboard shortcuts
+
+fn create_keydown_handler(state: Rc<State>) -> Closure<dyn FnMut(KeyboardEvent)> {
+    Closure::wrap(Box::new(move |event: KeyboardEvent| {
+        state.update_input(|input| {
+            record_modifiers(input, &event);
+            input.keys_pressed.push(plugin_key_name(&event.key()));
+        });
+        // Cmd on macOS, Ctrl everywhere else
+        if !(event.ctrl_key() || event.meta_key()) {
+            // letters typed into a shape aren't shortcuts
+            if state.is_editing_text() {
+                return;
+            }
+            match event.key().as_str() {
+                "h" => state.set_user_action_mode(UserActionMode::Drag),
+                "p" => state.set_user_action_mode(UserActionMode::Draw),
+                "e" => state.set_user_action_mode(UserActionMode::Erase),
+                "t" => state.set_user_action_mode(UserActionMode::Text),
+                _ => {}
+            }
+            return;
+        }
+        match event.key().to_lowercase().as_str() {
+            "z" if event.shift_key() => state.redo(),
+            "z" => state.undo(),
+            "y" => state.redo(),
+            _ => return,
+        }
+        event.prevent_default();
+    }) as Box<dyn FnMut(KeyboardEvent)>)
+}
+
+fn create_keyup_handler(state: Rc<State>) -> Closure<dyn FnMut(KeyboardEvent)> {
+    Closure::wrap(Box::new(move |event: KeyboardEvent| {
+        state.update_input(|input| record_modifiers(input, &event));
+    }) as Box<dyn FnMut(KeyboardEvent)>)
+}
+
+fn record_modifiers(input: &mut Input, event: &KeyboardEvent) {
+    input.shift = event.shift_key();
+    input.ctrl = event.ctrl_key() || event.meta_key();
+    input.alt = event.alt_key();
+}
+
+/// Plugins see letters in lowercase whatever the Shift state, as they do natively.
+fn plugin_key_name(key: &str) -> String {
+    if key.chars().count() == 1 {
+        key.to_lowercase()
+    } else {
+        key.to_owned()
+    }
+}
+
+/// Shortcuts are registered on the window since the canvas never has keyboard focus.
+pub fn add_keyboard_event_listeners(state: Rc<State>) -> Result<(), JsValue> {
+    let window = web_sys::window().unwrap();
+    let keydown_handler = create_keydown_handler(state.clone());
+    let keyup_handler = create_keyup_handler(state);
+
+    window.add_event_listener_with_callback("keydown", keydown_handler.as_ref().unchecked_ref())?;
+    window.add_event_listener_with_callback("keyup", keyup_handler.as_ref().unchecked_ref())?;
+
+    keydown_handler.forget();
+    keyup_handler.forget();
+
+    Ok(())
+}
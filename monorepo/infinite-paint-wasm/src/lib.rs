@@ -1,7 +1,6 @@
 mod utils;
 mod canvas;
 
-use mouse::add_mouse_event_listeners;
 use wasm_bindgen::prelude::*;
 use web_sys::{window, CanvasRenderingContext2d};
 use std::cell::RefCell; 
@@ -9,7 +8,15 @@ use std::rc::Rc;
 
 mod appstate;
 use appstate::State;
-mod mouse;
+mod camera;
+mod plugins;
+mod pointer;
+use pointer::add_pointer_event_listeners;
+mod keyboard;
+use keyboard::add_keyboard_event_listeners;
+mod text;
+mod text_input;
+use text_input::add_text_input_listeners;
 
 // TODO: Remove this comment
 #[wasm_bindgen(start)]
@@ -30,8 +37,10 @@ fn start() -> Result<(), JsValue> {
 
     let state = Rc::new(State::new(context.into()));
 
-    // adding the mouse event handlers (clousures)
-    add_mouse_event_listeners(&canvas, state.clone())?;
+    // adding the pointer event handlers (clousures)
+    add_pointer_event_listeners(&canvas, state.clone())?;
+    add_keyboard_event_listeners(state.clone())?;
+    add_text_input_listeners(state.clone())?;
     let _ = start_animation_loop(state.clone());
 
     Ok(())
@@ -45,9 +54,7 @@ pub fn start_animation_loop(state: Rc<State>) -> Result<(), JsValue> {
 
     // DON'T import borrow, it will cause errors, we want RefCell's borrow here
     *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
-        if state.mouse_info().is_pressed() {
-            state.draw_line();
-        }
+        state.render();
 
         request_animation_frame(f.borrow().as_ref().unwrap());
     }) as Box<dyn FnMut()>));
@@ -0,0 +1,51 @@
+use neo_scene::{plugin::Draw, Color, Point, Rect, Style};
+use web_sys::CanvasRenderingContext2d;
+
+/// Plugin drawing on the 2D canvas, in canvas pixels.
+pub(crate) struct CanvasDraw<'a>(pub &'a CanvasRenderingContext2d);
+
+impl CanvasDraw<'_> {
+    /// Fills and outlines the current path.
+    fn paint(&self, style: Style) {
+        if style.fill.a > 0.0 {
+            self.0.set_fill_style_str(&style.fill.to_css());
+            self.0.fill();
+        }
+        if style.stroke_width > 0.0 {
+            self.0.set_stroke_style_str(&style.stroke.to_css());
+            self.0.set_line_width(style.stroke_width);
+            self.0.stroke();
+        }
+    }
+}
+
+impl Draw for CanvasDraw<'_> {
+    fn line(&mut self, from: Point, to: Point, width: f64, color: Color) {
+        self.0.set_stroke_style_str(&color.to_css());
+        self.0.set_line_width(width);
+        self.0.begin_path();
+        self.0.move_to(from.x, from.y);
+        self.0.line_to(to.x, to.y);
+        self.0.stroke();
+    }
+
+    fn rect(&mut self, rect: Rect, style: Style) {
+        let (min, size) = (rect.min(), rect.size());
+        self.0.begin_path();
+        self.0.rect(min.x, min.y, size.x, size.y);
+        self.paint(style);
+    }
+
+    fn ellipse(&mut self, bounds: Rect, style: Style) {
+        let (center, radii) = (bounds.center(), bounds.size() * 0.5);
+        self.0.begin_path();
+        let _ = self.0.ellipse(center.x, center.y, radii.x, radii.y, 0.0, 0.0, std::f64::consts::TAU);
+        self.paint(style);
+    }
+
+    fn text(&mut self, text: &str, position: Point, size: f64, color: Color) {
+        self.0.set_font(&format!("{size}px sans-serif"));
+        self.0.set_fill_style_str(&color.to_css());
+        let _ = self.0.fill_text(text, position.x, position.y);
+    }
+}
@@ -0,0 +1,266 @@
+use std::{cell::{Cell, RefCell}, rc::Rc};
+
+use neo_scene::Point;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use web_sys::{HtmlCanvasElement, PointerEvent, WheelEvent};
+
+use crate::appstate::{State, UserActionMode};
+
+/// What the pointers currently on the canvas are doing.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Gesture {
+    Idle,
+    /// A single pointer drawing a stroke; every other pointer is ignored, so two fingers never
+    /// feed the same stroke.
+    Stroke(i32),
+    /// A single pointer dragging the view in Drag mode.
+    Pan(i32),
+    /// A single pointer erasing in Erase mode.
+    Erase(i32),
+    /// A single pointer placing the caret or selecting text in Text mode.
+    Text(i32),
+    /// Two pointers panning and pinch-zooming. Lasts until every pointer has lifted, so the
+    /// finger left behind after a pinch doesn't start drawing.
+    Pinch,
+}
+
+/// Pointers (mouse, pen or fingers) currently pressed on the canvas.
+pub(crate) struct Pointers {
+    /// Pointer id and canvas position, in the order they touched down.
+    active: RefCell<Vec<(i32, Point)>>,
+    gesture: Cell<Gesture>,
+}
+
+impl Pointers {
+    pub fn new() -> Pointers {
+        Pointers {
+            active: RefCell::new(Vec::new()),
+            gesture: Cell::new(Gesture::Idle),
+        }
+    }
+
+    pub fn gesture(&self) -> Gesture {
+        self.gesture.get()
+    }
+
+    fn press(&self, id: i32, position: Point) -> usize {
+        let mut active = self.active.borrow_mut();
+        active.retain(|(other, _)| *other != id);
+        active.push((id, position));
+        active.len()
+    }
+
+    /// Moves a pointer, returning the first two pointers' positions before and after the move.
+    fn move_to(&self, id: i32, position: Point) -> Option<((Point, Point), (Point, Point))> {
+        let mut active = self.active.borrow_mut();
+        let before = match active.as_slice() {
+            [(_, a), (_, b), ..] => Some((*a, *b)),
+            _ => None,
+        };
+        if let Some(entry) = active.iter_mut().find(|(other, _)| *other == id) {
+            entry.1 = position;
+        }
+        match active.as_slice() {
+            [(_, a), (_, b), ..] => Some((before?, (*a, *b))),
+            _ => None,
+        }
+    }
+
+    /// Forgets a pointer, returning how many are still down.
+    fn release(&self, id: i32) -> usize {
+        let mut active = self.active.borrow_mut();
+        active.retain(|(other, _)| *other != id);
+        active.len()
+    }
+}
+
+fn position(event: &PointerEvent) -> Point {
+    Point::new(event.offset_x() as f64, event.offset_y() as f64)
+}
+
+/// Pen pressure between 0 and 1. Mice report a flat 0.5 while pressed, so they count as full
+/// pressure instead.
+fn pressure(event: &PointerEvent) -> f64 {
+    match event.pointer_type().as_str() {
+        "mouse" => 1.0,
+        _ => event.pressure() as f64,
+    }
+}
+
+/// Angle of the pen away from upright, in radians, from the two tilt angles the browser
+/// reports in degrees.
+fn tilt(event: &PointerEvent) -> f64 {
+    let x = (event.tilt_x() as f64).to_radians().tan();
+    let y = (event.tilt_y() as f64).to_radians().tan();
+    x.hypot(y).atan()
+}
+
+// Event Handlers for pointer actions
+
+fn create_pointerdown_handler(state: Rc<State>, canvas: HtmlCanvasElement) -> Closure<dyn FnMut(PointerEvent)> {
+    Closure::wrap(Box::new(move |event: PointerEvent| {
+        // only the primary button (or a pen/finger contact) draws and drags
+        if event.button() != 0 {
+            return;
+        }
+        event.prevent_default();
+        if event.is_primary() {
+            state.update_input(|input| {
+                input.pointer = Some(position(&event));
+                input.primary_down = true;
+                input.primary_pressed = true;
+            });
+        }
+        // keep receiving this pointer's events even once it leaves the canvas
+        let id = event.pointer_id();
+        let _ = canvas.set_pointer_capture(id);
+
+        let pointers = state.pointers();
+        let count = pointers.press(id, position(&event));
+        match (pointers.gesture(), count) {
+            (Gesture::Idle, 1) => {
+                let gesture = match state.get_user_action_mode() {
+                    UserActionMode::Draw => {
+                        state.add_stroke_point(position(&event), pressure(&event), tilt(&event));
+                        Gesture::Stroke(id)
+                    }
+                    UserActionMode::Drag => Gesture::Pan(id),
+                    UserActionMode::Erase => {
+                        state.erase_to(position(&event));
+                        Gesture::Erase(id)
+                    }
+                    UserActionMode::Text => {
+                        state.press_text(position(&event), event.shift_key());
+                        Gesture::Text(id)
+                    }
+                };
+                pointers.gesture.set(gesture);
+            }
+            (Gesture::Stroke(_) | Gesture::Pan(_) | Gesture::Erase(_) | Gesture::Text(_), 2) => {
+                // a second finger turns the touch into a pinch; what the first one drew so far
+                // was the start of that gesture, not a stroke. Anything already erased stays
+                // erased, as its own undo step.
+                state.cancel_stroke();
+                state.end_erase();
+                pointers.gesture.set(Gesture::Pinch);
+            }
+            _ => {}
+        }
+    }) as Box<dyn FnMut(PointerEvent)>)
+}
+
+fn create_pointermove_handler(state: Rc<State>) -> Closure<dyn FnMut(PointerEvent)> {
+    Closure::wrap(Box::new(move |event: PointerEvent| {
+        if event.is_primary() {
+            state.update_input(|input| input.pointer = Some(position(&event)));
+        }
+        let id = event.pointer_id();
+        let pointers = state.pointers();
+        let last = pointers.active.borrow().iter().find(|(other, _)| *other == id).map(|(_, p)| *p);
+        let Some(last) = last else {
+            // hovering, nothing pressed
+            return;
+        };
+        let current = position(&event);
+        let pair = pointers.move_to(id, current);
+        match pointers.gesture() {
+            Gesture::Stroke(owner) if owner == id => {
+                state.add_stroke_point(current, pressure(&event), tilt(&event))
+            }
+            Gesture::Pan(owner) if owner == id => state.drag(current - last),
+            Gesture::Erase(owner) if owner == id => state.erase_to(current),
+            Gesture::Text(owner) if owner == id => state.select_text_to(current),
+            Gesture::Pinch => {
+                if let Some(((a0, b0), (a1, b1))) = pair.filter(|((a0, b0), _)| a0 != b0) {
+                    state.pinch((a0 + b0) * 0.5, (a1 + b1) * 0.5, a1.distance(b1) / a0.distance(b0));
+                }
+            }
+            _ => {}
+        }
+    }) as Box<dyn FnMut(PointerEvent)>)
+}
+
+/// Shared by `pointerup`, `pointercancel` and `lostpointercapture`, so a pointer is always
+/// released, even when the button comes up outside the canvas or the browser takes it over.
+fn create_pointerup_handler(state: Rc<State>) -> Closure<dyn FnMut(PointerEvent)> {
+    Closure::wrap(Box::new(move |event: PointerEvent| {
+        if event.is_primary() {
+            // the same release can arrive as both `pointerup` and `lostpointercapture`
+            state.update_input(|input| {
+                input.primary_released |= input.primary_down;
+                input.primary_down = false;
+            });
+        }
+        let id = event.pointer_id();
+        let pointers = state.pointers();
+        let remaining = pointers.release(id);
+        match pointers.gesture() {
+            Gesture::Stroke(owner) if owner == id => {
+                state.end_stroke();
+                pointers.gesture.set(Gesture::Idle);
+            }
+            Gesture::Pan(owner) | Gesture::Text(owner) if owner == id => pointers.gesture.set(Gesture::Idle),
+            Gesture::Erase(owner) if owner == id => {
+                state.end_erase();
+                pointers.gesture.set(Gesture::Idle);
+            }
+            Gesture::Pinch if remaining == 0 => pointers.gesture.set(Gesture::Idle),
+            _ => {}
+        }
+    }) as Box<dyn FnMut(PointerEvent)>)
+}
+
+fn create_pointerleave_handler(state: Rc<State>) -> Closure<dyn FnMut(PointerEvent)> {
+    Closure::wrap(Box::new(move |event: PointerEvent| {
+        if event.is_primary() {
+            state.update_input(|input| input.pointer = None);
+        }
+    }) as Box<dyn FnMut(PointerEvent)>)
+}
+
+fn create_wheel_handler(state: Rc<State>) -> Closure<dyn FnMut(WheelEvent)> {
+    Closure::wrap(Box::new(move |event: WheelEvent| {
+        // keep the page from scrolling or zooming along with the canvas
+        event.prevent_default();
+        let mode = event.delta_mode();
+        let delta = Point::new(
+            state.wheel_delta_to_pixels(event.delta_x(), mode),
+            state.wheel_delta_to_pixels(event.delta_y(), mode),
+        );
+        state.update_input(|input| input.wheel += delta);
+        state.wheel(
+            event.offset_x() as f64,
+            event.offset_y() as f64,
+            delta.x,
+            delta.y,
+            event.ctrl_key() || event.meta_key(),
+        );
+    }) as Box<dyn FnMut(WheelEvent)>)
+}
+
+pub fn add_pointer_event_listeners(canvas: &HtmlCanvasElement, state: Rc<State>) -> Result<(), JsValue> {
+    // touches go to us instead of scrolling or zooming the page
+    canvas.style().set_property("touch-action", "none")?;
+
+    let pointerdown_handler = create_pointerdown_handler(state.clone(), canvas.clone());
+    let pointermove_handler = create_pointermove_handler(state.clone());
+    let pointerup_handler = create_pointerup_handler(state.clone());
+    let pointerleave_handler = create_pointerleave_handler(state.clone());
+    let wheel_handler = create_wheel_handler(state.clone());
+
+    canvas.add_event_listener_with_callback("pointerdown", pointerdown_handler.as_ref().unchecked_ref())?;
+    canvas.add_event_listener_with_callback("pointermove", pointermove_handler.as_ref().unchecked_ref())?;
+    canvas.add_event_listener_with_callback("pointerup", pointerup_handler.as_ref().unchecked_ref())?;
+    canvas.add_event_listener_with_callback("pointercancel", pointerup_handler.as_ref().unchecked_ref())?;
+    canvas.add_event_listener_with_callback("lostpointercapture", pointerup_handler.as_ref().unchecked_ref())?;
+    canvas.add_event_listener_with_callback("pointerleave", pointerleave_handler.as_ref().unchecked_ref())?;
+    canvas.add_event_listener_with_callback("wheel", wheel_handler.as_ref().unchecked_ref())?;
+
+    pointerdown_handler.forget();
+    pointermove_handler.forget();
+    pointerup_handler.forget();
+    pointerleave_handler.forget();
+    wheel_handler.forget();
+
+    Ok(())
+}
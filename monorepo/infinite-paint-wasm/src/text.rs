@@ -0,0 +1,169 @@
+use std::ops::Range;
+
+use neo_scene::font::{EmbeddedFont, TextStyle};
+use neo_scene::text::{self, Composition, Line, TextCursor};
+use neo_scene::{Document, ObjectId, Point, Rect};
+use web_sys::{CanvasRenderingContext2d, FontFace, FontFaceDescriptors};
+
+/// Gap between a shape's edge and its text, in world units.
+const TEXT_PADDING: f64 = 10.0;
+const LINE_SPACING: f64 = 1.25;
+/// Part of the font size above the baseline, for carets and selection highlights.
+const ASCENT: f64 = 0.8;
+/// Size of a new text box in world units.
+pub const TEXT_BOX_WIDTH: f64 = 200.0;
+pub const TEXT_BOX_HEIGHT: f64 = 40.0;
+
+/// The shape being typed into in Text mode.
+pub(crate) struct TextEdit {
+    pub id: ObjectId,
+    /// The box was made for this edit and is thrown away if left empty.
+    pub created: bool,
+    pub cursor: TextCursor,
+    /// What the input method is composing at the caret, if anything.
+    pub composition: Option<Composition>,
+    /// World x the caret keeps to while moving up and down across shorter lines.
+    pub goal_x: Option<f64>,
+}
+
+impl TextEdit {
+    pub fn new(id: ObjectId, created: bool) -> Self {
+        TextEdit { id, created, cursor: TextCursor::default(), composition: None, goal_x: None }
+    }
+
+    /// Changes the text through the cursor; the document keeps its index up to date.
+    pub fn apply(&mut self, document: &mut Document, edit: impl FnOnce(&mut TextCursor, &mut String)) {
+        if let Some(mut shape) = document.shape_mut(self.id) {
+            edit(&mut self.cursor, &mut shape.text);
+        }
+        self.goal_x = None;
+    }
+}
+
+/// Shape text broken into lines and placed in the world, measured with the canvas font it is
+/// drawn with. The browser falls back to other fonts for missing glyphs the same way whether
+/// measuring or drawing.
+pub(crate) struct TextLayout<'a> {
+    context: &'a CanvasRenderingContext2d,
+    pub text: &'a str,
+    pub lines: Vec<Line>,
+    /// World position of the first line's baseline, at its left end.
+    pub origin: Point,
+    /// Font size in world units.
+    pub size: f64,
+}
+
+impl<'a> TextLayout<'a> {
+    /// Lays `text` out in `style` inside `bounds`. Sets the canvas font, so drawing right after
+    /// matches.
+    pub fn new(context: &'a CanvasRenderingContext2d, text: &'a str, bounds: Rect, style: &TextStyle) -> Self {
+        // the canvas is scaled by the camera, so the font is sized in world units
+        context.set_font(&style.to_css());
+        let max_width = (bounds.width - 2.0 * TEXT_PADDING) as f32;
+        TextLayout {
+            context,
+            text,
+            lines: text::wrap(text, max_width, |s| measure(context, s) as f32),
+            origin: bounds.min() + Point::new(TEXT_PADDING, TEXT_PADDING + style.size),
+            size: style.size,
+        }
+    }
+
+    pub fn line_height(&self) -> f64 {
+        self.size * LINE_SPACING
+    }
+
+    /// Height above the baseline of the caret and of selection highlights.
+    pub fn ascent(&self) -> f64 {
+        self.size * ASCENT
+    }
+
+    pub fn line_text(&self, row: usize) -> &'a str {
+        &self.text[self.lines[row].range.clone()]
+    }
+
+    fn width(&self, range: Range<usize>) -> f64 {
+        measure(self.context, &self.text[range])
+    }
+
+    /// World position of the baseline at a caret offset.
+    pub fn caret(&self, offset: usize) -> Point {
+        let row = text::line_at(&self.lines, offset);
+        let start = self.lines[row].range.start;
+        self.origin + Point::new(self.width(start..offset), row as f64 * self.line_height())
+    }
+
+    /// The caret offset closest to a world position.
+    pub fn offset_at(&self, point: Point) -> usize {
+        let top = self.origin.y - self.ascent();
+        let row = ((point.y - top) / self.line_height()).floor().clamp(0.0, (self.lines.len() - 1) as f64) as usize;
+        self.offset_in_row(row, point.x)
+    }
+
+    fn offset_in_row(&self, row: usize, x: f64) -> usize {
+        let x = (x - self.origin.x) as f32;
+        text::offset_at_x(self.text, &self.lines, row, x, |s| measure(self.context, s) as f32)
+    }
+
+    /// The offset `rows` lines above or below `offset`, as close to world x `goal_x` as the
+    /// line allows. Past the first or last line it goes to the start or end of the text.
+    pub fn vertical(&self, offset: usize, rows: isize, goal_x: f64) -> usize {
+        let row = text::line_at(&self.lines, offset) as isize + rows;
+        if row < 0 {
+            0
+        } else if row as usize >= self.lines.len() {
+            self.text.len()
+        } else {
+            self.offset_in_row(row as usize, goal_x)
+        }
+    }
+
+    /// Where the line holding `offset` starts and where its caret can go last, for Home and End.
+    pub fn line_bounds(&self, offset: usize) -> (usize, usize) {
+        let row = text::line_at(&self.lines, offset);
+        (self.lines[row].range.start, text::line_end(self.text, &self.lines, row))
+    }
+
+    /// World rectangles covering `range` line by line, from the top of the text to just below
+    /// the baseline.
+    pub fn highlight(&self, range: Range<usize>) -> Vec<Rect> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.range.start <= range.end && range.start <= line.range.end)
+            .filter_map(|(row, line)| {
+                let start = range.start.max(line.range.start);
+                let end = range.end.min(line.range.end);
+                let left = self.width(line.range.start..start);
+                // a selected newline shows as a sliver, so selected blank lines are visible
+                let mut width = self.width(start..end);
+                if range.end > line.range.end && self.lines.get(row + 1).is_some_and(|next| next.range.start > line.range.end) {
+                    width += self.size * 0.25;
+                }
+                let top = self.origin.y + row as f64 * self.line_height() - self.ascent();
+                (width > 0.0).then(|| Rect::new(self.origin.x + left, top, width, self.line_height()))
+            })
+            .collect()
+    }
+}
+
+fn measure(context: &CanvasRenderingContext2d, text: &str) -> f64 {
+    context.measure_text(text).map_or(0.0, |metrics| metrics.width())
+}
+
+/// Makes the fonts saved with a document available to the canvas under their family names.
+/// They finish loading in the background, after which text using them has to be drawn again.
+pub fn register_fonts(fonts: &[EmbeddedFont]) {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+    let set = document.fonts();
+    for font in fonts {
+        let descriptors = FontFaceDescriptors::new();
+        descriptors.set_weight(&font.weight.to_string());
+        descriptors.set_style(if font.italic { "italic" } else { "normal" });
+        if let Ok(face) = FontFace::new_with_u8_array_and_descriptors(&font.family, &font.data, &descriptors) {
+            let _ = set.add(&face);
+        }
+    }
+}
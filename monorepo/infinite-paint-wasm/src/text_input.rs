@@ -0,0 +1,156 @@
+//! Typing into shapes goes through a hidden `<textarea>`. Browsers only run input methods for a
+//! focused editable element, so this is what receives the composition events of Chinese,
+//! Japanese and Korean input methods and of dead keys. Kept at the caret, it also makes the
+//! input method's candidate window open next to the text being typed. The textarea itself stays
+//! empty: whatever is typed or composed into it is moved into the shape and drawn on the canvas.
+
+use std::rc::Rc;
+
+use neo_scene::text::Composition;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use web_sys::{ClipboardEvent, Document, Event, HtmlTextAreaElement, KeyboardEvent};
+
+use crate::appstate::State;
+
+/// Makes the textarea, invisible but still focusable, and adds it to the page.
+pub fn create_text_area(document: &Document) -> Result<HtmlTextAreaElement, JsValue> {
+    let text_area = document.create_element("textarea")?.dyn_into::<HtmlTextAreaElement>()?;
+    let style = text_area.style();
+    // `display: none` or `visibility: hidden` would keep it from being focused
+    for (property, value) in [
+        ("position", "absolute"),
+        ("opacity", "0"),
+        ("width", "1px"),
+        ("padding", "0"),
+        ("border", "0"),
+        ("resize", "none"),
+        ("overflow", "hidden"),
+        ("pointer-events", "none"),
+    ] {
+        style.set_property(property, value)?;
+    }
+    text_area.set_attribute("autocomplete", "off")?;
+    text_area.set_attribute("autocapitalize", "off")?;
+    text_area.set_attribute("spellcheck", "false")?;
+    document.body().unwrap().append_child(&text_area)?;
+    Ok(text_area)
+}
+
+/// Byte offset in `text` of an offset the DOM gives in UTF-16 code units.
+fn utf16_to_byte(text: &str, units: u32) -> usize {
+    let mut count = 0;
+    for (i, c) in text.char_indices() {
+        if count >= units as usize {
+            return i;
+        }
+        count += c.len_utf16();
+    }
+    text.len()
+}
+
+// Event Handlers for the textarea
+
+fn create_input_handler(state: Rc<State>, text_area: HtmlTextAreaElement) -> Closure<dyn FnMut(Event)> {
+    Closure::wrap(Box::new(move |_event: Event| {
+        let value = text_area.value();
+        if state.is_composing() {
+            // while composing, the textarea holds just the composition
+            let caret = match text_area.selection_end() {
+                Ok(Some(units)) => utf16_to_byte(&value, units),
+                _ => value.len(),
+            };
+            state.compose_text(Some(Composition { text: value, caret }));
+        } else if !value.is_empty() {
+            text_area.set_value("");
+            state.type_text(&value.replace("\r\n", "\n"));
+        }
+    }) as Box<dyn FnMut(Event)>)
+}
+
+fn create_compositionstart_handler(state: Rc<State>) -> Closure<dyn FnMut(Event)> {
+    Closure::wrap(Box::new(move |_event: Event| {
+        state.compose_text(Some(Composition::default()));
+    }) as Box<dyn FnMut(Event)>)
+}
+
+/// Commits the composition. Browsers disagree on whether an `input` event follows, so the
+/// textarea is emptied here and that event finds nothing left to type.
+fn create_compositionend_handler(state: Rc<State>, text_area: HtmlTextAreaElement) -> Closure<dyn FnMut(Event)> {
+    Closure::wrap(Box::new(move |_event: Event| {
+        let value = text_area.value();
+        text_area.set_value("");
+        state.compose_text(None);
+        state.type_text(&value);
+    }) as Box<dyn FnMut(Event)>)
+}
+
+/// Editing keys. Handled keys stop here, so typing never reaches the shortcuts on the window.
+fn create_keydown_handler(state: Rc<State>) -> Closure<dyn FnMut(KeyboardEvent)> {
+    Closure::wrap(Box::new(move |event: KeyboardEvent| {
+        // keys pressed while composing belong to the input method
+        if event.is_composing() || state.is_composing() || event.key() == "Process" {
+            return;
+        }
+        // Cmd on macOS, Ctrl everywhere else; words are Alt on macOS and Ctrl elsewhere
+        let command = event.ctrl_key() || event.meta_key();
+        let word = event.ctrl_key() || event.alt_key();
+        if state.text_key(&event.key(), event.shift_key(), command, word) {
+            event.prevent_default();
+            event.stop_propagation();
+        }
+    }) as Box<dyn FnMut(KeyboardEvent)>)
+}
+
+/// Shared by `copy` and `cut`; the textarea is empty, so the selection has to be put on the
+/// clipboard by hand.
+fn create_copy_handler(state: Rc<State>, cut: bool) -> Closure<dyn FnMut(ClipboardEvent)> {
+    Closure::wrap(Box::new(move |event: ClipboardEvent| {
+        let (Some(selected), Some(clipboard)) = (state.selected_text(), event.clipboard_data()) else {
+            return;
+        };
+        if clipboard.set_data("text/plain", &selected).is_ok() {
+            event.prevent_default();
+            if cut {
+                state.type_text("");
+            }
+        }
+    }) as Box<dyn FnMut(ClipboardEvent)>)
+}
+
+fn create_paste_handler(state: Rc<State>) -> Closure<dyn FnMut(ClipboardEvent)> {
+    Closure::wrap(Box::new(move |event: ClipboardEvent| {
+        event.prevent_default();
+        if let Some(pasted) = event.clipboard_data().and_then(|clipboard| clipboard.get_data("text/plain").ok()) {
+            state.type_text(&pasted.replace("\r\n", "\n"));
+        }
+    }) as Box<dyn FnMut(ClipboardEvent)>)
+}
+
+pub fn add_text_input_listeners(state: Rc<State>) -> Result<(), JsValue> {
+    let text_area = state.text_area();
+    let input_handler = create_input_handler(state.clone(), text_area.clone());
+    let compositionstart_handler = create_compositionstart_handler(state.clone());
+    let compositionend_handler = create_compositionend_handler(state.clone(), text_area.clone());
+    let keydown_handler = create_keydown_handler(state.clone());
+    let copy_handler = create_copy_handler(state.clone(), false);
+    let cut_handler = create_copy_handler(state.clone(), true);
+    let paste_handler = create_paste_handler(state);
+
+    text_area.add_event_listener_with_callback("input", input_handler.as_ref().unchecked_ref())?;
+    text_area.add_event_listener_with_callback("compositionstart", compositionstart_handler.as_ref().unchecked_ref())?;
+    text_area.add_event_listener_with_callback("compositionend", compositionend_handler.as_ref().unchecked_ref())?;
+    text_area.add_event_listener_with_callback("keydown", keydown_handler.as_ref().unchecked_ref())?;
+    text_area.add_event_listener_with_callback("copy", copy_handler.as_ref().unchecked_ref())?;
+    text_area.add_event_listener_with_callback("cut", cut_handler.as_ref().unchecked_ref())?;
+    text_area.add_event_listener_with_callback("paste", paste_handler.as_ref().unchecked_ref())?;
+
+    input_handler.forget();
+    compositionstart_handler.forget();
+    compositionend_handler.forget();
+    keydown_handler.forget();
+    copy_handler.forget();
+    cut_handler.forget();
+    paste_handler.forget();
+
+    Ok(())
+}
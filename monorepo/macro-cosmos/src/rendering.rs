@@ -1,21 +1,25 @@
 use wasm_bindgen::prelude::*;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, Window};
 use js_sys::Math;
-
-#[derive(Clone, Copy)]
-struct Square {
-    start_x: f64,
-    start_y: f64,
-    width: f64,
-    height: f64,
-    color: [f64; 4],
+use neo_scene::format::{self, NeoFile};
+use neo_scene::history::History;
+use neo_scene::layer;
+use neo_scene::{Color, Document, Point, Rect, Shape, ShapeKind, Style};
+
+/// Random id site for this browser session, see [`Document::with_site`].
+fn random_site() -> u32 {
+    (Math::random() * u32::MAX as f64) as u32
 }
 
+/// CSS pixels around the canvas that are still drawn, so nothing pops in at the edges.
+const CULL_MARGIN: f64 = 64.0;
+
 #[wasm_bindgen]
 pub struct Renderer {
     context: CanvasRenderingContext2d,
     canvas: HtmlCanvasElement,
-    squares: Vec<Square>,
+    document: Document,
+    history: History,
     grid_size: f64,
     dpr: f64,
 }
@@ -40,7 +44,8 @@ impl Renderer {
         let renderer = Renderer { 
             context, 
             canvas,
-            squares: Vec::new(),
+            document: Document::with_site(random_site()),
+            history: History::default(),
             grid_size,
             dpr,
         };
@@ -72,56 +77,65 @@ impl Renderer {
     }
 
     pub fn add_square(&mut self, start_x: f64, start_y: f64, end_x: f64, end_y: f64) {
-        let width = (end_x - start_x).abs();
-        let height = (end_y - start_y).abs();
-        let color = [
-            Math::random(),
-            Math::random(),
-            Math::random(),
+        let bounds = Rect::from_corners(Point::new(start_x, start_y), Point::new(end_x, end_y));
+        let fill = Color::new(
+            Math::random() as f32,
+            Math::random() as f32,
+            Math::random() as f32,
             1.0,
-        ];
-        let square = Square {
-            start_x: start_x.min(end_x),
-            start_y: start_y.min(end_y),
-            width,
-            height,
-            color,
-        };
-        self.squares.push(square);
+        );
+        let style = Style { fill, stroke: Color::BLACK, stroke_width: 1.0 };
+        let id = self.document.push(Shape::new(ShapeKind::Rectangle, bounds, style));
+        self.history.track_created(id);
+        self.history.commit(&self.document);
         self.draw_all_squares();
     }
 
-    pub fn draw_all_squares(&self) {
-        self.clear();
-        for square in &self.squares {
-            self.draw_square(square);
+    pub fn undo(&mut self) {
+        if self.history.undo(&mut self.document) {
+            self.draw_all_squares();
         }
     }
 
-    fn draw_square(&self, square: &Square) {
-        self.context.set_fill_style(&JsValue::from_str(&format!(
-            "rgba({},{},{},{})",
-            (square.color[0] * 255.0) as u8,
-            (square.color[1] * 255.0) as u8,
-            (square.color[2] * 255.0) as u8,
-            square.color[3]
-        )));
-
-        self.context.fill_rect(
-            square.start_x,
-            square.start_y,
-            square.width,
-            square.height
-        );
+    pub fn redo(&mut self) {
+        if self.history.redo(&mut self.document) {
+            self.draw_all_squares();
+        }
+    }
 
-        // Draw border
-        self.context.set_stroke_style(&JsValue::from_str("black"));
-        self.context.set_line_width(1.0);
-        self.context.stroke_rect(
-            square.start_x,
-            square.start_y,
-            square.width,
-            square.height
-        );
+    /// The canvas as the contents of a `.neo` file.
+    pub fn save(&self) -> String {
+        format::to_string(&NeoFile { document: self.document.clone(), ..NeoFile::default() })
     }
-}
\ No newline at end of file
+
+    /// Replaces the canvas with the contents of a `.neo` file, throwing a readable message if it
+    /// can't be read.
+    pub fn load(&mut self, contents: &str) -> Result<(), JsValue> {
+        let file = format::from_str(contents).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let site = self.document.site();
+        self.document = file.document;
+        self.document.set_site(site);
+        self.history = file.history.unwrap_or_default();
+        self.draw_all_squares();
+        Ok(())
+    }
+
+    /// Redraws the objects that intersect the canvas; the rest are culled through the
+    /// document's spatial index.
+    pub fn draw_all_squares(&self) {
+        self.clear();
+        let viewport = Rect::new(
+            0.0,
+            0.0,
+            self.canvas.width() as f64 / self.dpr,
+            self.canvas.height() as f64 / self.dpr,
+        )
+        .expand(CULL_MARGIN);
+        for (id, object) in self.document.iter_in(viewport) {
+            if layer::is_hidden(&self.document, id) {
+                continue;
+            }
+            neo_canvas::draw_object(&self.context, object);
+        }
+    }
+}
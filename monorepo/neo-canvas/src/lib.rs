@@ -0,0 +1,110 @@
+//! Draws [`neo_scene`] objects on an HTML canvas, for the frontends that run in the browser.
+//!
+//! Everything is drawn in world coordinates: the caller sets up the canvas transform for its
+//! camera first.
+
+use neo_scene::{Connector, Object, Shape, ShapeKind, Stroke, Transform};
+use web_sys::CanvasRenderingContext2d;
+
+/// Draws one object. A group draws nothing itself; its children are objects of their own.
+pub fn draw_object(context: &CanvasRenderingContext2d, object: &Object) {
+    match object {
+        Object::Shape(shape) => {
+            context.save();
+            apply_transform(context, shape.world_transform());
+            draw_shape(context, shape);
+            context.restore();
+        }
+        Object::Stroke(stroke) => draw_stroke(context, stroke),
+        Object::Connector(connector) => draw_connector(context, connector),
+        Object::Group(_) => {}
+    }
+}
+
+/// Multiplies the canvas transform by `transform`, so what follows is drawn through it.
+pub fn apply_transform(context: &CanvasRenderingContext2d, transform: Transform) {
+    let Transform { a, b, c, d, e, f } = transform;
+    let _ = context.transform(a, b, c, d, e, f);
+}
+
+/// Draws a shape in its own coordinates, with its transform already applied.
+pub fn draw_shape(context: &CanvasRenderingContext2d, shape: &Shape) {
+    let bounds = shape.bounds;
+    context.begin_path();
+    match shape.kind {
+        ShapeKind::Rectangle => context.rect(bounds.x, bounds.y, bounds.width, bounds.height),
+        ShapeKind::Ellipse => {
+            let center = bounds.center();
+            let (rx, ry) = (bounds.width / 2.0, bounds.height / 2.0);
+            let _ = context.ellipse(center.x, center.y, rx, ry, 0.0, 0.0, std::f64::consts::TAU);
+        }
+    }
+    context.set_fill_style_str(&shape.style.fill.to_css());
+    context.fill();
+    if shape.style.stroke_width > 0.0 {
+        context.set_stroke_style_str(&shape.style.stroke.to_css());
+        context.set_line_width(shape.style.stroke_width);
+        context.stroke();
+    }
+}
+
+pub fn draw_connector(context: &CanvasRenderingContext2d, connector: &Connector) {
+    let path = connector.path();
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+    let color = connector.style.stroke.to_css();
+    context.set_stroke_style_str(&color);
+    context.set_line_width(connector.style.stroke_width);
+    context.set_line_cap("round");
+    context.set_line_join("round");
+    context.begin_path();
+    context.move_to(first.x, first.y);
+    for point in rest {
+        context.line_to(point.x, point.y);
+    }
+    context.stroke();
+
+    context.set_fill_style_str(&color);
+    for [tip, left, right] in connector.arrowheads() {
+        context.begin_path();
+        context.move_to(tip.x, tip.y);
+        context.line_to(left.x, left.y);
+        context.line_to(right.x, right.y);
+        context.close_path();
+        context.fill();
+    }
+}
+
+pub fn draw_stroke(context: &CanvasRenderingContext2d, stroke: &Stroke) {
+    let samples = stroke.samples();
+    let Some(((first, width), rest)) = samples.split_first() else {
+        return;
+    };
+    context.set_stroke_style_str(&stroke.style.stroke.to_css());
+    context.set_line_cap("round");
+    context.set_line_join("round");
+    if stroke.widths.is_empty() {
+        context.set_line_width(*width);
+        context.begin_path();
+        context.move_to(first.x, first.y);
+        // a single sample still leaves a dot thanks to the round cap
+        if rest.is_empty() {
+            context.line_to(first.x, first.y);
+        }
+        for (point, _) in rest {
+            context.line_to(point.x, point.y);
+        }
+        context.stroke();
+        return;
+    }
+    // the canvas can't vary the width along a path, so each segment is its own path
+    for segment in samples.windows(2) {
+        let ((a, wa), (b, wb)) = (segment[0], segment[1]);
+        context.set_line_width((wa + wb) / 2.0);
+        context.begin_path();
+        context.move_to(a.x, a.y);
+        context.line_to(b.x, b.y);
+        context.stroke();
+    }
+}
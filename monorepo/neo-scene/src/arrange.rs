@@ -0,0 +1,162 @@
+//! Lining up several objects at once: aligning their edges or centres, spacing them evenly and
+//! tidying them into a grid.
+//!
+//! Every command tracks what it moves in the given [`History`], so committing afterwards makes
+//! it one undo step, and returns whether anything moved. A group is lined up as a whole.
+//! Connectors attached to a shape are left out, since they follow their shapes anyway, and so
+//! are hidden and locked objects and groups holding a locked one, which stay where they are.
+
+use crate::{
+    document::{Document, Object},
+    geometry::{Point, Rect},
+    history::History,
+    id::ObjectId,
+    layer,
+};
+
+/// Which edge or centre line objects are lined up on. Each is taken from the bounds of all of
+/// them together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    /// Centred on a vertical line.
+    Center,
+    Right,
+    Top,
+    /// Centred on a horizontal line.
+    Middle,
+    Bottom,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Lines `ids` up on one edge or centre line. Needs at least two objects.
+pub fn align(document: &mut Document, history: &mut History, ids: &[ObjectId], alignment: Alignment) -> bool {
+    let items = movable(document, ids);
+    let Some(all) = union(&items) else {
+        return false;
+    };
+    if items.len() < 2 {
+        return false;
+    }
+    let moves = items.iter().map(|(id, bounds)| {
+        let offset = match alignment {
+            Alignment::Left => Point::new(all.x - bounds.x, 0.0),
+            Alignment::Center => Point::new(all.center().x - bounds.center().x, 0.0),
+            Alignment::Right => Point::new(all.max().x - bounds.max().x, 0.0),
+            Alignment::Top => Point::new(0.0, all.y - bounds.y),
+            Alignment::Middle => Point::new(0.0, all.center().y - bounds.center().y),
+            Alignment::Bottom => Point::new(0.0, all.max().y - bounds.max().y),
+        };
+        (*id, offset)
+    });
+    apply(document, history, moves.collect())
+}
+
+/// Spaces `ids` out along `axis` so the gaps between neighbours are equal. The first and last
+/// stay where they are. Needs at least three objects.
+pub fn distribute(document: &mut Document, history: &mut History, ids: &[ObjectId], axis: Axis) -> bool {
+    let mut items = movable(document, ids);
+    if items.len() < 3 {
+        return false;
+    }
+    // worked out along x, with y swapped in for a vertical axis
+    let along = |rect: &Rect| match axis {
+        Axis::Horizontal => (rect.x, rect.width),
+        Axis::Vertical => (rect.y, rect.height),
+    };
+    items.sort_by(|(_, a), (_, b)| along(a).0.total_cmp(&along(b).0));
+    let start = along(&items[0].1).0;
+    let end = items.iter().map(|(_, bounds)| along(bounds).0 + along(bounds).1).fold(f64::MIN, f64::max);
+    let sizes: f64 = items.iter().map(|(_, bounds)| along(bounds).1).sum();
+    let gap = (end - start - sizes) / (items.len() - 1) as f64;
+    let mut position = start;
+    let mut moves = Vec::new();
+    for (id, bounds) in &items {
+        let (from, size) = along(bounds);
+        let delta = position - from;
+        moves.push((*id, if axis == Axis::Horizontal { Point::new(delta, 0.0) } else { Point::new(0.0, delta) }));
+        position += size + gap;
+    }
+    apply(document, history, moves)
+}
+
+/// Arranges `ids` in a grid of about as many columns as rows, `gap` apart, starting from the
+/// top left of their bounds. They keep their reading order, top to bottom and then left to
+/// right, and each sits in the top left of its cell.
+pub fn tidy(document: &mut Document, history: &mut History, ids: &[ObjectId], gap: f64) -> bool {
+    let mut items = movable(document, ids);
+    let Some(all) = union(&items) else {
+        return false;
+    };
+    let columns = (items.len() as f64).sqrt().ceil() as usize;
+    items.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y));
+    let mut rows: Vec<Vec<(ObjectId, Rect)>> = items.chunks(columns).map(<[_]>::to_vec).collect();
+    for row in &mut rows {
+        row.sort_by(|(_, a), (_, b)| a.x.total_cmp(&b.x));
+    }
+    let widths: Vec<f64> = (0..columns)
+        .map(|column| rows.iter().filter_map(|row| row.get(column)).map(|(_, bounds)| bounds.width).fold(0.0, f64::max))
+        .collect();
+    let mut moves = Vec::new();
+    let mut y = all.y;
+    for row in &rows {
+        let mut x = all.x;
+        for ((id, bounds), width) in row.iter().zip(&widths) {
+            moves.push((*id, Point::new(x, y) - bounds.min()));
+            x += width + gap;
+        }
+        y += row.iter().map(|(_, bounds)| bounds.height).fold(0.0, f64::max) + gap;
+    }
+    apply(document, history, moves)
+}
+
+/// The objects among `ids` a command can move, each once, with their bounds.
+fn movable(document: &Document, ids: &[ObjectId]) -> Vec<(ObjectId, Rect)> {
+    let mut items: Vec<(ObjectId, Rect)> = Vec::new();
+    for &id in ids {
+        let Some(object) = document.get(id) else {
+            continue;
+        };
+        let locked_inside = document.leaves(id).iter().any(|leaf| layer::is_locked(document, *leaf));
+        if !layer::is_pickable(document, id) || locked_inside {
+            continue;
+        }
+        if let Object::Connector(connector) = object {
+            if connector.bound_shapes().next().is_some() {
+                continue;
+            }
+        }
+        if let Some(bounds) = document.bounds_of(id) {
+            if !items.iter().any(|(other, _)| *other == id) {
+                items.push((id, bounds));
+            }
+        }
+    }
+    items
+}
+
+fn union(items: &[(ObjectId, Rect)]) -> Option<Rect> {
+    items.iter().map(|(_, bounds)| *bounds).reduce(|a, b| a.union(&b))
+}
+
+fn apply(document: &mut Document, history: &mut History, moves: Vec<(ObjectId, Point)>) -> bool {
+    let mut moved = false;
+    for (id, offset) in moves {
+        if offset == Point::ZERO {
+            continue;
+        }
+        for leaf in document.leaves(id) {
+            history.track(document, leaf);
+            if let Some(mut object) = document.get_mut(leaf) {
+                object.translate(offset);
+                moved = true;
+            }
+        }
+    }
+    moved
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geometry::Point,
+    stroke::Stroke,
+    style::{Color, Style},
+};
+
+/// Thinnest a stroke gets from pressure, as a fraction of the brush width, so a light touch
+/// still leaves a visible line.
+const MIN_WIDTH_FACTOR: f64 = 0.1;
+
+/// Settings for drawing freehand strokes: what a new stroke looks like and how raw pointer
+/// input is turned into it. Frontends keep one per tool or preset.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Brush {
+    pub color: Color,
+    /// Width at full pressure with the pen upright, in world units.
+    pub width: f64,
+    /// 0 ignores pressure; 1 lets a feather-light touch thin the line right down.
+    pub pressure_sensitivity: f64,
+    /// How much tilting the pen widens the line, like shading with the side of a pencil.
+    /// 0 ignores tilt; 1 doubles the width with the pen lying flat.
+    pub tilt_sensitivity: f64,
+    /// Between 0 and 1: how strongly new samples are pulled towards the previous one to steady
+    /// a shaky hand. 0 follows the pointer exactly.
+    pub stabilizer: f64,
+    /// Points that move the finished line by less than this many screen pixels are dropped
+    /// when the stroke is committed. 0 keeps every sample.
+    pub simplify_tolerance: f64,
+    /// Draw strokes as splines through their points rather than straight segments.
+    pub smooth: bool,
+}
+
+impl Brush {
+    pub fn new(color: Color, width: f64) -> Self {
+        Brush { color, width, ..Brush::default() }
+    }
+
+    pub fn style(&self) -> Style {
+        Style::stroked(self.color, self.width)
+    }
+
+    /// An empty stroke in this brush's style.
+    pub fn begin(&self) -> Stroke {
+        let mut stroke = Stroke::new(self.style());
+        stroke.smooth = self.smooth;
+        stroke
+    }
+
+    /// Width multiplier for a pointer sample. `pressure` is between 0 and 1 and `tilt` is the
+    /// pen's angle away from upright in radians; a mouse reports full pressure and no tilt.
+    pub fn width_factor(&self, pressure: f64, tilt: f64) -> f64 {
+        let pressure = 1.0 - self.pressure_sensitivity * (1.0 - pressure.clamp(0.0, 1.0));
+        let tilt = 1.0 + self.tilt_sensitivity * tilt.sin().abs();
+        (pressure * tilt).max(MIN_WIDTH_FACTOR)
+    }
+
+    /// Where the next point of a stroke goes when the pointer moves to `sample` and the last
+    /// point was `previous`.
+    pub fn stabilize(&self, previous: Point, sample: Point) -> Point {
+        previous + (sample - previous) * (1.0 - self.stabilizer.clamp(0.0, 0.95))
+    }
+
+    /// Adds a pointer sample to `stroke`, applying the stabilizer and pressure curve.
+    pub fn extend(&self, stroke: &mut Stroke, sample: Point, pressure: f64, tilt: f64) {
+        let point = match stroke.points.last() {
+            Some(previous) => self.stabilize(*previous, sample),
+            None => sample,
+        };
+        if stroke.points.last() != Some(&point) {
+            stroke.push(point, self.width_factor(pressure, tilt));
+        }
+    }
+
+    /// Finishes a stroke drawn at `zoom`, simplifying it to the brush's on-screen tolerance.
+    pub fn finish(&self, stroke: &mut Stroke, zoom: f64) {
+        if self.simplify_tolerance > 0.0 {
+            stroke.simplify(self.simplify_tolerance / zoom);
+        }
+    }
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Brush {
+            color: Color::BLACK,
+            width: 2.0,
+            pressure_sensitivity: 0.8,
+            tilt_sensitivity: 0.0,
+            stabilizer: 0.3,
+            simplify_tolerance: 0.5,
+            smooth: true,
+        }
+    }
+}
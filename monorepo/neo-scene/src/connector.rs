@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geometry::{Point, Rect},
+    id::ObjectId,
+    layer::Layer,
+    style::Style,
+};
+
+/// World units an elbow runs straight out of a shape before turning.
+const ELBOW_STUB: f64 = 20.0;
+/// Points sampled along a curved connector.
+const CURVE_STEPS: usize = 24;
+/// Arrowhead length at a stroke width of zero; it grows with the width.
+const ARROW_LENGTH: f64 = 10.0;
+/// Half the angle at an arrowhead's tip.
+const ARROW_HALF_ANGLE: f64 = 0.45;
+
+/// Where on a shape a connector end attaches: the middle of a side, or the centre.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Anchor {
+    Top,
+    Right,
+    Bottom,
+    Left,
+    Center,
+}
+
+impl Anchor {
+    pub const SIDES: [Anchor; 4] = [Anchor::Top, Anchor::Right, Anchor::Bottom, Anchor::Left];
+
+    pub fn position(&self, bounds: Rect) -> Point {
+        let center = bounds.center();
+        match self {
+            Anchor::Top => Point::new(center.x, bounds.y),
+            Anchor::Right => Point::new(bounds.x + bounds.width, center.y),
+            Anchor::Bottom => Point::new(center.x, bounds.y + bounds.height),
+            Anchor::Left => Point::new(bounds.x, center.y),
+            Anchor::Center => center,
+        }
+    }
+
+    /// Unit vector pointing away from the shape, or zero for the centre.
+    fn direction(&self) -> Point {
+        match self {
+            Anchor::Top => Point::new(0.0, -1.0),
+            Anchor::Right => Point::new(1.0, 0.0),
+            Anchor::Bottom => Point::new(0.0, 1.0),
+            Anchor::Left => Point::new(-1.0, 0.0),
+            Anchor::Center => Point::ZERO,
+        }
+    }
+}
+
+/// A connector end attached to a shape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Binding {
+    pub shape: ObjectId,
+    pub anchor: Anchor,
+}
+
+/// One end of a connector.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Endpoint {
+    /// World position. The document keeps it on the anchor of a bound shape as the shape moves,
+    /// and the end stays here, free, if the shape is deleted.
+    pub position: Point,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binding: Option<Binding>,
+}
+
+impl Endpoint {
+    pub fn free(position: Point) -> Self {
+        Endpoint { position, binding: None }
+    }
+
+    pub fn bound(shape: ObjectId, anchor: Anchor, shape_bounds: Rect) -> Self {
+        Endpoint { position: anchor.position(shape_bounds), binding: Some(Binding { shape, anchor }) }
+    }
+
+    /// Direction the line leaves the end in: away from the shape for a bound end, or towards
+    /// `other` along its main axis for a free one.
+    fn direction(&self, other: Point) -> Point {
+        match self.binding.map(|b| b.anchor.direction()).filter(|d| *d != Point::ZERO) {
+            Some(direction) => direction,
+            None => {
+                let delta = other - self.position;
+                if delta.x.abs() >= delta.y.abs() {
+                    Point::new(delta.x.signum(), 0.0)
+                } else {
+                    Point::new(0.0, delta.y.signum())
+                }
+            }
+        }
+    }
+}
+
+/// How a connector gets from one end to the other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Routing {
+    #[default]
+    Straight,
+    /// Horizontal and vertical segments only.
+    Elbow,
+    /// A smooth curve leaving each end square to its shape.
+    Curved,
+}
+
+/// A line between two points, each either free or attached to a shape, for flowcharts and
+/// diagrams. Moving or resizing a shape reroutes the connectors attached to it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Connector {
+    pub start: Endpoint,
+    pub end: Endpoint,
+    #[serde(default)]
+    pub routing: Routing,
+    #[serde(default)]
+    pub start_arrow: bool,
+    #[serde(default)]
+    pub end_arrow: bool,
+    pub style: Style,
+    #[serde(default, skip_serializing_if = "Layer::is_default")]
+    pub layer: Layer,
+}
+
+impl Connector {
+    /// A straight connector with an arrowhead at the end.
+    pub fn new(start: Endpoint, end: Endpoint, style: Style) -> Self {
+        Connector { start, end, routing: Routing::Straight, start_arrow: false, end_arrow: true, style, layer: Layer::default() }
+    }
+
+    /// The line as a polyline in world space, from start to end.
+    pub fn path(&self) -> Vec<Point> {
+        let (a, b) = (self.start.position, self.end.position);
+        match self.routing {
+            Routing::Straight => vec![a, b],
+            Routing::Elbow => self.elbow(),
+            Routing::Curved => {
+                let reach = a.distance(b) / 2.0;
+                let c1 = a + self.start.direction(b) * reach;
+                let c2 = b + self.end.direction(a) * reach;
+                (0..=CURVE_STEPS)
+                    .map(|step| cubic_bezier(a, c1, c2, b, step as f64 / CURVE_STEPS as f64))
+                    .collect()
+            }
+        }
+    }
+
+    fn elbow(&self) -> Vec<Point> {
+        let (a, b) = (self.start.position, self.end.position);
+        let stub = |end: &Endpoint, other: Point| match end.binding {
+            Some(_) => end.position + end.direction(other) * ELBOW_STUB,
+            None => end.position,
+        };
+        let (a1, b1) = (stub(&self.start, b), stub(&self.end, a));
+        let mut path = vec![a, a1];
+        if self.start.direction(b).x != 0.0 {
+            let middle = (a1.x + b1.x) / 2.0;
+            path.extend([Point::new(middle, a1.y), Point::new(middle, b1.y)]);
+        } else {
+            let middle = (a1.y + b1.y) / 2.0;
+            path.extend([Point::new(a1.x, middle), Point::new(b1.x, middle)]);
+        }
+        path.extend([b1, b]);
+        path.dedup();
+        path
+    }
+
+    /// Arrowheads as triangles, tip first.
+    pub fn arrowheads(&self) -> Vec<[Point; 3]> {
+        let path = self.path();
+        let length = ARROW_LENGTH + self.style.stroke_width * 3.0;
+        let head = |tip: Point, from: Point| {
+            let direction = tip - from;
+            let distance = direction.length();
+            if distance == 0.0 {
+                return None;
+            }
+            let back = direction * (-length / distance);
+            let (sin, cos) = ARROW_HALF_ANGLE.sin_cos();
+            let rotate = |p: Point, sin: f64| Point::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos);
+            Some([tip, tip + rotate(back, sin), tip + rotate(back, -sin)])
+        };
+        let mut heads = Vec::new();
+        if let [first, second, ..] = path.as_slice() {
+            if self.start_arrow {
+                heads.extend(head(*first, *second));
+            }
+        }
+        if let [.., before, last] = path.as_slice() {
+            if self.end_arrow {
+                heads.extend(head(*last, *before));
+            }
+        }
+        heads
+    }
+
+    /// Whether `point` is within `tolerance` of the line or an arrowhead.
+    pub fn hit_test(&self, point: Point, tolerance: f64) -> bool {
+        let reach = self.style.stroke_width / 2.0 + tolerance;
+        self.path().windows(2).any(|s| point.distance_to_segment(s[0], s[1]) <= reach)
+            || self
+                .arrowheads()
+                .iter()
+                .any(|[tip, left, right]| point.distance_to_segment(*left, *right) <= reach || point.distance(*tip) <= reach)
+    }
+
+    pub fn bounds(&self) -> Option<Rect> {
+        let heads = self.arrowheads().into_iter().flatten();
+        Rect::from_points(self.path().into_iter().chain(heads)).map(|r| r.expand(self.style.stroke_width / 2.0))
+    }
+
+    /// Moves both ends. In a document, ends attached to a shape snap back onto it afterwards,
+    /// so only free ends and ends whose shape was deleted actually move.
+    pub fn translate(&mut self, offset: Point) {
+        self.start.position += offset;
+        self.end.position += offset;
+    }
+
+    /// Shapes the connector is attached to.
+    pub fn bound_shapes(&self) -> impl Iterator<Item = ObjectId> {
+        [self.start.binding, self.end.binding].into_iter().flatten().map(|binding| binding.shape)
+    }
+
+    /// Moves bound ends onto their anchors, given a way to find where an anchor is. Ends whose
+    /// shape is gone keep their last position. Returns whether anything moved.
+    pub(crate) fn reroute(&mut self, anchor_position: impl Fn(Binding) -> Option<Point>) -> bool {
+        let mut moved = false;
+        for end in [&mut self.start, &mut self.end] {
+            let Some(binding) = end.binding else {
+                continue;
+            };
+            if let Some(position) = anchor_position(binding) {
+                moved |= position != end.position;
+                end.position = position;
+            }
+        }
+        moved
+    }
+}
+
+fn cubic_bezier(p0: Point, p1: Point, p2: Point, p3: Point, t: f64) -> Point {
+    let u = 1.0 - t;
+    p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+}
@@ -0,0 +1,724 @@
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connector::Connector,
+    font::EmbeddedFont,
+    geometry::{Point, Rect, Transform},
+    group::Group,
+    id::ObjectId,
+    layer::Layer,
+    shape::Shape,
+    spatial::SpatialIndex,
+    stroke::Stroke,
+};
+
+/// Anything that can live on the canvas.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Object {
+    Shape(Shape),
+    Stroke(Stroke),
+    Connector(Connector),
+    /// Has no geometry of its own: it is drawn, picked and moved through its children, see
+    /// [`Document::leaves`] and [`Document::bounds_of`].
+    Group(Group),
+}
+
+impl Object {
+    /// World-space bounds, or `None` for an object with no geometry yet (an empty stroke) or
+    /// none of its own (a group).
+    pub fn bounds(&self) -> Option<Rect> {
+        match self {
+            Object::Shape(shape) => Some(shape.world_bounds()),
+            Object::Stroke(stroke) => stroke.bounds(),
+            Object::Connector(connector) => connector.bounds(),
+            Object::Group(_) => None,
+        }
+    }
+
+    /// Whether `point` touches the object's painted outline, within `tolerance` world units.
+    pub fn hit_test(&self, point: Point, tolerance: f64) -> bool {
+        match self {
+            Object::Shape(shape) => shape.hit_test(point, tolerance),
+            Object::Stroke(stroke) => stroke.hit_test(point, tolerance),
+            Object::Connector(connector) => connector.hit_test(point, tolerance),
+            Object::Group(_) => false,
+        }
+    }
+
+    pub fn translate(&mut self, offset: Point) {
+        match self {
+            Object::Shape(shape) => shape.bounds = shape.bounds.translate(offset),
+            Object::Stroke(stroke) => stroke.points.iter_mut().for_each(|p| *p += offset),
+            Object::Connector(connector) => connector.translate(offset),
+            Object::Group(_) => {}
+        }
+    }
+
+    /// Scales, turns, slants or flips the object in world space, for working on several objects
+    /// together. Shapes keep the transform; strokes and connectors are only points, so the
+    /// points themselves are moved.
+    pub fn apply_transform(&mut self, transform: Transform) {
+        match self {
+            Object::Shape(shape) => shape.apply_transform(transform),
+            Object::Stroke(stroke) => stroke.points.iter_mut().for_each(|p| *p = transform.apply(*p)),
+            Object::Connector(connector) => {
+                connector.start.position = transform.apply(connector.start.position);
+                connector.end.position = transform.apply(connector.end.position);
+            }
+            Object::Group(_) => {}
+        }
+    }
+
+    /// The object's name and whether it is hidden or locked, see [`crate::layer`].
+    pub fn layer(&self) -> &Layer {
+        match self {
+            Object::Shape(shape) => &shape.layer,
+            Object::Stroke(stroke) => &stroke.layer,
+            Object::Connector(connector) => &connector.layer,
+            Object::Group(group) => &group.layer,
+        }
+    }
+
+    pub fn layer_mut(&mut self) -> &mut Layer {
+        match self {
+            Object::Shape(shape) => &mut shape.layer,
+            Object::Stroke(stroke) => &mut stroke.layer,
+            Object::Connector(connector) => &mut connector.layer,
+            Object::Group(group) => &mut group.layer,
+        }
+    }
+}
+
+/// Stands for the shape of a document's tree at one moment, see [`Document::tree_revision`].
+/// Every revision is handed out once per process, so revisions of two different documents
+/// never match by accident.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Revision(u64);
+
+impl Revision {
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Revision(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for Revision {
+    fn default() -> Self {
+        Revision::next()
+    }
+}
+
+/// The contents of a canvas.
+///
+/// Objects are addressed by [`ObjectId`] rather than by position, so an id handed out once keeps
+/// pointing at the same object across deletes, reordering, saving and remote edits. Draw order
+/// is tracked separately, back to front, so later objects draw on top.
+///
+/// Groups are objects too, listing their children. The children stay in the document like any
+/// other object, so each one keeps its id and place in draw order; commands that group or
+/// restack keep a group's members next to each other.
+///
+/// The document also keeps a spatial index over object bounds for hit testing and viewport
+/// queries. It is refreshed automatically: mutable access goes through [`ObjectMut`], which
+/// re-indexes the object when it is dropped and reroutes the connectors attached to it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(try_from = "DocumentData", into = "DocumentData")]
+pub struct Document {
+    objects: HashMap<ObjectId, Object>,
+    order: Vec<ObjectId>,
+    /// Position of every id in `order`.
+    positions: HashMap<ObjectId, usize>,
+    index: SpatialIndex,
+    /// Every connector, so the ones attached to a shape can be found when it changes.
+    connectors: HashSet<ObjectId>,
+    /// The group each grouped object is in.
+    parents: HashMap<ObjectId, ObjectId>,
+    /// Font files saved with the document for its text.
+    fonts: Vec<EmbeddedFont>,
+    tree: Revision,
+    /// Session-local; never saved, every session picks its own.
+    site: u32,
+    next_seq: u32,
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Document::default()
+    }
+
+    /// An empty document whose new ids are minted under `site`. Concurrent editors of the same
+    /// document should each use a different, ideally random, site.
+    pub fn with_site(site: u32) -> Self {
+        let mut document = Document::new();
+        document.set_site(site);
+        document
+    }
+
+    pub fn site(&self) -> u32 {
+        self.site
+    }
+
+    /// Switches the site new ids are minted under, e.g. after loading a document from disk.
+    pub fn set_site(&mut self, site: u32) {
+        self.site = site;
+        self.next_seq = self
+            .order
+            .iter()
+            .filter(|id| id.site() == site)
+            .map(|id| id.seq().wrapping_add(1))
+            .max()
+            .unwrap_or(0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn contains(&self, id: ObjectId) -> bool {
+        self.objects.contains_key(&id)
+    }
+
+    pub fn get(&self, id: ObjectId) -> Option<&Object> {
+        self.objects.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: ObjectId) -> Option<ObjectMut<'_, Object>> {
+        ObjectMut::new(self, id)
+    }
+
+    pub fn fonts(&self) -> &[EmbeddedFont] {
+        &self.fonts
+    }
+
+    /// Saves a font file with the document, replacing any earlier file for the same face.
+    pub fn embed_font(&mut self, font: EmbeddedFont) {
+        match self.fonts.iter_mut().find(|embedded| embedded.same_face(&font)) {
+            Some(embedded) => *embedded = font,
+            None => self.fonts.push(font),
+        }
+    }
+
+    pub fn shape(&self, id: ObjectId) -> Option<&Shape> {
+        match self.objects.get(&id)? {
+            Object::Shape(shape) => Some(shape),
+            _ => None,
+        }
+    }
+
+    pub fn shape_mut(&mut self, id: ObjectId) -> Option<ObjectMut<'_, Shape>> {
+        ObjectMut::new(self, id)
+    }
+
+    pub fn stroke_mut(&mut self, id: ObjectId) -> Option<ObjectMut<'_, Stroke>> {
+        ObjectMut::new(self, id)
+    }
+
+    pub fn connector(&self, id: ObjectId) -> Option<&Connector> {
+        match self.objects.get(&id)? {
+            Object::Connector(connector) => Some(connector),
+            _ => None,
+        }
+    }
+
+    pub fn connector_mut(&mut self, id: ObjectId) -> Option<ObjectMut<'_, Connector>> {
+        ObjectMut::new(self, id)
+    }
+
+    pub fn group(&self, id: ObjectId) -> Option<&Group> {
+        match self.objects.get(&id)? {
+            Object::Group(group) => Some(group),
+            _ => None,
+        }
+    }
+
+    pub fn group_mut(&mut self, id: ObjectId) -> Option<ObjectMut<'_, Group>> {
+        ObjectMut::new(self, id)
+    }
+
+    /// The group `id` is in, if any.
+    pub fn parent(&self, id: ObjectId) -> Option<ObjectId> {
+        self.parents.get(&id).copied()
+    }
+
+    /// The objects directly inside the group `parent`, or at the top level for `None`, back to
+    /// front. A group stacks where the topmost object inside it does.
+    pub fn children(&self, parent: Option<ObjectId>) -> Vec<ObjectId> {
+        let mut children: Vec<ObjectId> = match parent {
+            Some(parent) => self.group(parent).map_or_else(Vec::new, |group| {
+                group.children.iter().copied().filter(|id| self.contains(*id) && self.parent(*id) == Some(parent)).collect()
+            }),
+            None => self.order.iter().copied().filter(|id| self.parent(*id).is_none()).collect(),
+        };
+        children.sort_by_cached_key(|id| self.stack_position(*id));
+        children.dedup();
+        children
+    }
+
+    /// `id` and, for a group, everything inside it however deeply nested, back to front.
+    pub fn subtree(&self, id: ObjectId) -> Vec<ObjectId> {
+        let (mut found, mut seen) = (Vec::new(), HashSet::new());
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            // a damaged file could make a group contain itself
+            if !self.contains(id) || !seen.insert(id) {
+                continue;
+            }
+            found.push(id);
+            if let Some(group) = self.group(id) {
+                pending.extend(group.children.iter().filter(|child| self.parent(**child) == Some(id)));
+            }
+        }
+        found.sort_unstable_by_key(|id| self.positions[id]);
+        found
+    }
+
+    /// The objects that draw `id`: itself, or everything inside a group other than more groups.
+    pub fn leaves(&self, id: ObjectId) -> Vec<ObjectId> {
+        self.subtree(id).into_iter().filter(|id| self.group(*id).is_none()).collect()
+    }
+
+    /// World-space bounds of an object, or of everything in a group together.
+    pub fn bounds_of(&self, id: ObjectId) -> Option<Rect> {
+        self.leaves(id).into_iter().filter_map(|id| self.get(id)?.bounds()).reduce(|a, b| a.union(&b))
+    }
+
+    /// Where `id` sits in draw order: the topmost of everything it draws, or its own position
+    /// for a group with nothing in it.
+    fn stack_position(&self, id: ObjectId) -> Option<usize> {
+        self.leaves(id).into_iter().filter_map(|id| self.index_of(id)).max().or_else(|| self.index_of(id))
+    }
+
+    /// Connectors with at least one end attached to `shape`, in no particular order.
+    pub fn connectors_of(&self, shape: ObjectId) -> Vec<ObjectId> {
+        self.connectors
+            .iter()
+            .copied()
+            .filter(|id| self.connector(*id).is_some_and(|c| c.bound_shapes().any(|bound| bound == shape)))
+            .collect()
+    }
+
+    /// Changes whenever objects are added, removed or restacked or a group's children change,
+    /// but not when an object is only edited, so a view of the tree such as a layers panel can
+    /// tell when to build itself again.
+    pub fn tree_revision(&self) -> Revision {
+        self.tree
+    }
+
+    /// Ids in draw order, back to front.
+    pub fn ids(&self) -> &[ObjectId] {
+        &self.order
+    }
+
+    /// Objects in draw order, back to front.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (ObjectId, &Object)> + '_ {
+        self.order.iter().map(move |id| (*id, &self.objects[id]))
+    }
+
+    /// Position of an object in draw order, 0 being the bottom.
+    pub fn index_of(&self, id: ObjectId) -> Option<usize> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Objects whose outline is under `point`, within `tolerance` world units, topmost first.
+    pub fn hits_at(&self, point: Point, tolerance: f64) -> Vec<ObjectId> {
+        let probe = Rect::from_corners(point, point).expand(tolerance);
+        let mut hits: Vec<ObjectId> = self
+            .index
+            .in_rect(probe)
+            .filter(|id| self.objects[id].hit_test(point, tolerance))
+            .collect();
+        hits.sort_unstable_by_key(|id| std::cmp::Reverse(self.positions[id]));
+        hits
+    }
+
+    /// The object drawn on top at `point`, which is the one a click there should select.
+    pub fn topmost_at(&self, point: Point, tolerance: f64) -> Option<ObjectId> {
+        self.hits_at(point, tolerance).first().copied()
+    }
+
+    /// Objects whose bounds intersect `rect`, in draw order (back to front).
+    pub fn in_rect(&self, rect: Rect) -> Vec<ObjectId> {
+        let mut found: Vec<ObjectId> = self.index.in_rect(rect).collect();
+        found.sort_unstable_by_key(|id| self.positions[id]);
+        found
+    }
+
+    /// Objects whose bounds intersect `rect`, back to front. Renderers pass the visible world
+    /// rectangle (plus a margin) to draw only what is on screen.
+    pub fn iter_in(&self, rect: Rect) -> impl Iterator<Item = (ObjectId, &Object)> + '_ {
+        self.in_rect(rect).into_iter().map(move |id| (id, &self.objects[&id]))
+    }
+
+    /// The object whose bounds are closest to `point`, if any lies within `max_distance`.
+    /// Among equally close objects, such as several that contain the point, the topmost wins.
+    pub fn nearest(&self, point: Point, max_distance: f64) -> Option<ObjectId> {
+        let mut candidates = self.index.nearest(point);
+        let (first, distance) = candidates.next()?;
+        if distance > max_distance {
+            return None;
+        }
+        candidates
+            .take_while(|(_, d)| *d <= distance)
+            .map(|(id, _)| id)
+            .chain(std::iter::once(first))
+            .max_by_key(|id| self.positions[id])
+    }
+
+    /// Adds an object on top of everything else under a freshly minted id.
+    pub fn push(&mut self, object: impl Into<Object>) -> ObjectId {
+        let id = self.mint_id();
+        self.insert(id, object);
+        id
+    }
+
+    /// Adds an object under a freshly minted id at a given position in draw order, such as
+    /// right above the object it was split from.
+    pub fn add_at(&mut self, index: usize, object: impl Into<Object>) -> ObjectId {
+        let id = self.mint_id();
+        self.insert_at(index, id, object);
+        id
+    }
+
+    /// Stores an object under an id chosen elsewhere, such as a remote edit. A new id goes on
+    /// top; an existing one is replaced in place and the old object is returned.
+    pub fn insert(&mut self, id: ObjectId, object: impl Into<Object>) -> Option<Object> {
+        let previous = self.objects.insert(id, object.into());
+        match previous {
+            None => {
+                self.positions.insert(id, self.order.len());
+                self.order.push(id);
+                self.tree = Revision::next();
+            }
+            Some(Object::Group(_)) => {
+                self.parents.retain(|_, parent| *parent != id);
+                self.tree = Revision::next();
+            }
+            Some(_) => {}
+        }
+        self.refresh(id);
+        previous
+    }
+
+    /// Stores an object under `id` at a given position in draw order, replacing any object
+    /// already stored under that id. Indices past the top are clamped.
+    pub fn insert_at(&mut self, index: usize, id: ObjectId, object: impl Into<Object>) {
+        self.remove(id);
+        self.objects.insert(id, object.into());
+        let index = index.min(self.order.len());
+        self.order.insert(index, id);
+        self.reindex_from(index);
+        self.tree = Revision::next();
+        self.refresh(id);
+    }
+
+    /// Removes an object. Connectors attached to a removed shape keep their ends where they
+    /// were, and pick the shape up again if it comes back, e.g. on undo.
+    pub fn remove(&mut self, id: ObjectId) -> Option<Object> {
+        let object = self.objects.remove(&id)?;
+        self.index.remove(id);
+        self.connectors.remove(&id);
+        if let Object::Group(_) = object {
+            self.parents.retain(|_, parent| *parent != id);
+        }
+        let index = self.positions.remove(&id).unwrap();
+        self.order.remove(index);
+        self.reindex_from(index);
+        self.tree = Revision::next();
+        Some(object)
+    }
+
+    /// Puts every object in a new draw order, back to front. `order` must hold each id in the
+    /// document exactly once.
+    pub(crate) fn set_order(&mut self, order: Vec<ObjectId>) {
+        debug_assert_eq!(order.len(), self.order.len());
+        self.order = order;
+        self.reindex_from(0);
+        self.tree = Revision::next();
+    }
+
+    /// Brings everything derived from an object up to date after it changed: its entry in the
+    /// spatial index, for a shape the connectors attached to it and for a group which objects
+    /// it holds.
+    fn refresh(&mut self, id: ObjectId) {
+        let Some(object) = self.objects.get(&id) else {
+            return;
+        };
+        if let Object::Group(group) = object {
+            self.parents.retain(|_, parent| *parent != id);
+            // an object can only be in one group, and never in itself
+            for child in group.children.iter().filter(|child| **child != id) {
+                self.parents.insert(*child, id);
+            }
+            self.tree = Revision::next();
+        }
+        if let Object::Connector(_) = object {
+            self.connectors.insert(id);
+            self.reroute(id);
+        } else {
+            self.connectors.remove(&id);
+            for connector in self.connectors_of(id) {
+                self.reroute(connector);
+            }
+        }
+        self.index.update(id, self.objects[&id].bounds());
+    }
+
+    /// Moves a connector's attached ends onto their shapes' anchors.
+    fn reroute(&mut self, id: ObjectId) {
+        let Some(mut connector) = self.connector(id).cloned() else {
+            return;
+        };
+        if connector.reroute(|binding| Some(self.shape(binding.shape)?.anchor(binding.anchor))) {
+            self.index.update(id, connector.bounds());
+            self.objects.insert(id, Object::Connector(connector));
+        }
+    }
+
+    fn reindex_from(&mut self, start: usize) {
+        for (position, id) in self.order.iter().enumerate().skip(start) {
+            self.positions.insert(*id, position);
+        }
+    }
+
+    /// Inserts clipboard contents on top of the document. Objects keep their id unless it is
+    /// already taken (copy rather than cut, or pasting twice), in which case a new one is
+    /// minted. Returns the resulting ids in the same order as the input.
+    pub fn paste(&mut self, objects: impl IntoIterator<Item = (ObjectId, Object)>) -> Vec<ObjectId> {
+        objects
+            .into_iter()
+            .map(|(id, object)| {
+                let id = if self.contains(id) { self.mint_id() } else { id };
+                self.insert(id, object);
+                id
+            })
+            .collect()
+    }
+
+    fn mint_id(&mut self) -> ObjectId {
+        loop {
+            let id = ObjectId::new(self.site, self.next_seq);
+            self.next_seq = self.next_seq.wrapping_add(1);
+            if !self.contains(id) {
+                return id;
+            }
+        }
+    }
+}
+
+/// Mutable access to one object in a [`Document`]. Dereferences to the object; when dropped,
+/// the document's spatial index is updated with the object's new bounds and connectors
+/// attached to it are rerouted.
+pub struct ObjectMut<'a, T: Variant> {
+    id: ObjectId,
+    document: &'a mut Document,
+    variant: PhantomData<T>,
+}
+
+impl<'a, T: Variant> ObjectMut<'a, T> {
+    fn new(document: &'a mut Document, id: ObjectId) -> Option<Self> {
+        T::view(document.objects.get(&id)?)?;
+        Some(ObjectMut { id, document, variant: PhantomData })
+    }
+}
+
+impl<T: Variant> Deref for ObjectMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        T::view(&self.document.objects[&self.id]).unwrap()
+    }
+}
+
+impl<T: Variant> DerefMut for ObjectMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        T::view_mut(self.document.objects.get_mut(&self.id).unwrap()).unwrap()
+    }
+}
+
+impl<T: Variant> Drop for ObjectMut<'_, T> {
+    fn drop(&mut self) {
+        self.document.refresh(self.id);
+    }
+}
+
+/// Anything [`ObjectMut`] can hand out: the object itself or the value inside one variant.
+pub trait Variant {
+    fn view(object: &Object) -> Option<&Self>;
+    fn view_mut(object: &mut Object) -> Option<&mut Self>;
+}
+
+impl Variant for Object {
+    fn view(object: &Object) -> Option<&Self> {
+        Some(object)
+    }
+
+    fn view_mut(object: &mut Object) -> Option<&mut Self> {
+        Some(object)
+    }
+}
+
+impl Variant for Shape {
+    fn view(object: &Object) -> Option<&Self> {
+        match object {
+            Object::Shape(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn view_mut(object: &mut Object) -> Option<&mut Self> {
+        match object {
+            Object::Shape(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl Variant for Stroke {
+    fn view(object: &Object) -> Option<&Self> {
+        match object {
+            Object::Stroke(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn view_mut(object: &mut Object) -> Option<&mut Self> {
+        match object {
+            Object::Stroke(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl Variant for Group {
+    fn view(object: &Object) -> Option<&Self> {
+        match object {
+            Object::Group(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn view_mut(object: &mut Object) -> Option<&mut Self> {
+        match object {
+            Object::Group(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl Variant for Connector {
+    fn view(object: &Object) -> Option<&Self> {
+        match object {
+            Object::Connector(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn view_mut(object: &mut Object) -> Option<&mut Self> {
+        match object {
+            Object::Connector(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Document {
+    /// Two documents are equal when they hold the same objects in the same order and the same
+    /// fonts, whichever session they belong to.
+    fn eq(&self, other: &Self) -> bool {
+        self.order == other.order && self.objects == other.objects && self.fonts == other.fonts
+    }
+}
+
+/// Serialized form of a [`Document`]: the objects as a list in draw order.
+#[derive(Serialize, Deserialize)]
+struct DocumentData {
+    objects: Vec<Entry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fonts: Vec<EmbeddedFont>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    id: ObjectId,
+    object: Object,
+}
+
+impl TryFrom<DocumentData> for Document {
+    type Error = String;
+
+    /// Fails when an id is listed twice, which would leave the draw order and the objects out
+    /// of step.
+    fn try_from(data: DocumentData) -> Result<Self, String> {
+        let mut document = Document::new();
+        for (position, entry) in data.objects.into_iter().enumerate() {
+            if document.positions.insert(entry.id, position).is_some() {
+                return Err(format!("object {} is listed twice", entry.id));
+            }
+            document.order.push(entry.id);
+            match &entry.object {
+                Object::Connector(_) => {
+                    document.connectors.insert(entry.id);
+                }
+                Object::Group(group) => {
+                    for child in group.children.iter().filter(|child| **child != entry.id) {
+                        document.parents.insert(*child, entry.id);
+                    }
+                }
+                _ => {}
+            }
+            document.objects.insert(entry.id, entry.object);
+        }
+        document.index = SpatialIndex::bulk_load(
+            document.iter().filter_map(|(id, object)| Some((id, object.bounds()?))).collect::<Vec<_>>(),
+        );
+        document.fonts = data.fonts;
+        document.set_site(0);
+        Ok(document)
+    }
+}
+
+impl From<Document> for DocumentData {
+    fn from(mut document: Document) -> Self {
+        let objects = document
+            .order
+            .iter()
+            .map(|id| Entry { id: *id, object: document.objects.remove(id).unwrap() })
+            .collect();
+        DocumentData { objects, fonts: document.fonts }
+    }
+}
+
+impl From<Shape> for Object {
+    fn from(shape: Shape) -> Self {
+        Object::Shape(shape)
+    }
+}
+
+impl From<Stroke> for Object {
+    fn from(stroke: Stroke) -> Self {
+        Object::Stroke(stroke)
+    }
+}
+
+impl From<Connector> for Object {
+    fn from(connector: Connector) -> Self {
+        Object::Connector(connector)
+    }
+}
+
+impl From<Group> for Object {
+    fn from(group: Group) -> Self {
+        Object::Group(group)
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    document::{Document, Object},
+    geometry::{Point, Rect},
+    history::History,
+    layer,
+};
+
+/// What the eraser does to a stroke it touches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EraseMode {
+    /// Deletes the whole stroke.
+    Stroke,
+    /// Cuts the stroke where the eraser passes and keeps the pieces on either side as separate
+    /// strokes.
+    Partial,
+}
+
+/// Settings for erasing freehand strokes.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Eraser {
+    pub mode: EraseMode,
+    /// Radius in screen pixels, so the eraser feels the same at every zoom level.
+    pub radius: f64,
+}
+
+impl Eraser {
+    pub fn new(mode: EraseMode, radius: f64) -> Self {
+        Eraser { mode, radius }
+    }
+
+    /// Erases along the eraser's path from `from` to `to` in world space, at `zoom`. Every
+    /// change is tracked in `history`, so committing afterwards makes the whole drag one undo
+    /// step. Hidden and locked strokes are left alone, and an eraser without a positive radius
+    /// at a finite zoom erases nothing. Returns whether anything was erased.
+    pub fn erase(&self, document: &mut Document, history: &mut History, from: Point, to: Point, zoom: f64) -> bool {
+        let radius = self.radius / zoom;
+        if !(radius.is_finite() && radius > 0.0) {
+            return false;
+        }
+        // step along the path so a fast drag doesn't skip over thin strokes
+        let steps = (from.distance(to) / (radius / 2.0)).ceil().max(1.0) as usize;
+        let mut erased = false;
+        for step in 1..=steps {
+            let center = from + (to - from) * (step as f64 / steps as f64);
+            erased |= self.erase_at(document, history, center, radius);
+        }
+        erased
+    }
+
+    fn erase_at(&self, document: &mut Document, history: &mut History, center: Point, radius: f64) -> bool {
+        let probe = Rect::from_corners(center, center).expand(radius);
+        let mut erased = false;
+        for id in document.in_rect(probe) {
+            let Some(Object::Stroke(stroke)) = document.get(id).filter(|_| layer::is_pickable(document, id)) else {
+                continue;
+            };
+            match self.mode {
+                EraseMode::Stroke => {
+                    if stroke.hit_test(center, radius) {
+                        history.track(document, id);
+                        document.remove(id);
+                        erased = true;
+                    }
+                }
+                EraseMode::Partial => {
+                    let Some(mut pieces) = stroke.erase(center, radius) else {
+                        continue;
+                    };
+                    history.track(document, id);
+                    erased = true;
+                    if pieces.is_empty() {
+                        document.remove(id);
+                        continue;
+                    }
+                    // the first piece keeps the stroke's id and place; the rest go right
+                    // above it
+                    let rest = pieces.split_off(1);
+                    let index = document.index_of(id).unwrap();
+                    if let Some(mut stroke) = document.stroke_mut(id) {
+                        *stroke = pieces.remove(0);
+                    }
+                    let parent = document.parent(id);
+                    for (offset, piece) in rest.into_iter().enumerate() {
+                        let piece = document.add_at(index + 1 + offset, piece);
+                        history.track_created(piece);
+                        // the pieces of a grouped stroke stay in its group
+                        if let Some(parent) = parent {
+                            history.track(document, parent);
+                            if let Some(mut group) = document.group_mut(parent) {
+                                group.children.push(piece);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        erased
+    }
+}
+
+impl Default for Eraser {
+    fn default() -> Self {
+        Eraser::new(EraseMode::Partial, 8.0)
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// Weight of regular text; 700 is bold.
+pub const REGULAR: u16 = 400;
+pub const BOLD: u16 = 700;
+
+/// How a shape's text is set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextStyle {
+    /// Font families in order of preference. A character the first family has no glyph for is
+    /// looked up in the next one, and so on, before the frontend's own fallback fonts. Empty
+    /// means the frontend's default font.
+    pub families: Vec<String>,
+    /// Font size in world units.
+    pub size: f64,
+    /// From 100 (thin) to 900 (black). Frontends pick the closest weight a family has.
+    pub weight: u16,
+    pub italic: bool,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle { families: Vec::new(), size: 20.0, weight: REGULAR, italic: false }
+    }
+}
+
+impl TextStyle {
+    pub fn is_default(&self) -> bool {
+        *self == TextStyle::default()
+    }
+
+    /// CSS `font` shorthand, for the canvas 2D context in the browser builds.
+    pub fn to_css(&self) -> String {
+        let mut families: Vec<String> = self.families.iter().map(|family| format!("\"{}\"", family.replace('"', ""))).collect();
+        families.push("sans-serif".to_owned());
+        let style = if self.italic { "italic" } else { "normal" };
+        format!("{style} {} {}px {}", self.weight, self.size, families.join(", "))
+    }
+}
+
+/// A font file carried inside a document, so its text looks the same wherever it is opened.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddedFont {
+    pub family: String,
+    pub weight: u16,
+    pub italic: bool,
+    /// The TTF or OTF file.
+    #[serde(with = "base64_data")]
+    pub data: Vec<u8>,
+}
+
+impl EmbeddedFont {
+    /// Whether both are the same face of the same family, whatever their files.
+    pub fn same_face(&self, other: &EmbeddedFont) -> bool {
+        self.family == other.family && self.weight == other.weight && self.italic == other.italic
+    }
+}
+
+/// Font files are stored as base64 strings rather than JSON arrays of numbers.
+mod base64_data {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(D::Error::custom)
+    }
+}
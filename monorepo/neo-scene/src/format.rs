@@ -0,0 +1,158 @@
+//! The versioned `.neo` file format. The layout is described in `FORMAT.md` at the root of
+//! this crate; bump [`FORMAT_VERSION`] and add a step to `MIGRATIONS` whenever it changes.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{document::Document, geometry::Point, history::History};
+
+/// Version written by this build. Files up to this version can be opened.
+pub const FORMAT_VERSION: u32 = 7;
+
+const FORMAT_NAME: &str = "neo-space";
+
+/// Upgrades a parsed file in place by one version. `MIGRATIONS[n]` turns a version `n + 1` file
+/// into a version `n + 2` file, so older files are walked forward one step at a time.
+type Migration = fn(&mut Value) -> Result<(), FormatError>;
+
+const MIGRATIONS: &[Migration] = &[
+    added_connectors,
+    added_groups,
+    added_stroke_widths,
+    added_text_styles,
+    added_shape_transforms,
+    added_layers,
+];
+
+const _: () = assert!(MIGRATIONS.len() + 1 == FORMAT_VERSION as usize);
+
+/// Version 2 added connector objects. Version 1 files have none, so they load unchanged.
+fn added_connectors(_: &mut Value) -> Result<(), FormatError> {
+    Ok(())
+}
+
+/// Version 3 added group objects. Version 2 files have none, so they load unchanged.
+fn added_groups(_: &mut Value) -> Result<(), FormatError> {
+    Ok(())
+}
+
+/// Version 4 added `widths` and `smooth` to strokes. Left out, a stroke is a polyline of even
+/// width, so version 3 strokes load unchanged.
+fn added_stroke_widths(_: &mut Value) -> Result<(), FormatError> {
+    Ok(())
+}
+
+/// Version 5 added `text_style` to shapes and `fonts` to the document. Version 4 text is set in
+/// the frontend's own font, which is what a missing style means, so it loads unchanged.
+fn added_text_styles(_: &mut Value) -> Result<(), FormatError> {
+    Ok(())
+}
+
+/// Version 6 added `transform` to shapes. Version 5 shapes are all upright, which is what a
+/// missing transform means, so they load unchanged.
+fn added_shape_transforms(_: &mut Value) -> Result<(), FormatError> {
+    Ok(())
+}
+
+/// Version 7 added `layer` to every object. Version 6 objects have no name and are neither
+/// hidden nor locked, which is what a missing layer means, so they load unchanged.
+fn added_layers(_: &mut Value) -> Result<(), FormatError> {
+    Ok(())
+}
+
+/// Where the canvas was looking when it was saved.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct View {
+    /// World position at the centre of the screen.
+    pub center: Point,
+    pub zoom: f64,
+    /// Name of the active user action mode, e.g. `"drag"`. Frontends ignore names they
+    /// do not know.
+    pub mode: String,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        View { center: Point::ZERO, zoom: 1.0, mode: "drag".to_owned() }
+    }
+}
+
+/// Everything stored in a `.neo` file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NeoFile {
+    pub document: Document,
+    pub view: View,
+    /// Undo history, for frontends that choose to keep it across sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<History>,
+}
+
+#[derive(Serialize)]
+struct Header<'a> {
+    format: &'static str,
+    version: u32,
+    #[serde(flatten)]
+    file: &'a NeoFile,
+}
+
+#[derive(Debug)]
+pub enum FormatError {
+    /// The input parsed, but is not a `.neo` file.
+    NotNeoFile,
+    /// The input is damaged: invalid JSON or missing/malformed fields.
+    Corrupt(String),
+    /// Saved by a newer build than this one.
+    TooNew { version: u32 },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::NotNeoFile => write!(f, "not a .neo file"),
+            FormatError::Corrupt(reason) => write!(f, "corrupt .neo file: {}", reason),
+            FormatError::TooNew { version } => write!(
+                f,
+                "file uses format version {}, but this build only reads up to version {}; please update",
+                version, FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// Serializes a file at the current [`FORMAT_VERSION`].
+pub fn to_string(file: &NeoFile) -> String {
+    let header = Header { format: FORMAT_NAME, version: FORMAT_VERSION, file };
+    serde_json::to_string_pretty(&header).expect("document serialization cannot fail")
+}
+
+/// Parses a file written by this or any older build, migrating it to the current version.
+pub fn from_str(input: &str) -> Result<NeoFile, FormatError> {
+    let mut value: Value =
+        serde_json::from_str(input).map_err(|e| FormatError::Corrupt(e.to_string()))?;
+    let fields = value.as_object_mut().ok_or(FormatError::NotNeoFile)?;
+    if fields.get("format").and_then(Value::as_str) != Some(FORMAT_NAME) {
+        return Err(FormatError::NotNeoFile);
+    }
+    let version = fields
+        .remove("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| FormatError::Corrupt("missing format version".to_owned()))?;
+    fields.remove("format");
+
+    let version = u32::try_from(version).map_err(|_| FormatError::TooNew { version: u32::MAX })?;
+    if version == 0 {
+        return Err(FormatError::Corrupt("format version 0 does not exist".to_owned()));
+    }
+    if version > FORMAT_VERSION {
+        return Err(FormatError::TooNew { version });
+    }
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(&mut value)?;
+    }
+
+    serde_json::from_value(value).map_err(|e| FormatError::Corrupt(e.to_string()))
+}
@@ -0,0 +1,265 @@
+use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+/// A position or offset in world space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub const ZERO: Point = Point { x: 0.0, y: 0.0 };
+
+    pub const fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn length(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    pub fn distance(self, other: Point) -> f64 {
+        (self - other).length()
+    }
+
+    /// Distance to the closest point of the segment `a`-`b`.
+    pub fn distance_to_segment(self, a: Point, b: Point) -> f64 {
+        let ab = b - a;
+        let length_2 = ab.x * ab.x + ab.y * ab.y;
+        if length_2 == 0.0 {
+            return self.distance(a);
+        }
+        let t = (((self.x - a.x) * ab.x + (self.y - a.y) * ab.y) / length_2).clamp(0.0, 1.0);
+        self.distance(a + ab * t)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Mul<f64> for Point {
+    type Output = Point;
+    fn mul(self, rhs: f64) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Div<f64> for Point {
+    type Output = Point;
+    fn div(self, rhs: f64) -> Point {
+        Point::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+/// Axis-aligned rectangle in world space, stored as its top-left corner and size.
+/// Width and height are never negative.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Rect::from_corners(Point::new(x, y), Point::new(x + width, y + height))
+    }
+
+    /// Builds the rectangle spanned by two opposite corners, in any order.
+    pub fn from_corners(a: Point, b: Point) -> Self {
+        Rect {
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+            width: (a.x - b.x).abs(),
+            height: (a.y - b.y).abs(),
+        }
+    }
+
+    /// Smallest rectangle containing every point, or `None` for an empty iterator.
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        });
+        Some(Rect::from_corners(min, max))
+    }
+
+    pub fn min(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    pub fn max(&self) -> Point {
+        Point::new(self.x + self.width, self.y + self.height)
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    pub fn size(&self) -> Point {
+        Point::new(self.width, self.height)
+    }
+
+    /// Half-open containment, so shapes that share an edge never both claim a point on it.
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= self.x && p.x < self.x + self.width && p.y >= self.y && p.y < self.y + self.height
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+
+    pub fn union(&self, other: &Rect) -> Rect {
+        let min = Point::new(self.x.min(other.x), self.y.min(other.y));
+        let max = Point::new(
+            (self.x + self.width).max(other.x + other.width),
+            (self.y + self.height).max(other.y + other.height),
+        );
+        Rect::from_corners(min, max)
+    }
+
+    pub fn translate(&self, offset: Point) -> Rect {
+        Rect { x: self.x + offset.x, y: self.y + offset.y, ..*self }
+    }
+
+    /// Grows the rectangle by `margin` on every side.
+    pub fn expand(&self, margin: f64) -> Rect {
+        Rect::from_corners(
+            Point::new(self.x - margin, self.y - margin),
+            Point::new(self.x + self.width + margin, self.y + self.height + margin),
+        )
+    }
+}
+
+/// A 2D affine transform, taking (x, y) to (a x + c y + e, b x + d y + f). The fields are in
+/// the same order as the arguments of the canvas `setTransform`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Transform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+
+    pub fn translation(offset: Point) -> Self {
+        Transform { e: offset.x, f: offset.y, ..Transform::IDENTITY }
+    }
+
+    /// Turns by `angle` radians about the origin, clockwise on screen where y points down.
+    pub fn rotation(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Transform { a: cos, b: sin, c: -sin, d: cos, ..Transform::IDENTITY }
+    }
+
+    /// Scales about the origin; a negative factor flips that axis.
+    pub fn scaling(x: f64, y: f64) -> Self {
+        Transform { a: x, d: y, ..Transform::IDENTITY }
+    }
+
+    /// Slants vertical lines by `x` radians and horizontal ones by `y` radians.
+    pub fn skewing(x: f64, y: f64) -> Self {
+        Transform { b: y.tan(), c: x.tan(), ..Transform::IDENTITY }
+    }
+
+    /// This transform followed by `next`.
+    pub fn then(&self, next: Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    /// The same transform done about `pivot` rather than the origin.
+    pub fn about(&self, pivot: Point) -> Transform {
+        Transform::translation(Point::ZERO - pivot).then(*self).then(Transform::translation(pivot))
+    }
+
+    /// The transform without its translation, for directions and sizes.
+    pub fn linear(&self) -> Transform {
+        Transform { e: 0.0, f: 0.0, ..*self }
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        Point::new(self.a * p.x + self.c * p.y + self.e, self.b * p.x + self.d * p.y + self.f)
+    }
+
+    /// The transform undoing this one, or `None` if it squashes everything onto a line.
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Transform { a, b, c, d, e: -(a * self.e + c * self.f), f: -(b * self.e + d * self.f) })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Transform::IDENTITY
+    }
+
+    /// How far the x axis is turned, in radians.
+    pub fn rotation_angle(&self) -> f64 {
+        self.b.atan2(self.a)
+    }
+
+    /// Where the corners of `rect` end up, clockwise from its top left.
+    pub fn corners(&self, rect: Rect) -> [Point; 4] {
+        let (min, max) = (rect.min(), rect.max());
+        [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)].map(|p| self.apply(p))
+    }
+
+    /// Axis-aligned bounds of `rect` once transformed.
+    pub fn bounds(&self, rect: Rect) -> Rect {
+        Rect::from_points(self.corners(rect)).unwrap()
+    }
+}
@@ -0,0 +1,85 @@
+//! Gathering objects into groups, which are picked, moved and transformed as one and can hold
+//! other groups to any depth.
+//!
+//! Like the [`arrange`](crate::arrange) commands, these track everything they change in the
+//! given [`History`], so committing afterwards makes each one undo step.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{document::Document, history::History, id::ObjectId, layer::Layer, order};
+
+/// Objects kept together. The children are ordinary objects in the document; draw order, not
+/// the order they are listed in here, decides which is on top.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    pub children: Vec<ObjectId>,
+    #[serde(default, skip_serializing_if = "Layer::is_default")]
+    pub layer: Layer,
+}
+
+/// Gathers `ids` into a new group, which takes the place in draw order and the parent group of
+/// the topmost of them. The rest move up to join it. Returns the group, or `None` when fewer
+/// than two objects are given.
+pub fn group(document: &mut Document, history: &mut History, ids: &[ObjectId]) -> Option<ObjectId> {
+    // an object inside another one being grouped comes along with it
+    let mut items: Vec<ObjectId> = Vec::new();
+    for &id in ids {
+        let nested = ancestors(document, id).any(|ancestor| ids.contains(&ancestor));
+        if document.contains(id) && !nested && !items.contains(&id) {
+            items.push(id);
+        }
+    }
+    if items.len() < 2 {
+        return None;
+    }
+    let stacked = order::stacked(document);
+    let topmost = *items.iter().max_by_key(|id| stacked.iter().position(|other| other == *id))?;
+    let parent = document.parent(topmost);
+
+    for &item in &items {
+        if let Some(old_parent) = document.parent(item) {
+            history.track(document, old_parent);
+            if let Some(mut old_parent) = document.group_mut(old_parent) {
+                old_parent.children.retain(|child| *child != item);
+            }
+        }
+    }
+    // stacks where its topmost member is, once the others are moved up to it
+    let group = document.push(Group { children: items, ..Default::default() });
+    history.track_created(group);
+    if let Some(parent) = parent {
+        history.track(document, parent);
+        if let Some(mut parent) = document.group_mut(parent) {
+            parent.children.push(group);
+        }
+    }
+    order::restack(document, history, order::stacked(document));
+    Some(group)
+}
+
+/// Dissolves the group `id`. Its children stay where they are in draw order and join the group
+/// it was in, if any. Returns them, back to front, or nothing if `id` isn't a group.
+pub fn ungroup(document: &mut Document, history: &mut History, id: ObjectId) -> Vec<ObjectId> {
+    let Some(group) = document.group(id).cloned() else {
+        return Vec::new();
+    };
+    let children = document.children(Some(id));
+    let parent = document.parent(id);
+    history.track(document, id);
+    document.remove(id);
+    if let Some(parent) = parent {
+        history.track(document, parent);
+        if let Some(mut parent) = document.group_mut(parent) {
+            parent.children.retain(|child| *child != id);
+            // ones deleted since keep their place too, to come back if the deletion is undone
+            parent.children.extend(group.children);
+        }
+    }
+    children
+}
+
+/// The groups `id` is in, innermost first.
+pub fn ancestors(document: &Document, id: ObjectId) -> impl Iterator<Item = ObjectId> + '_ {
+    // bounded, in case a damaged file has groups inside each other
+    std::iter::successors(document.parent(id), |group| document.parent(*group)).take(document.len())
+}
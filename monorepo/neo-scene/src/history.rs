@@ -0,0 +1,163 @@
+//! Undo/redo for document edits.
+//!
+//! Edits are recorded as before/after snapshots of the objects they touch rather than as
+//! per-operation inverse functions, so any mutation a frontend makes to the [`Document`] can be
+//! undone as long as it [tracks](History::track) the affected ids first. Everything tracked
+//! until the next [`History::commit`] becomes a single undo step, which is how continuous
+//! gestures such as a drag or a stroke collapse into one entry.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    document::{Document, Object},
+    id::ObjectId,
+};
+
+pub const DEFAULT_HISTORY_LIMIT: usize = 200;
+
+/// An object together with its position in draw order.
+type Placement = (usize, Object);
+
+/// How one object changed: `None` on the `before` side means it was created, `None` on the
+/// `after` side means it was deleted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub id: ObjectId,
+    pub before: Option<Placement>,
+    pub after: Option<Placement>,
+}
+
+/// One undo step.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Command {
+    pub changes: Vec<Change>,
+}
+
+impl Command {
+    pub fn redo(&self, document: &mut Document) {
+        restore(document, self.changes.iter().map(|change| (change.id, &change.after)));
+    }
+
+    pub fn undo(&self, document: &mut Document) {
+        restore(document, self.changes.iter().map(|change| (change.id, &change.before)));
+    }
+}
+
+/// Puts the objects of a step back as recorded. They are all taken out first and then put
+/// back from the bottom up, so each lands at the position it was recorded at, however many
+/// of them changed places.
+fn restore<'a>(document: &mut Document, placements: impl Iterator<Item = (ObjectId, &'a Option<Placement>)>) {
+    let mut placed = Vec::new();
+    for (id, placement) in placements {
+        document.remove(id);
+        if let Some((index, object)) = placement {
+            placed.push((*index, id, object));
+        }
+    }
+    placed.sort_by_key(|(index, _, _)| *index);
+    for (index, id, object) in placed {
+        document.insert_at(index, id, object.clone());
+    }
+}
+
+fn snapshot(document: &Document, id: ObjectId) -> Option<Placement> {
+    Some((document.index_of(id)?, document.get(id)?.clone()))
+}
+
+/// Bounded undo and redo stacks. Can be saved alongside the document in a `.neo` file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    undo: VecDeque<Command>,
+    redo: Vec<Command>,
+    limit: usize,
+    /// Snapshots taken since the last commit, in the order the ids were first tracked.
+    #[serde(skip)]
+    open: Vec<(ObjectId, Option<Placement>)>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl History {
+    /// A history that keeps at most `limit` undo steps, dropping the oldest first.
+    pub fn new(limit: usize) -> Self {
+        History { undo: VecDeque::new(), redo: Vec::new(), limit, open: Vec::new() }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || !self.open.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Remembers how `id` looks right now so the open step can restore it. Call before the
+    /// first mutation of a gesture; tracking an id twice in one step keeps the first snapshot.
+    pub fn track(&mut self, document: &Document, id: ObjectId) {
+        if !self.open.iter().any(|(tracked, _)| *tracked == id) {
+            self.open.push((id, snapshot(document, id)));
+        }
+    }
+
+    /// Records that `id` did not exist before the open step, for objects that were pushed
+    /// before they could be tracked.
+    pub fn track_created(&mut self, id: ObjectId) {
+        if !self.open.iter().any(|(tracked, _)| *tracked == id) {
+            self.open.push((id, None));
+        }
+    }
+
+    /// Closes the open step, turning everything tracked since the last commit into one undo
+    /// entry. Returns `false` when nothing actually changed.
+    pub fn commit(&mut self, document: &Document) -> bool {
+        let changes: Vec<Change> = self
+            .open
+            .drain(..)
+            .filter_map(|(id, before)| {
+                let after = snapshot(document, id);
+                (before != after).then_some(Change { id, before, after })
+            })
+            .collect();
+        if changes.is_empty() {
+            return false;
+        }
+        self.push(Command { changes });
+        true
+    }
+
+    /// Records a command that has already been applied to the document.
+    pub fn push(&mut self, command: Command) {
+        self.redo.clear();
+        self.undo.push_back(command);
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Reverts the most recent step, committing any open one first.
+    pub fn undo(&mut self, document: &mut Document) -> bool {
+        self.commit(document);
+        let Some(command) = self.undo.pop_back() else {
+            return false;
+        };
+        command.undo(document);
+        self.redo.push(command);
+        true
+    }
+
+    pub fn redo(&mut self, document: &mut Document) -> bool {
+        self.commit(document);
+        let Some(command) = self.redo.pop() else {
+            return false;
+        };
+        command.redo(document);
+        self.undo.push_back(command);
+        true
+    }
+}
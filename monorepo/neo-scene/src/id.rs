@@ -0,0 +1,75 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Persistent identifier of a canvas object.
+///
+/// An id is the pair of the site (editing session) that minted it and a per-site counter, so
+/// sessions that pick different site numbers never collide, even when editing the same
+/// document concurrently. Ids are written out as 16 hex digits so they survive a round trip
+/// through JavaScript, which cannot represent every `u64` as a number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct ObjectId {
+    site: u32,
+    seq: u32,
+}
+
+impl ObjectId {
+    pub const fn new(site: u32, seq: u32) -> Self {
+        ObjectId { site, seq }
+    }
+
+    pub fn site(&self) -> u32 {
+        self.site
+    }
+
+    pub fn seq(&self) -> u32 {
+        self.seq
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08x}{:08x}", self.site, self.seq)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseObjectIdError(String);
+
+impl fmt::Display for ParseObjectIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid object id {:?}, expected 16 hex digits", self.0)
+    }
+}
+
+impl std::error::Error for ParseObjectIdError {}
+
+impl FromStr for ObjectId {
+    type Err = ParseObjectIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseObjectIdError(s.to_owned());
+        if s.len() != 16 || !s.is_ascii() {
+            return Err(err());
+        }
+        let site = u32::from_str_radix(&s[..8], 16).map_err(|_| err())?;
+        let seq = u32::from_str_radix(&s[8..], 16).map_err(|_| err())?;
+        Ok(ObjectId { site, seq })
+    }
+}
+
+impl From<ObjectId> for String {
+    fn from(id: ObjectId) -> String {
+        id.to_string()
+    }
+}
+
+impl TryFrom<String> for ObjectId {
+    type Error = ParseObjectIdError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
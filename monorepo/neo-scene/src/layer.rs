@@ -0,0 +1,49 @@
+//! How objects show in a layers panel: a name, and whether they are hidden or locked.
+//!
+//! Every object carries its own [`Layer`], so renaming, hiding or locking is an ordinary edit
+//! that [`History`](crate::history::History) can track and undo. A group's flags cover
+//! everything inside it, which is what makes a group at the top level work as a layer.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{document::Document, group, id::ObjectId};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Layer {
+    /// Given by the user; empty until then, and frontends show a name of their own.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// Not drawn, and can't be picked.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    /// Drawn, but can't be picked, so it isn't moved or changed by accident.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+}
+
+impl Layer {
+    pub fn is_default(&self) -> bool {
+        *self == Layer::default()
+    }
+}
+
+/// Whether `id` is hidden, itself or through a group it is in.
+pub fn is_hidden(document: &Document, id: ObjectId) -> bool {
+    covered(document, id, |layer| layer.hidden)
+}
+
+/// Whether `id` is locked, itself or through a group it is in.
+pub fn is_locked(document: &Document, id: ObjectId) -> bool {
+    covered(document, id, |layer| layer.locked)
+}
+
+/// Whether clicks, marquees and the eraser may pick `id`: it is neither hidden nor locked.
+pub fn is_pickable(document: &Document, id: ObjectId) -> bool {
+    !is_hidden(document, id) && !is_locked(document, id)
+}
+
+fn covered(document: &Document, id: ObjectId, flag: impl Fn(&Layer) -> bool) -> bool {
+    std::iter::once(id)
+        .chain(group::ancestors(document, id))
+        .any(|id| document.get(id).is_some_and(|object| flag(object.layer())))
+}
@@ -0,0 +1,39 @@
+//! Renderer-agnostic document model shared by the native engine and the wasm crates.
+//!
+//! Everything in here lives in world space and knows nothing about macroquad or
+//! web-sys. Frontends convert to their own vector/color types at the edges.
+
+pub mod arrange;
+mod brush;
+mod connector;
+mod document;
+mod eraser;
+pub mod font;
+pub mod format;
+mod geometry;
+pub mod group;
+pub mod history;
+mod id;
+pub mod layer;
+pub mod order;
+pub mod plugin;
+pub mod resize;
+pub mod selection;
+mod shape;
+pub mod snap;
+mod spatial;
+mod stroke;
+mod style;
+pub mod text;
+
+pub use brush::Brush;
+pub use connector::{Anchor, Binding, Connector, Endpoint, Routing};
+pub use document::{Document, Object, ObjectMut, Revision, Variant};
+pub use eraser::{EraseMode, Eraser};
+pub use geometry::{Point, Rect, Transform};
+pub use group::Group;
+pub use id::{ObjectId, ParseObjectIdError};
+pub use layer::Layer;
+pub use shape::{Shape, ShapeKind};
+pub use stroke::Stroke;
+pub use style::{Color, Style};
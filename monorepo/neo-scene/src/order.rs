@@ -0,0 +1,152 @@
+//! Changing which objects are drawn on top: bringing them forward or to the front, or sending
+//! them backward or to the back. Objects only move among the others in the same group, and a
+//! group moves with everything inside it.
+//!
+//! Like the [`arrange`](crate::arrange) commands, these track everything they change in the
+//! given [`History`] and return whether anything moved.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{document::Document, history::History, id::ObjectId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reorder {
+    /// Above the next object up.
+    Forward,
+    /// Below the next object down.
+    Backward,
+    /// Above everything else in the same group.
+    ToFront,
+    /// Below everything else in the same group.
+    ToBack,
+}
+
+/// Moves `ids` up or down the draw order. Several objects keep their order among themselves.
+pub fn reorder(document: &mut Document, history: &mut History, ids: &[ObjectId], reorder: Reorder) -> bool {
+    let mut reordered: HashMap<Option<ObjectId>, Vec<ObjectId>> = HashMap::new();
+    for &id in ids.iter().filter(|id| document.contains(**id)) {
+        let parent = document.parent(id);
+        if reordered.contains_key(&parent) {
+            continue;
+        }
+        let siblings = document.children(parent);
+        let moving: Vec<bool> = siblings.iter().map(|sibling| ids.contains(sibling)).collect();
+        reordered.insert(parent, restacked(siblings, moving, reorder));
+    }
+    let mut order = Vec::new();
+    stack(document, None, &reordered, &mut HashSet::new(), &mut order);
+    restack(document, history, order)
+}
+
+/// Moves `id` into the group `parent`, or the top level for `None`, at `index` among what is
+/// there now, counted back to front as [`Document::children`] lists it, as dragging a row in a
+/// layers panel does. A group can't go inside itself. Returns whether anything changed.
+pub fn move_to(
+    document: &mut Document,
+    history: &mut History,
+    id: ObjectId,
+    parent: Option<ObjectId>,
+    index: usize,
+) -> bool {
+    let inside_itself = parent.is_some_and(|parent| document.subtree(id).contains(&parent));
+    if !document.contains(id) || inside_itself || parent.is_some_and(|parent| document.group(parent).is_none()) {
+        return false;
+    }
+    let mut siblings = document.children(parent);
+    let mut index = index.min(siblings.len());
+    if let Some(current) = siblings.iter().position(|sibling| *sibling == id) {
+        siblings.remove(current);
+        if current < index {
+            index -= 1;
+        }
+    }
+    siblings.insert(index, id);
+
+    let old_parent = document.parent(id);
+    if old_parent != parent {
+        if let Some(old_parent) = old_parent {
+            history.track(document, old_parent);
+            if let Some(mut old_parent) = document.group_mut(old_parent) {
+                old_parent.children.retain(|child| *child != id);
+            }
+        }
+        if let Some(parent) = parent {
+            history.track(document, parent);
+            if let Some(mut parent) = document.group_mut(parent) {
+                parent.children.push(id);
+            }
+        }
+    }
+    let mut order = Vec::new();
+    stack(document, None, &HashMap::from([(parent, siblings)]), &mut HashSet::new(), &mut order);
+    restack(document, history, order) || old_parent != parent
+}
+
+/// `siblings` in their new order, back to front, after moving the ones marked in `moving`.
+fn restacked(siblings: Vec<ObjectId>, moving: Vec<bool>, reorder: Reorder) -> Vec<ObjectId> {
+    let mut items: Vec<(ObjectId, bool)> = siblings.into_iter().zip(moving).collect();
+    match reorder {
+        Reorder::ToFront => items.sort_by_key(|(_, moving)| *moving),
+        Reorder::ToBack => items.sort_by_key(|(_, moving)| !*moving),
+        // each moving object swaps with the one above it, unless that one moves too and is
+        // already in the way; working from the top keeps a run of them together
+        Reorder::Forward => {
+            for i in (0..items.len().saturating_sub(1)).rev() {
+                if items[i].1 && !items[i + 1].1 {
+                    items.swap(i, i + 1);
+                }
+            }
+        }
+        Reorder::Backward => {
+            for i in 1..items.len() {
+                if items[i].1 && !items[i - 1].1 {
+                    items.swap(i, i - 1);
+                }
+            }
+        }
+    }
+    items.into_iter().map(|(id, _)| id).collect()
+}
+
+/// The whole document in draw order, back to front, with every group's members together and
+/// the group itself right above them.
+pub(crate) fn stacked(document: &Document) -> Vec<ObjectId> {
+    let mut order = Vec::new();
+    stack(document, None, &HashMap::new(), &mut HashSet::new(), &mut order);
+    order
+}
+
+/// Appends what is inside `parent`, or the top level, to `order`, taking the order of any group
+/// in `reordered` from there. `seen` stops a damaged file's groups looping back on themselves.
+fn stack(
+    document: &Document,
+    parent: Option<ObjectId>,
+    reordered: &HashMap<Option<ObjectId>, Vec<ObjectId>>,
+    seen: &mut HashSet<ObjectId>,
+    order: &mut Vec<ObjectId>,
+) {
+    let children = reordered.get(&parent).cloned().unwrap_or_else(|| document.children(parent));
+    for child in children {
+        if !seen.insert(child) {
+            continue;
+        }
+        if document.group(child).is_some() {
+            stack(document, Some(child), reordered, seen, order);
+        }
+        order.push(child);
+    }
+}
+
+/// Puts the document in `order`, tracking every object that changes place.
+pub(crate) fn restack(document: &mut Document, history: &mut History, order: Vec<ObjectId>) -> bool {
+    if order.len() != document.len() || order == document.ids() {
+        return false;
+    }
+    for (index, id) in order.iter().enumerate() {
+        if document.index_of(*id) != Some(index) {
+            history.track(document, *id);
+        }
+    }
+    document.set_order(order);
+    true
+}
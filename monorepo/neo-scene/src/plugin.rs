@@ -0,0 +1,270 @@
+//! Extension point shared by every frontend.
+//!
+//! A [`Plugin`] gets the same lifecycle hooks as the TypeScript prototype's plugins (`init`,
+//! `begin_frame`, `render`, `end_frame`, `resize` and `destroy`) and sees the canvas through a
+//! [`PluginContext`]: the document and its history, the camera as a [`Viewport`], this frame's
+//! [`Input`], and during `render` a [`Draw`] implementation. Frontends own a [`PluginHost`] and
+//! call it from their frame loop, so the same plugin runs natively and in the browser.
+
+use crate::{
+    document::Document,
+    geometry::{Point, Rect},
+    history::History,
+    style::{Color, Style},
+};
+
+/// The camera, in a form every frontend can share. `center` is the world point in the middle of
+/// the screen; `width` and `height` are the screen size in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub center: Point,
+    pub zoom: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Viewport {
+    fn screen_center(&self) -> Point {
+        Point::new(self.width, self.height) * 0.5
+    }
+
+    pub fn world_to_screen(&self, world_pos: Point) -> Point {
+        (world_pos - self.center) * self.zoom + self.screen_center()
+    }
+
+    pub fn screen_to_world(&self, screen_pos: Point) -> Point {
+        (screen_pos - self.screen_center()) / self.zoom + self.center
+    }
+
+    /// The world rectangle currently on screen.
+    pub fn visible_world_bounds(&self) -> Rect {
+        Rect::from_corners(
+            self.screen_to_world(Point::ZERO),
+            self.screen_to_world(Point::new(self.width, self.height)),
+        )
+    }
+}
+
+/// Mouse, pen and keyboard input for one frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Input {
+    /// Pointer position in screen pixels, or `None` when it is outside the canvas.
+    pub pointer: Option<Point>,
+    pub primary_down: bool,
+    /// The primary button went down this frame.
+    pub primary_pressed: bool,
+    /// The primary button came up this frame.
+    pub primary_released: bool,
+    /// Wheel movement this frame in pixels, positive being down and to the right.
+    pub wheel: Point,
+    /// Keys that went down this frame, named like the browser's `KeyboardEvent.key`: the
+    /// character for printable keys (lowercase letters) and names such as `"Enter"`,
+    /// `"Escape"`, `"Backspace"`, `"Delete"`, `"Tab"` or `"ArrowLeft"` for the rest.
+    pub keys_pressed: Vec<String>,
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Input {
+    pub fn key_pressed(&self, name: &str) -> bool {
+        self.keys_pressed.iter().any(|key| key == name)
+    }
+
+    /// The pointer position in world space.
+    pub fn pointer_world(&self, viewport: &Viewport) -> Option<Point> {
+        self.pointer.map(|p| viewport.screen_to_world(p))
+    }
+
+    /// Forgets the one-frame events, keeping what is still held down. Frontends that collect
+    /// input from events call this once the frame's hooks have run.
+    pub fn end_frame(&mut self) {
+        self.primary_pressed = false;
+        self.primary_released = false;
+        self.wheel = Point::ZERO;
+        self.keys_pressed.clear();
+    }
+}
+
+/// Drawing on top of the canvas, in screen pixels. Use [`Viewport::world_to_screen`] to place
+/// things on world positions.
+pub trait Draw {
+    fn line(&mut self, from: Point, to: Point, width: f64, color: Color);
+    fn rect(&mut self, rect: Rect, style: Style);
+    fn ellipse(&mut self, bounds: Rect, style: Style);
+    /// `position` is the left end of the baseline.
+    fn text(&mut self, text: &str, position: Point, size: f64, color: Color);
+}
+
+/// What a plugin can see and change.
+pub struct PluginContext<'a> {
+    pub document: &'a mut Document,
+    /// Edits made through the document should be tracked here to be undoable; the frontend
+    /// commits open steps as usual.
+    pub history: &'a mut History,
+    /// Moving or zooming the viewport moves the frontend's camera.
+    pub viewport: &'a mut Viewport,
+    pub input: &'a Input,
+    active_tool: Option<String>,
+    redraw: bool,
+}
+
+impl<'a> PluginContext<'a> {
+    pub fn new(
+        document: &'a mut Document,
+        history: &'a mut History,
+        viewport: &'a mut Viewport,
+        input: &'a Input,
+    ) -> Self {
+        PluginContext { document, history, viewport, input, active_tool: None, redraw: false }
+    }
+
+    /// Which of the plugin's own [`Plugin::tools`] is selected in the toolbar, if any.
+    pub fn active_tool(&self) -> Option<&str> {
+        self.active_tool.as_deref()
+    }
+
+    /// Asks for the canvas to be painted again. Frontends that only repaint when something
+    /// changed need this for animated overlays or edits they can't see.
+    pub fn request_redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    pub fn redraw_requested(&self) -> bool {
+        self.redraw
+    }
+}
+
+/// Code that hooks into a canvas. Every hook is optional.
+pub trait Plugin {
+    /// Shown in error messages and logs.
+    fn name(&self) -> &str;
+
+    /// Called once when the plugin is added.
+    fn init(&mut self, _ctx: &mut PluginContext) {}
+
+    /// Called at the start of every frame, after input was collected and before tools act on it.
+    fn begin_frame(&mut self, _ctx: &mut PluginContext) {}
+
+    /// Called every frame after the document is drawn, to draw overlays on top.
+    fn render(&mut self, _ctx: &mut PluginContext, _draw: &mut dyn Draw) {}
+
+    /// Called at the end of every frame.
+    fn end_frame(&mut self, _ctx: &mut PluginContext) {}
+
+    /// Called after `init` and whenever the canvas changes size, with the new size in pixels.
+    fn resize(&mut self, _ctx: &mut PluginContext, _width: f64, _height: f64) {}
+
+    /// Called once when the plugin is removed or the canvas shuts down.
+    fn destroy(&mut self) {}
+
+    /// Names of the tools the plugin adds to the toolbar. While one of them is selected the
+    /// built-in tools stay idle, and the plugin handles the input, usually in `begin_frame`.
+    fn tools(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The plugins installed in one canvas, called in the order they were added.
+#[derive(Default)]
+pub struct PluginHost {
+    plugins: Vec<Box<dyn Plugin>>,
+    /// Canvas size the plugins were last told about.
+    size: (f64, f64),
+    /// The selected plugin tool, as plugin name and tool name.
+    active_tool: Option<(String, String)>,
+}
+
+impl PluginHost {
+    pub fn new() -> Self {
+        PluginHost::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.plugins.iter().map(|plugin| plugin.name())
+    }
+
+    /// Every plugin tool, as plugin name and tool name, in toolbar order.
+    pub fn tools(&self) -> Vec<(String, String)> {
+        self.plugins
+            .iter()
+            .flat_map(|plugin| plugin.tools().into_iter().map(|tool| (plugin.name().to_owned(), tool)))
+            .collect()
+    }
+
+    pub fn active_tool(&self) -> Option<(&str, &str)> {
+        self.active_tool.as_ref().map(|(plugin, tool)| (plugin.as_str(), tool.as_str()))
+    }
+
+    /// Selects a plugin tool, or hands input back to the built-in tools with `None`.
+    pub fn set_active_tool(&mut self, tool: Option<(String, String)>) {
+        self.active_tool = tool;
+    }
+
+    pub fn add(&mut self, mut plugin: Box<dyn Plugin>, ctx: &mut PluginContext) {
+        plugin.init(ctx);
+        plugin.resize(ctx, ctx.viewport.width, ctx.viewport.height);
+        self.size = (ctx.viewport.width, ctx.viewport.height);
+        self.plugins.push(plugin);
+    }
+
+    /// Removes the plugin called `name`, calling its `destroy` hook. Returns whether one was
+    /// found.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let Some(index) = self.plugins.iter().position(|plugin| plugin.name() == name) else {
+            return false;
+        };
+        if self.active_tool.as_ref().is_some_and(|(plugin, _)| plugin == name) {
+            self.active_tool = None;
+        }
+        self.plugins.remove(index).destroy();
+        true
+    }
+
+    /// Runs `resize` if the canvas changed size since the last frame, then `begin_frame`.
+    pub fn begin_frame(&mut self, ctx: &mut PluginContext) {
+        let size = (ctx.viewport.width, ctx.viewport.height);
+        if size != self.size {
+            self.size = size;
+            self.each(ctx, |plugin, ctx| plugin.resize(ctx, size.0, size.1));
+        }
+        self.each(ctx, |plugin, ctx| plugin.begin_frame(ctx));
+    }
+
+    pub fn render(&mut self, ctx: &mut PluginContext, draw: &mut dyn Draw) {
+        self.each(ctx, |plugin, ctx| plugin.render(ctx, draw));
+    }
+
+    pub fn end_frame(&mut self, ctx: &mut PluginContext) {
+        self.each(ctx, |plugin, ctx| plugin.end_frame(ctx));
+    }
+
+    /// Runs `hook` for every plugin, telling each one whether its tool is selected.
+    fn each(&mut self, ctx: &mut PluginContext, mut hook: impl FnMut(&mut dyn Plugin, &mut PluginContext)) {
+        for plugin in &mut self.plugins {
+            ctx.active_tool = match &self.active_tool {
+                Some((owner, tool)) if owner == plugin.name() => Some(tool.clone()),
+                _ => None,
+            };
+            hook(plugin.as_mut(), ctx);
+        }
+        ctx.active_tool = None;
+    }
+
+    /// Calls every plugin's `destroy` hook and removes them all.
+    pub fn destroy(&mut self) {
+        for mut plugin in self.plugins.drain(..) {
+            plugin.destroy();
+        }
+    }
+}
+
+impl Drop for PluginHost {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
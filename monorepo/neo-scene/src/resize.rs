@@ -0,0 +1,118 @@
+//! Resizing a box by one of the eight handles around it, with the aspect ratio kept or from
+//! the centre if asked. Dragging a handle past the opposite edge flips the box over.
+
+use crate::geometry::{Point, Rect, Transform};
+
+/// A handle on the edge of a selection box.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Handle {
+    TopLeft,
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+}
+
+impl Handle {
+    pub const ALL: [Handle; 8] = [
+        Handle::TopLeft,
+        Handle::Top,
+        Handle::TopRight,
+        Handle::Right,
+        Handle::BottomRight,
+        Handle::Bottom,
+        Handle::BottomLeft,
+        Handle::Left,
+    ];
+
+    /// Which side the handle is on along each axis: -1 for left or top, 1 for right or bottom
+    /// and 0 for the middle, along which it doesn't move anything.
+    pub fn sides(self) -> (f64, f64) {
+        match self {
+            Handle::TopLeft => (-1.0, -1.0),
+            Handle::Top => (0.0, -1.0),
+            Handle::TopRight => (1.0, -1.0),
+            Handle::Right => (1.0, 0.0),
+            Handle::BottomRight => (1.0, 1.0),
+            Handle::Bottom => (0.0, 1.0),
+            Handle::BottomLeft => (-1.0, 1.0),
+            Handle::Left => (-1.0, 0.0),
+        }
+    }
+
+    /// Where the handle sits on `bounds`.
+    pub fn position(self, bounds: Rect) -> Point {
+        let (x, y) = self.sides();
+        bounds.center() + Point::new(x * bounds.width / 2.0, y * bounds.height / 2.0)
+    }
+}
+
+/// A scaling about a fixed point, negative along an axis that was flipped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scale {
+    pub anchor: Point,
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Scale {
+    pub fn apply(&self, point: Point) -> Point {
+        let offset = point - self.anchor;
+        self.anchor + Point::new(offset.x * self.x, offset.y * self.y)
+    }
+
+    /// The same scaling as a transform, for objects that keep one.
+    pub fn transform(&self) -> Transform {
+        Transform::scaling(self.x, self.y).about(self.anchor)
+    }
+}
+
+/// One drag of a handle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Resize {
+    pub handle: Handle,
+    /// Scales both axes by the same amount.
+    pub keep_aspect: bool,
+    /// Moves the opposite handle the other way too, so the centre stays put.
+    pub from_center: bool,
+    /// Smallest width and height the box shrinks to, in world units.
+    pub min_size: f64,
+}
+
+impl Resize {
+    /// How to scale what is in `bounds` for the handle to end up at `to`.
+    pub fn scale(&self, bounds: Rect, to: Point) -> Scale {
+        let (side_x, side_y) = self.handle.sides();
+        let (anchor_x, mut x) = self.axis(bounds.x, bounds.width, side_x, to.x);
+        let (anchor_y, mut y) = self.axis(bounds.y, bounds.height, side_y, to.y);
+        if self.keep_aspect {
+            // a corner follows whichever axis was dragged further, an edge handle scales the
+            // other axis about its middle
+            let amount = match (side_x == 0.0, side_y == 0.0) {
+                (true, _) => y.abs(),
+                (_, true) => x.abs(),
+                _ => x.abs().max(y.abs()),
+            };
+            x = amount.copysign(x);
+            y = amount.copysign(y);
+        }
+        Scale { anchor: Point::new(anchor_x, anchor_y), x, y }
+    }
+
+    /// The fixed point and the scale along one axis, for a box from `min` spanning `size`.
+    fn axis(&self, min: f64, size: f64, side: f64, to: f64) -> (f64, f64) {
+        let center = min + size / 2.0;
+        if side == 0.0 || size == 0.0 {
+            return (center, 1.0);
+        }
+        let anchor = if self.from_center { center } else { center - side * size / 2.0 };
+        let moving = center + side * size / 2.0;
+        let scale = (to - anchor) / (moving - anchor);
+        // the box can't be squashed thinner than the minimum, even on its way to flipping
+        let min_scale = self.min_size / size;
+        (anchor, if scale.abs() < min_scale { min_scale.copysign(scale) } else { scale })
+    }
+}
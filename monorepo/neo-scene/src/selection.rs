@@ -0,0 +1,205 @@
+//! Which objects a frontend has picked to work on. The selection lives next to the document
+//! rather than in it: every session selects its own objects and picking isn't an edit to undo.
+
+use std::collections::HashSet;
+
+use crate::{
+    document::{Document, Object},
+    geometry::{Point, Rect},
+    group,
+    id::ObjectId,
+    layer,
+};
+
+/// Selected objects, in the order they were picked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    ids: Vec<ObjectId>,
+    /// The group being edited, whose children are picked rather than the group itself.
+    entered: Option<ObjectId>,
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Selection::default()
+    }
+
+    pub fn ids(&self) -> &[ObjectId] {
+        &self.ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn contains(&self, id: ObjectId) -> bool {
+        self.ids.contains(&id)
+    }
+
+    /// The selected object when there is exactly one.
+    pub fn single(&self) -> Option<ObjectId> {
+        match self.ids[..] {
+            [id] => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.ids.clear();
+    }
+
+    /// Selects `id` and nothing else.
+    pub fn set(&mut self, id: ObjectId) {
+        self.ids.clear();
+        self.ids.push(id);
+    }
+
+    pub fn add(&mut self, id: ObjectId) {
+        if !self.contains(id) {
+            self.ids.push(id);
+        }
+    }
+
+    pub fn remove(&mut self, id: ObjectId) {
+        self.ids.retain(|selected| *selected != id);
+    }
+
+    /// Adds `id` if it isn't selected and removes it if it is, as Shift-click does.
+    pub fn toggle(&mut self, id: ObjectId) {
+        if self.contains(id) {
+            self.remove(id);
+        } else {
+            self.ids.push(id);
+        }
+    }
+
+    /// Forgets objects that were hidden or locked, which can't be worked on.
+    pub fn retain_pickable(&mut self, document: &Document) {
+        self.ids.retain(|id| layer::is_pickable(document, *id));
+    }
+
+    /// Forgets objects that are no longer in `document`, after an undo or opening a file.
+    pub fn retain_existing(&mut self, document: &Document) {
+        self.ids.retain(|id| document.contains(*id));
+        if self.entered.is_some_and(|group| document.group(group).is_none()) {
+            self.entered = None;
+        }
+    }
+
+    pub fn entered(&self) -> Option<ObjectId> {
+        self.entered
+    }
+
+    /// Starts editing inside `group`, or back at the top level for `None`.
+    pub fn enter(&mut self, group: Option<ObjectId>) {
+        self.entered = group;
+    }
+
+    /// What a click on `id` picks: the outermost group around it inside the entered group, or
+    /// `id` itself. Something outside the entered group is picked as if none were entered.
+    pub fn pick(&self, document: &Document, id: ObjectId) -> ObjectId {
+        let mut item = id;
+        for group in group::ancestors(document, id) {
+            if Some(group) == self.entered {
+                return item;
+            }
+            item = group;
+        }
+        item
+    }
+
+    /// What a marquee or lasso around `leaves` picks: like [`Selection::pick`], but a group only
+    /// once everything drawn in it is among them.
+    pub fn pick_enclosed(&self, document: &Document, leaves: &[ObjectId]) -> Vec<ObjectId> {
+        let enclosed: HashSet<ObjectId> = leaves.iter().copied().collect();
+        let mut seen = HashSet::new();
+        let mut picked = Vec::new();
+        for &leaf in leaves {
+            let item = self.pick(document, leaf);
+            if seen.insert(item) && (item == leaf || document.leaves(item).iter().all(|leaf| enclosed.contains(leaf))) {
+                picked.push(item);
+            }
+        }
+        picked
+    }
+
+    /// The objects drawing the selection, with groups opened up: what moving or resizing it
+    /// changes.
+    pub fn leaves(&self, document: &Document) -> Vec<ObjectId> {
+        let mut seen = HashSet::new();
+        self.ids.iter().flat_map(|id| document.leaves(*id)).filter(|leaf| seen.insert(*leaf)).collect()
+    }
+
+    /// Bounds of all the selected objects together: the box they are moved and resized by.
+    pub fn bounds(&self, document: &Document) -> Option<Rect> {
+        self.ids
+            .iter()
+            .filter_map(|id| document.bounds_of(*id))
+            .reduce(|a, b| a.union(&b))
+    }
+}
+
+impl Extend<ObjectId> for Selection {
+    fn extend<I: IntoIterator<Item = ObjectId>>(&mut self, ids: I) {
+        for id in ids {
+            self.add(id);
+        }
+    }
+}
+
+/// Objects lying entirely inside `rect`, back to front, for a marquee drag. Hidden and locked
+/// objects are left out.
+pub fn in_marquee(document: &Document, rect: Rect) -> Vec<ObjectId> {
+    document
+        .iter_in(rect)
+        .filter(|(id, _)| layer::is_pickable(document, *id))
+        .filter(|(_, object)| object.bounds().is_some_and(|bounds| encloses(rect, bounds)))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+/// Objects lying entirely inside the closed outline `lasso`, back to front. An object counts
+/// when every point of its geometry is inside, so a curved stroke can be picked out from
+/// between others whose boxes overlap it. Hidden and locked objects are left out.
+pub fn in_lasso(document: &Document, lasso: &[Point]) -> Vec<ObjectId> {
+    let Some(area) = Rect::from_points(lasso.iter().copied()) else {
+        return Vec::new();
+    };
+    document
+        .iter_in(area)
+        .filter(|(id, _)| layer::is_pickable(document, *id))
+        .filter(|(_, object)| object.bounds().is_some_and(|bounds| encloses(area, bounds)))
+        .filter(|(_, object)| outline(object).into_iter().all(|point| inside_polygon(lasso, point)))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+fn encloses(outer: Rect, inner: Rect) -> bool {
+    outer.x <= inner.x && outer.y <= inner.y && inner.max().x <= outer.max().x && inner.max().y <= outer.max().y
+}
+
+/// Points that are all inside a region exactly when the object is.
+fn outline(object: &Object) -> Vec<Point> {
+    match object {
+        Object::Shape(shape) => shape.world_transform().corners(shape.bounds).to_vec(),
+        Object::Stroke(stroke) => stroke.points.clone(),
+        Object::Connector(connector) => vec![connector.start.position, connector.end.position],
+        Object::Group(_) => Vec::new(),
+    }
+}
+
+/// Even-odd test: a ray from `point` crosses the outline an odd number of times if it's inside.
+fn inside_polygon(polygon: &[Point], point: Point) -> bool {
+    let mut inside = false;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        if (a.y > point.y) != (b.y > point.y) && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connector::Anchor,
+    font::TextStyle,
+    geometry::{Point, Rect, Transform},
+    layer::Layer,
+    style::Style,
+};
+
+/// Smallest scale a shape's box is reshaped by; below it a transform is left as it is.
+const MIN_SCALE: f64 = 1e-9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShapeKind {
+    Rectangle,
+    /// Inscribed in the shape's bounds; a circle when the bounds are square.
+    Ellipse,
+}
+
+/// A closed shape with optional label text.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Shape {
+    pub kind: ShapeKind,
+    /// The shape before its transform: its size, and where its centre is.
+    pub bounds: Rect,
+    /// Rotation, skew or flip, applied about the centre of `bounds`.
+    #[serde(default, skip_serializing_if = "Transform::is_identity")]
+    pub transform: Transform,
+    pub style: Style,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "TextStyle::is_default")]
+    pub text_style: TextStyle,
+    #[serde(default, skip_serializing_if = "Layer::is_default")]
+    pub layer: Layer,
+}
+
+impl Shape {
+    pub fn new(kind: ShapeKind, bounds: Rect, style: Style) -> Self {
+        Shape { kind, bounds, transform: Transform::IDENTITY, style, text: String::new(), text_style: TextStyle::default(), layer: Layer::default() }
+    }
+
+    /// Takes points in `bounds` to where they are drawn in the world.
+    pub fn world_transform(&self) -> Transform {
+        self.transform.about(self.bounds.center())
+    }
+
+    /// Axis-aligned bounds of the shape as drawn.
+    pub fn world_bounds(&self) -> Rect {
+        self.world_transform().bounds(self.bounds)
+    }
+
+    /// World position of a connector anchor, which turns with the shape.
+    pub fn anchor(&self, anchor: Anchor) -> Point {
+        self.world_transform().apply(anchor.position(self.bounds))
+    }
+
+    /// The side whose anchor is closest to `point`.
+    pub fn nearest_anchor(&self, point: Point) -> Anchor {
+        Anchor::SIDES.into_iter().min_by(|a, b| point.distance(self.anchor(*a)).total_cmp(&point.distance(self.anchor(*b)))).unwrap()
+    }
+
+    /// Applies `transform`, given in world space, on top of the shape's own. Any change of
+    /// size along the shape's own axes goes into `bounds`, so the transform only ever turns,
+    /// slants or flips and text keeps wrapping at the width the shape is drawn at.
+    pub fn apply_transform(&mut self, transform: Transform) {
+        let center = self.bounds.center();
+        let moved = transform.apply(self.world_transform().apply(center));
+        let m = self.transform.then(transform.linear());
+        // m = rotation * [sx k; 0 sy], so the first column's length is the scale along x
+        let sx = m.a.hypot(m.b);
+        let sy = (m.a * m.d - m.b * m.c) / sx.max(MIN_SCALE);
+        let (width, height) = (self.bounds.width * sx, self.bounds.height * sy.abs());
+        if sx < MIN_SCALE || sy.abs() < MIN_SCALE {
+            self.transform = Transform { e: 0.0, f: 0.0, ..m };
+        } else {
+            self.transform = Transform::scaling(1.0 / sx, 1.0 / sy.abs()).then(m.linear());
+            self.bounds = Rect::new(center.x - width / 2.0, center.y - height / 2.0, width, height);
+        }
+        self.bounds = self.bounds.translate(moved - self.bounds.center());
+    }
+
+    /// Whether `point` falls inside the shape's outline, grown by `tolerance` on every side.
+    pub fn hit_test(&self, point: Point, tolerance: f64) -> bool {
+        let Some(local) = self.world_transform().inverse() else {
+            return false;
+        };
+        let point = local.apply(point);
+        let bounds = self.bounds.expand(tolerance);
+        match self.kind {
+            ShapeKind::Rectangle => bounds.contains(point),
+            ShapeKind::Ellipse => {
+                let center = bounds.center();
+                let (rx, ry) = (bounds.width / 2.0, bounds.height / 2.0);
+                if rx <= 0.0 || ry <= 0.0 {
+                    return false;
+                }
+                let (dx, dy) = ((point.x - center.x) / rx, (point.y - center.y) / ry);
+                dx * dx + dy * dy <= 1.0
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Snapping for objects being dragged: to the edges and centres of other objects, to positions
+//! that repeat the spacing between neighbours, and otherwise to the grid.
+//!
+//! Everything here is in world units. Frontends pick the snapping distance in screen pixels and
+//! convert it, so snapping feels the same at every zoom.
+
+use crate::geometry::{Point, Rect};
+
+/// How close two lines must be to count as aligned after snapping, to allow for rounding.
+const EPSILON: f64 = 1e-6;
+
+/// A line showing what a drag snapped to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Guide {
+    /// Edges or centres lined up, drawn across every object on the line.
+    Alignment { from: Point, to: Point },
+    /// One of the equal gaps the dragged object was spaced by.
+    Gap { from: Point, to: Point },
+}
+
+impl Guide {
+    fn transpose(self) -> Guide {
+        match self {
+            Guide::Alignment { from, to } => Guide::Alignment { from: flip(from), to: flip(to) },
+            Guide::Gap { from, to } => Guide::Gap { from: flip(from), to: flip(to) },
+        }
+    }
+}
+
+/// How far to move what is being dragged so it snaps, and the guides to show for it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snap {
+    pub offset: Point,
+    pub guides: Vec<Guide>,
+}
+
+/// What dragged objects snap to.
+#[derive(Clone, Copy, Debug)]
+pub struct Snapper<'a> {
+    /// Bounds of the other objects, usually just the visible ones.
+    pub targets: &'a [Rect],
+    /// Grid spacing, used when there is no object to snap to.
+    pub grid: Option<f64>,
+    /// How far something may move to snap.
+    pub distance: f64,
+}
+
+impl Snapper<'_> {
+    /// Snaps a moving box by its edges and centre, or by its top left corner to the grid.
+    pub fn snap_rect(&self, rect: Rect) -> Snap {
+        self.snap(rect, true)
+    }
+
+    /// Snaps a single point, like a corner being resized, without spacing.
+    pub fn snap_point(&self, point: Point) -> Snap {
+        self.snap(Rect::new(point.x, point.y, 0.0, 0.0), false)
+    }
+
+    fn snap(&self, rect: Rect, spacing: bool) -> Snap {
+        // the other axis is worked out the same way with x and y swapped
+        let transposed: Vec<Rect> = self.targets.iter().map(|target| transpose(*target)).collect();
+        let (dx, x_snapped) = self.snap_x(rect, self.targets, spacing);
+        let (dy, y_snapped) = self.snap_x(transpose(rect), &transposed, spacing);
+        let offset = Point::new(dx, dy);
+        let moved = rect.translate(offset);
+        let mut guides = Vec::new();
+        if x_snapped {
+            guides.extend(guides_x(moved, self.targets, spacing));
+        }
+        if y_snapped {
+            guides.extend(guides_x(transpose(moved), &transposed, spacing).into_iter().map(Guide::transpose));
+        }
+        Snap { offset, guides }
+    }
+
+    /// How far to move `rect` along x, and whether that lines it up with other objects rather
+    /// than just the grid.
+    fn snap_x(&self, rect: Rect, targets: &[Rect], spacing: bool) -> (f64, bool) {
+        let mut best: Option<f64> = None;
+        let mut consider = |delta: f64| {
+            if delta.abs() <= self.distance && best.is_none_or(|best| delta.abs() < best.abs()) {
+                best = Some(delta);
+            }
+        };
+        for target in targets {
+            for from in vertical_lines(rect) {
+                for to in vertical_lines(*target) {
+                    consider(to - from);
+                }
+            }
+        }
+        if spacing {
+            for (left, _) in spaced_positions(rect, targets) {
+                consider(left - rect.x);
+            }
+        }
+        if let Some(delta) = best {
+            return (delta, true);
+        }
+        // the grid is everywhere, so objects nearby win over it
+        let delta = self.grid.map_or(0.0, |grid| (rect.x / grid).round() * grid - rect.x);
+        (if delta.abs() <= self.distance { delta } else { 0.0 }, false)
+    }
+}
+
+/// The guides for a box that snapped along x to where it is now.
+fn guides_x(moved: Rect, targets: &[Rect], spacing: bool) -> Vec<Guide> {
+    // one guide per line, spanning every object on it
+    let mut lines: Vec<(f64, f64, f64)> = Vec::new();
+    for target in targets {
+        for x in vertical_lines(moved) {
+            if !vertical_lines(*target).iter().any(|line| (line - x).abs() < EPSILON) {
+                continue;
+            }
+            let (top, bottom) = (moved.y.min(target.y), moved.max().y.max(target.max().y));
+            match lines.iter_mut().find(|(line, _, _)| (line - x).abs() < EPSILON) {
+                Some((_, from, to)) => (*from, *to) = (from.min(top), to.max(bottom)),
+                None => lines.push((x, top, bottom)),
+            }
+        }
+    }
+    let mut guides: Vec<Guide> = lines
+        .into_iter()
+        .map(|(x, top, bottom)| Guide::Alignment { from: Point::new(x, top), to: Point::new(x, bottom) })
+        .collect();
+    if spacing {
+        let y = moved.center().y;
+        let gaps = spaced_positions(moved, targets).into_iter().find(|(left, _)| (left - moved.x).abs() < EPSILON);
+        for (from, to) in gaps.map(|(_, gaps)| gaps).unwrap_or_default() {
+            guides.push(Guide::Gap { from: Point::new(from, y), to: Point::new(to, y) });
+        }
+    }
+    guides
+}
+
+/// Left edge, centre and right edge.
+fn vertical_lines(rect: Rect) -> [f64; 3] {
+    [rect.x, rect.x + rect.width / 2.0, rect.x + rect.width]
+}
+
+/// Left edges `rect` could move to so that it sits evenly between two neighbours in its row, or
+/// continues the spacing of two of them. Each comes with the gaps that would then be equal, as
+/// ranges of x.
+fn spaced_positions(rect: Rect, targets: &[Rect]) -> Vec<(f64, Vec<(f64, f64)>)> {
+    let mut row: Vec<Rect> = targets.iter().copied().filter(|target| rect.y < target.max().y && target.y < rect.max().y).collect();
+    row.sort_by(|a, b| a.x.total_cmp(&b.x));
+    let mut positions = Vec::new();
+    for pair in row.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let gap = b.x - a.max().x;
+        if gap <= 0.0 {
+            continue;
+        }
+        // in the middle
+        let room = gap - rect.width;
+        if room >= 0.0 {
+            let left = a.max().x + room / 2.0;
+            positions.push((left, vec![(a.max().x, left), (left + rect.width, b.x)]));
+        }
+        // after the pair, then before it
+        let left = b.max().x + gap;
+        positions.push((left, vec![(a.max().x, b.x), (b.max().x, left)]));
+        let left = a.x - gap - rect.width;
+        positions.push((left, vec![(left + rect.width, a.x), (a.max().x, b.x)]));
+    }
+    positions
+}
+
+fn flip(point: Point) -> Point {
+    Point::new(point.y, point.x)
+}
+
+fn transpose(rect: Rect) -> Rect {
+    Rect::new(rect.y, rect.x, rect.height, rect.width)
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use rstar::{primitives::GeomWithData, primitives::Rectangle, RTree, AABB};
+
+use crate::{
+    geometry::{Point, Rect},
+    id::ObjectId,
+};
+
+type Entry = GeomWithData<Rectangle<[f64; 2]>, ObjectId>;
+
+fn entry(id: ObjectId, bounds: Rect) -> Entry {
+    let min = bounds.min();
+    let max = bounds.max();
+    GeomWithData::new(Rectangle::from_corners([min.x, min.y], [max.x, max.y]), id)
+}
+
+fn envelope(rect: Rect) -> AABB<[f64; 2]> {
+    let min = rect.min();
+    let max = rect.max();
+    AABB::from_corners([min.x, min.y], [max.x, max.y])
+}
+
+/// R-tree over the world-space bounds of every object in a document.
+///
+/// This is only the broad phase: queries return ids in no particular order, and callers are
+/// expected to sort by draw order and run exact hit tests on the candidates.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SpatialIndex {
+    tree: RTree<Entry>,
+    /// The bounds each id was inserted with, needed to find its entry again.
+    bounds: HashMap<ObjectId, Rect>,
+}
+
+impl SpatialIndex {
+    pub fn bulk_load(objects: impl IntoIterator<Item = (ObjectId, Rect)>) -> Self {
+        let bounds: HashMap<ObjectId, Rect> = objects.into_iter().collect();
+        let tree = RTree::bulk_load(bounds.iter().map(|(id, rect)| entry(*id, *rect)).collect());
+        SpatialIndex { tree, bounds }
+    }
+
+    /// Moves an object's entry to its new bounds. `None` drops it from the index, which is
+    /// how objects without geometry (an empty stroke) are handled.
+    pub fn update(&mut self, id: ObjectId, bounds: Option<Rect>) {
+        if self.bounds.get(&id).copied() == bounds {
+            return;
+        }
+        self.remove(id);
+        if let Some(bounds) = bounds {
+            self.tree.insert(entry(id, bounds));
+            self.bounds.insert(id, bounds);
+        }
+    }
+
+    pub fn remove(&mut self, id: ObjectId) {
+        if let Some(old) = self.bounds.remove(&id) {
+            self.tree.remove(&entry(id, old));
+        }
+    }
+
+    /// Every object whose bounds intersect `rect`.
+    pub fn in_rect(&self, rect: Rect) -> impl Iterator<Item = ObjectId> + '_ {
+        self.tree.locate_in_envelope_intersecting(&envelope(rect)).map(|e| e.data)
+    }
+
+    /// Objects ordered by the distance from `point` to their bounds, nearest first.
+    pub fn nearest(&self, point: Point) -> impl Iterator<Item = (ObjectId, f64)> + '_ {
+        self.tree
+            .nearest_neighbor_iter_with_distance_2(&[point.x, point.y])
+            .map(|(e, distance_2)| (e.data, distance_2.sqrt()))
+    }
+}
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geometry::{Point, Rect},
+    layer::Layer,
+    style::Style,
+};
+
+/// Samples per segment when a smooth stroke is flattened for drawing and hit testing.
+const SPLINE_STEPS: usize = 8;
+
+/// A freehand line in world space.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stroke {
+    pub points: Vec<Point>,
+    /// Line width at each point as a multiple of `style.stroke_width`, from pen pressure and
+    /// tilt. Empty for a line of constant width.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub widths: Vec<f64>,
+    /// Drawn as a Catmull-Rom spline through the points instead of straight segments.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub smooth: bool,
+    pub style: Style,
+    #[serde(default, skip_serializing_if = "Layer::is_default")]
+    pub layer: Layer,
+}
+
+impl Stroke {
+    pub fn new(style: Style) -> Self {
+        Stroke { points: Vec::new(), widths: Vec::new(), smooth: false, style, layer: Layer::default() }
+    }
+
+    /// Appends a point drawn at `width` times the style's width.
+    pub fn push(&mut self, point: Point, width: f64) {
+        // widths are only kept once one of them isn't 1, then for every point
+        if width != 1.0 || !self.widths.is_empty() {
+            self.widths.resize(self.points.len(), 1.0);
+            self.widths.push(width);
+        }
+        self.points.push(point);
+    }
+
+    /// Line width at the `index`th point, in world units.
+    pub fn width_at(&self, index: usize) -> f64 {
+        self.style.stroke_width * self.widths.get(index).copied().unwrap_or(1.0)
+    }
+
+    /// The line as it is drawn: positions with their width in world units, close enough
+    /// together that renderers can join them with straight segments.
+    pub fn samples(&self) -> Vec<(Point, f64)> {
+        let points = &self.points;
+        if !self.smooth || points.len() < 3 {
+            return points.iter().enumerate().map(|(i, p)| (*p, self.width_at(i))).collect();
+        }
+        let last = points.len() - 1;
+        let mut samples = Vec::with_capacity(last * SPLINE_STEPS + 1);
+        for i in 0..last {
+            let p0 = points[i.saturating_sub(1)];
+            let (p1, p2) = (points[i], points[i + 1]);
+            let p3 = points[(i + 2).min(last)];
+            let (w1, w2) = (self.width_at(i), self.width_at(i + 1));
+            for step in 0..SPLINE_STEPS {
+                let t = step as f64 / SPLINE_STEPS as f64;
+                samples.push((catmull_rom(p0, p1, p2, p3, t), w1 + (w2 - w1) * t));
+            }
+        }
+        samples.push((points[last], self.width_at(last)));
+        samples
+    }
+
+    /// Whether `point` is within `tolerance` of the painted line.
+    pub fn hit_test(&self, point: Point, tolerance: f64) -> bool {
+        match self.samples().as_slice() {
+            [] => false,
+            [(only, width)] => point.distance(*only) <= width / 2.0 + tolerance,
+            samples => samples.windows(2).any(|s| {
+                let reach = s[0].1.max(s[1].1) / 2.0 + tolerance;
+                point.distance_to_segment(s[0].0, s[1].0) <= reach
+            }),
+        }
+    }
+
+    /// Bounds of the line grown by half its widest width, or `None` while empty.
+    pub fn bounds(&self) -> Option<Rect> {
+        let samples = self.samples();
+        let widest = samples.iter().map(|(_, width)| *width).fold(0.0, f64::max);
+        Rect::from_points(samples.iter().map(|(p, _)| *p)).map(|r| r.expand(widest / 2.0))
+    }
+
+    /// Drops points that change the line by no more than `tolerance` world units, using
+    /// Ramer-Douglas-Peucker. Width changes count too, so pressure detail survives.
+    pub fn simplify(&mut self, tolerance: f64) {
+        let count = self.points.len();
+        if count < 3 {
+            return;
+        }
+        let mut keep = vec![false; count];
+        keep[0] = true;
+        keep[count - 1] = true;
+        let mut spans = vec![(0, count - 1)];
+        while let Some((first, last)) = spans.pop() {
+            let (a, b) = (self.points[first], self.points[last]);
+            let (wa, wb) = (self.width_at(first), self.width_at(last));
+            let mut worst = (0.0, first);
+            for i in first + 1..last {
+                let t = (i - first) as f64 / (last - first) as f64;
+                // half the width change, since each edge of the line moves by that much
+                let width_error = (self.width_at(i) - (wa + (wb - wa) * t)).abs() / 2.0;
+                let error = self.points[i].distance_to_segment(a, b).max(width_error);
+                if error > worst.0 {
+                    worst = (error, i);
+                }
+            }
+            if worst.0 > tolerance {
+                keep[worst.1] = true;
+                spans.push((first, worst.1));
+                spans.push((worst.1, last));
+            }
+        }
+
+        let mut kept = keep.iter();
+        self.points.retain(|_| *kept.next().unwrap());
+        if !self.widths.is_empty() {
+            let mut kept = keep.iter();
+            self.widths.retain(|_| *kept.next().unwrap_or(&false));
+        }
+    }
+
+    /// Cuts away the part of the line within `radius` of `center`, as it is drawn: along the
+    /// [samples](Stroke::samples) of a smooth line, and widened by half the width there, the
+    /// same reach [`Stroke::hit_test`] uses. Returns the pieces left over, which may be none,
+    /// or `None` if the circle doesn't touch the line.
+    pub fn erase(&self, center: Point, radius: f64) -> Option<Vec<Stroke>> {
+        let samples = self.samples();
+        let factor = |width: f64| if self.style.stroke_width > 0.0 { width / self.style.stroke_width } else { 1.0 };
+
+        let ((first, first_width), rest) = samples.split_first()?;
+        let mut cut = first.distance(center) < radius + first_width / 2.0;
+        let mut pieces = Vec::new();
+        let mut piece = self.empty_piece();
+        if !cut {
+            piece.push(*first, factor(*first_width));
+        }
+        for (i, (b, b_width)) in rest.iter().enumerate().map(|(i, b)| (i + 1, *b)) {
+            let (a, a_width) = samples[i - 1];
+            let lerp = |t: f64| (a + (b - a) * t, factor(a_width + (b_width - a_width) * t));
+            let reach = radius + a_width.max(b_width) / 2.0;
+            let Some((t0, t1)) = segment_in_circle(a, b, center, reach) else {
+                // starting again right at the edge of the circle
+                if piece.points.is_empty() {
+                    piece.push(a, factor(a_width));
+                }
+                piece.push(b, factor(b_width));
+                continue;
+            };
+            cut = true;
+            if t0 > 0.0 {
+                let (point, width) = lerp(t0);
+                piece.push(point, width);
+            }
+            let finished = std::mem::replace(&mut piece, self.empty_piece());
+            if finished.points.len() > 1 {
+                pieces.push(finished);
+            }
+            if t1 < 1.0 {
+                let (point, width) = lerp(t1);
+                piece.push(point, width);
+                piece.push(b, factor(b_width));
+            }
+        }
+        if piece.points.len() > 1 {
+            pieces.push(piece);
+        }
+        cut.then_some(pieces)
+    }
+
+    /// A stroke drawn like this one but without any points, for building pieces of it. The
+    /// pieces keep its name and flags. They are cut from the samples, which already follow any
+    /// smoothing, so they are drawn as they are.
+    fn empty_piece(&self) -> Stroke {
+        Stroke { points: Vec::new(), widths: Vec::new(), smooth: false, style: self.style, layer: self.layer.clone() }
+    }
+}
+
+/// Part of the segment `a`-`b` inside the circle, as a range of `t` along it, if any.
+fn segment_in_circle(a: Point, b: Point, center: Point, radius: f64) -> Option<(f64, f64)> {
+    let (d, f) = (b - a, a - center);
+    let qa = d.x * d.x + d.y * d.y;
+    let qb = 2.0 * (f.x * d.x + f.y * d.y);
+    let qc = f.x * f.x + f.y * f.y - radius * radius;
+    if qa == 0.0 {
+        return (qc < 0.0).then_some((0.0, 1.0));
+    }
+    let discriminant = qb * qb - 4.0 * qa * qc;
+    if discriminant <= 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let t0 = ((-qb - root) / (2.0 * qa)).max(0.0);
+    let t1 = ((-qb + root) / (2.0 * qa)).min(1.0);
+    (t0 < t1).then_some((t0, t1))
+}
+
+/// Point at `t` on the uniform Catmull-Rom segment from `p1` to `p2`.
+fn catmull_rom(p0: Point, p1: Point, p2: Point, p3: Point, t: f64) -> Point {
+    let (t2, t3) = (t * t, t * t * t);
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+/// RGBA color with every channel in `0.0..=1.0`, the same convention macroquad uses.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+    pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// CSS `rgba()` string, for the canvas 2D context in the browser builds.
+    pub fn to_css(&self) -> String {
+        format!(
+            "rgba({},{},{},{})",
+            (self.r * 255.0) as u8,
+            (self.g * 255.0) as u8,
+            (self.b * 255.0) as u8,
+            self.a
+        )
+    }
+}
+
+/// How an object is painted. Freehand strokes only use the stroke half.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+    pub fill: Color,
+    pub stroke: Color,
+    pub stroke_width: f64,
+}
+
+impl Style {
+    pub fn filled(fill: Color) -> Self {
+        Style { fill, stroke: Color::TRANSPARENT, stroke_width: 0.0 }
+    }
+
+    pub fn stroked(stroke: Color, stroke_width: f64) -> Self {
+        Style { fill: Color::TRANSPARENT, stroke, stroke_width }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style::stroked(Color::BLACK, 1.0)
+    }
+}
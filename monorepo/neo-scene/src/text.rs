@@ -0,0 +1,215 @@
+//! Editing and wrapping shape text, shared by the frontends.
+//!
+//! Offsets are byte offsets into the text and always sit between grapheme clusters, so a caret
+//! never lands inside an emoji or between a letter and its accent. Lines are broken with the
+//! Unicode line breaking algorithm; how wide a piece of text is depends on the frontend's fonts,
+//! so it is passed in as a `measure` function.
+
+use std::ops::Range;
+
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Characters that end a line by themselves.
+const NEWLINES: [char; 7] = ['\n', '\r', '\u{b}', '\u{c}', '\u{85}', '\u{2028}', '\u{2029}'];
+
+/// One line of wrapped text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    /// Where the line sits in the text, without the newline ending it. Spaces before a wrap
+    /// stay at the end of the line they follow.
+    pub range: Range<usize>,
+}
+
+/// Breaks `text` into lines no wider than `max_width`. Newlines always start a new line, so
+/// blank lines are kept and there is a line for a caret after a trailing newline. A word too
+/// long for a line of its own is split between graphemes.
+pub fn wrap(text: &str, max_width: f32, measure: impl Fn(&str) -> f32) -> Vec<Line> {
+    let content_end = |range: Range<usize>| range.start + text[range].trim_end().len();
+    let mut lines = Vec::new();
+    let mut start = 0;
+    // the last break opportunity that still fits on the current line
+    let mut end = 0;
+    for (position, opportunity) in linebreaks(text) {
+        if end > start && measure(&text[start..content_end(start..position)]) > max_width {
+            lines.push(Line { range: start..end });
+            start = end;
+        }
+        loop {
+            let content = content_end(start..position);
+            if start == content || measure(&text[start..content]) <= max_width {
+                break;
+            }
+            let split = longest_fit(text, start..content, max_width, &measure);
+            // a single grapheme wider than the line has nowhere else to go
+            if split == content {
+                break;
+            }
+            lines.push(Line { range: start..split });
+            start = split;
+        }
+        end = position;
+        if opportunity == BreakOpportunity::Mandatory {
+            let line_end = start + text[start..position].trim_end_matches(NEWLINES).len();
+            lines.push(Line { range: start..line_end });
+            start = position;
+        }
+    }
+    if lines.is_empty() || text.ends_with(NEWLINES) {
+        lines.push(Line { range: text.len()..text.len() });
+    }
+    lines
+}
+
+/// The end of the longest run of graphemes from the start of `range` that fits in `max_width`,
+/// but at least one grapheme so wrapping always moves on.
+fn longest_fit(text: &str, range: Range<usize>, max_width: f32, measure: &impl Fn(&str) -> f32) -> usize {
+    let mut ends = text[range.clone()].grapheme_indices(true).map(|(i, g)| range.start + i + g.len());
+    let mut fit = ends.next().unwrap_or(range.end);
+    for end in ends {
+        if measure(&text[range.start..end]) > max_width {
+            break;
+        }
+        fit = end;
+    }
+    fit
+}
+
+/// Index of the line `offset` is on. An offset at a wrap belongs to the line starting there.
+pub fn line_at(lines: &[Line], offset: usize) -> usize {
+    lines.iter().rposition(|line| line.range.start <= offset).unwrap_or(0)
+}
+
+/// The last caret position on a line. That's the line's end, except at a wrap, where the end
+/// is shown at the start of the next line instead, so it stops one grapheme short.
+pub fn line_end(text: &str, lines: &[Line], index: usize) -> usize {
+    let range = &lines[index].range;
+    let wrapped = lines.get(index + 1).is_some_and(|next| next.range.start == range.end);
+    if wrapped {
+        previous_grapheme(text, range.end).max(range.start)
+    } else {
+        range.end
+    }
+}
+
+/// The caret position on a line closest to `x`, measured from the line's left edge.
+pub fn offset_at_x(text: &str, lines: &[Line], index: usize, x: f32, measure: impl Fn(&str) -> f32) -> usize {
+    let start = lines[index].range.start;
+    let end = line_end(text, lines, index);
+    let boundaries = text[start..end].grapheme_indices(true).map(|(i, g)| start + i + g.len());
+    std::iter::once(start)
+        .chain(boundaries)
+        .map(|offset| (offset, (measure(&text[start..offset]) - x).abs()))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(start, |(offset, _)| offset)
+}
+
+pub fn previous_grapheme(text: &str, offset: usize) -> usize {
+    text[..offset].grapheme_indices(true).next_back().map_or(0, |(i, _)| i)
+}
+
+pub fn next_grapheme(text: &str, offset: usize) -> usize {
+    text[offset..].graphemes(true).next().map_or(text.len(), |g| offset + g.len())
+}
+
+/// The start of the word before `offset`, skipping spaces and punctuation.
+pub fn previous_word(text: &str, offset: usize) -> usize {
+    text.unicode_word_indices().map(|(i, _)| i).take_while(|i| *i < offset).last().unwrap_or(0)
+}
+
+/// The end of the word after `offset`, skipping spaces and punctuation.
+pub fn next_word(text: &str, offset: usize) -> usize {
+    text.unicode_word_indices().map(|(i, word)| i + word.len()).find(|end| *end > offset).unwrap_or(text.len())
+}
+
+/// A caret in a piece of text, and the selection it makes with the anchor. When nothing is
+/// selected, the anchor is at the caret.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextCursor {
+    pub anchor: usize,
+    pub caret: usize,
+}
+
+impl TextCursor {
+    pub fn at(offset: usize) -> Self {
+        TextCursor { anchor: offset, caret: offset }
+    }
+
+    /// The selected bytes, first to last.
+    pub fn range(&self) -> Range<usize> {
+        self.anchor.min(self.caret)..self.anchor.max(self.caret)
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.anchor != self.caret
+    }
+
+    pub fn selected<'a>(&self, text: &'a str) -> &'a str {
+        &text[self.range()]
+    }
+
+    /// Moves the caret, taking the anchor along unless the selection is being extended.
+    pub fn move_to(&mut self, offset: usize, extend: bool) {
+        self.caret = offset;
+        if !extend {
+            self.anchor = offset;
+        }
+    }
+
+    pub fn select_all(&mut self, text: &str) {
+        self.anchor = 0;
+        self.caret = text.len();
+    }
+
+    /// Pulls both ends back inside `text` after it was changed elsewhere, e.g. by a plugin.
+    pub fn clamp(&mut self, text: &str) {
+        let clamp = |mut offset: usize| {
+            offset = offset.min(text.len());
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            offset
+        };
+        self.anchor = clamp(self.anchor);
+        self.caret = clamp(self.caret);
+    }
+
+    /// Replaces the selection with `insert` and puts the caret after it.
+    pub fn insert(&mut self, text: &mut String, insert: &str) {
+        let range = self.range();
+        text.replace_range(range.clone(), insert);
+        *self = TextCursor::at(range.start + insert.len());
+    }
+
+    /// Deletes the selection, or else everything between the caret and `offset`. Returns
+    /// whether anything was deleted.
+    pub fn delete_to(&mut self, text: &mut String, offset: usize) -> bool {
+        if !self.has_selection() {
+            self.anchor = offset;
+        }
+        let deleted = self.has_selection();
+        self.insert(text, "");
+        deleted
+    }
+
+    /// `text` as it looks while `composition` is being typed: the selection replaced by the
+    /// composed text. Returns the shown text and the range the composition takes up in it.
+    pub fn preview(&self, text: &str, composition: &Composition) -> (String, Range<usize>) {
+        let range = self.range();
+        let mut shown = String::with_capacity(text.len() + composition.text.len());
+        shown.push_str(&text[..range.start]);
+        shown.push_str(&composition.text);
+        shown.push_str(&text[range.end..]);
+        (shown, range.start..range.start + composition.text.len())
+    }
+}
+
+/// Text an input method is still composing, like kana waiting to be converted to kanji or a
+/// dead key waiting for its letter. It shows at the caret in place of the selection, but only
+/// becomes part of the text once the input method commits it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Composition {
+    pub text: String,
+    /// The input method's own caret, as a byte offset into `text`.
+    pub caret: usize,
+}
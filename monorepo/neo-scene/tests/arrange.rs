@@ -0,0 +1,106 @@
+use neo_scene::arrange::{self, Alignment, Axis};
+use neo_scene::history::History;
+use neo_scene::{Color, Document, ObjectId, Rect, Shape, ShapeKind, Style};
+
+fn boxes(document: &mut Document, rects: &[Rect]) -> Vec<ObjectId> {
+    rects
+        .iter()
+        .map(|rect| document.push(Shape::new(ShapeKind::Rectangle, *rect, Style::filled(Color::BLACK))))
+        .collect()
+}
+
+fn bounds(document: &Document, ids: &[ObjectId]) -> Vec<Rect> {
+    ids.iter().map(|id| document.shape(*id).unwrap().bounds).collect()
+}
+
+#[test]
+fn aligns_to_the_edges_and_centres_of_the_whole_selection() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let ids = boxes(&mut document, &[Rect::new(0.0, 0.0, 10.0, 10.0), Rect::new(30.0, 50.0, 30.0, 20.0)]);
+
+    assert!(arrange::align(&mut document, &mut history, &ids, Alignment::Right));
+    assert_eq!(bounds(&document, &ids).iter().map(|b| b.max().x).collect::<Vec<_>>(), [60.0, 60.0]);
+    assert!(arrange::align(&mut document, &mut history, &ids, Alignment::Middle));
+    // the bounds run from 0 to 70, so both centre on 35
+    assert_eq!(bounds(&document, &ids).iter().map(|b| b.center().y).collect::<Vec<_>>(), [35.0, 35.0]);
+    assert!(!arrange::align(&mut document, &mut history, &ids, Alignment::Middle));
+    assert!(!arrange::align(&mut document, &mut history, &ids[..1], Alignment::Left));
+}
+
+#[test]
+fn distributes_with_equal_gaps_between_the_outermost() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let ids = boxes(
+        &mut document,
+        &[Rect::new(100.0, 0.0, 20.0, 10.0), Rect::new(0.0, 0.0, 10.0, 10.0), Rect::new(30.0, 5.0, 40.0, 10.0)],
+    );
+
+    assert!(arrange::distribute(&mut document, &mut history, &ids, Axis::Horizontal));
+    // 120 wide, 70 of it boxes, so 25 between each
+    let xs: Vec<f64> = bounds(&document, &ids).iter().map(|b| b.x).collect();
+    assert_eq!(xs, [100.0, 0.0, 35.0]);
+    assert_eq!(bounds(&document, &ids)[2].y, 5.0);
+    assert!(!arrange::distribute(&mut document, &mut history, &ids[..2], Axis::Vertical));
+}
+
+#[test]
+fn tidies_into_a_grid_in_reading_order() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let ids = boxes(
+        &mut document,
+        &[
+            Rect::new(10.0, 10.0, 20.0, 20.0),
+            Rect::new(200.0, 15.0, 10.0, 30.0),
+            Rect::new(50.0, 300.0, 10.0, 10.0),
+            Rect::new(0.0, 400.0, 10.0, 10.0),
+        ],
+    );
+
+    assert!(arrange::tidy(&mut document, &mut history, &ids, 5.0));
+    let mins: Vec<(f64, f64)> = bounds(&document, &ids).iter().map(|b| (b.x, b.y)).collect();
+    // two columns 20 and 10 wide, two rows 30 and 10 high, starting at the old top left; the
+    // last box is further left than the one before it, so it comes first in the second row
+    assert_eq!(mins, [(0.0, 10.0), (25.0, 10.0), (25.0, 45.0), (0.0, 45.0)]);
+}
+
+#[test]
+fn locked_and_hidden_objects_stay_where_they_are() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let rects = [
+        Rect::new(0.0, 0.0, 10.0, 10.0),
+        Rect::new(30.0, 50.0, 30.0, 20.0),
+        Rect::new(100.0, 20.0, 10.0, 10.0),
+    ];
+    let ids = boxes(&mut document, &rects);
+    document.get_mut(ids[0]).unwrap().layer_mut().locked = true;
+    document.get_mut(ids[2]).unwrap().layer_mut().hidden = true;
+
+    // only one object is left to move, and that isn't enough to line up
+    assert!(!arrange::align(&mut document, &mut history, &ids, Alignment::Left));
+    assert!(!arrange::distribute(&mut document, &mut history, &ids, Axis::Horizontal));
+    assert!(!arrange::tidy(&mut document, &mut history, &ids, 20.0));
+    assert_eq!(bounds(&document, &ids), rects);
+
+    document.get_mut(ids[2]).unwrap().layer_mut().hidden = false;
+    assert!(arrange::align(&mut document, &mut history, &ids, Alignment::Left));
+    assert_eq!(bounds(&document, &ids), [rects[0], rects[1], Rect::new(30.0, 20.0, 10.0, 10.0)]);
+}
+
+#[test]
+fn each_command_is_one_undo_step() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let ids = boxes(&mut document, &[Rect::new(0.0, 0.0, 10.0, 10.0), Rect::new(30.0, 50.0, 30.0, 20.0)]);
+    let before = bounds(&document, &ids);
+
+    assert!(arrange::align(&mut document, &mut history, &ids, Alignment::Left));
+    history.commit(&document);
+    assert_eq!(document.shape(ids[1]).unwrap().bounds.x, 0.0);
+    assert!(history.undo(&mut document));
+    assert_eq!(bounds(&document, &ids), before);
+    assert!(!history.can_undo());
+}
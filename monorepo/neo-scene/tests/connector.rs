@@ -0,0 +1,86 @@
+use neo_scene::format::{self, NeoFile};
+use neo_scene::history::History;
+use neo_scene::{
+    Anchor, Color, Connector, Document, Endpoint, ObjectId, Point, Rect, Routing, Shape, ShapeKind, Style,
+};
+
+fn square(x: f64, y: f64) -> Shape {
+    Shape::new(ShapeKind::Rectangle, Rect::new(x, y, 10.0, 10.0), Style::filled(Color::BLACK))
+}
+
+/// Two squares side by side, linked from the right of the first to the left of the second.
+fn linked() -> (Document, ObjectId, ObjectId, ObjectId) {
+    let mut document = Document::new();
+    let a = document.push(square(0.0, 0.0));
+    let b = document.push(square(100.0, 0.0));
+    let start = Endpoint::bound(a, Anchor::Right, document.shape(a).unwrap().bounds);
+    let end = Endpoint::bound(b, Anchor::Left, document.shape(b).unwrap().bounds);
+    let connector = document.push(Connector::new(start, end, Style::stroked(Color::BLACK, 2.0)));
+    (document, a, b, connector)
+}
+
+#[test]
+fn connectors_follow_shapes_that_move_or_resize() {
+    let (mut document, a, b, id) = linked();
+    document.shape_mut(b).unwrap().bounds = Rect::new(200.0, 50.0, 10.0, 10.0);
+    document.get_mut(a).unwrap().translate(Point::new(0.0, 20.0));
+    document.shape_mut(a).unwrap().bounds.height = 30.0;
+
+    let connector = document.connector(id).unwrap();
+    assert_eq!(connector.start.position, Point::new(10.0, 35.0));
+    assert_eq!(connector.end.position, Point::new(200.0, 55.0));
+    // the spatial index sees the new route too
+    assert_eq!(document.topmost_at(Point::new(100.0, 45.0), 1.0), Some(id));
+}
+
+#[test]
+fn elbows_are_axis_aligned_and_curves_leave_square_to_the_shape() {
+    let (mut document, _, b, id) = linked();
+    document.shape_mut(b).unwrap().bounds.y = 40.0;
+
+    document.connector_mut(id).unwrap().routing = Routing::Elbow;
+    let path = document.connector(id).unwrap().path();
+    assert_eq!(path.first(), Some(&Point::new(10.0, 5.0)));
+    assert_eq!(path.last(), Some(&Point::new(100.0, 45.0)));
+    assert!(path.windows(2).all(|s| s[0].x == s[1].x || s[0].y == s[1].y));
+
+    document.connector_mut(id).unwrap().routing = Routing::Curved;
+    let path = document.connector(id).unwrap().path();
+    assert!(path[1].x > path[0].x && (path[1].y - path[0].y).abs() < path[1].x - path[0].x);
+    assert_eq!(path.last(), Some(&Point::new(100.0, 45.0)));
+}
+
+#[test]
+fn deleting_a_shape_leaves_a_free_end_until_undo_brings_it_back() {
+    let (mut document, _, b, id) = linked();
+    let mut history = History::default();
+    history.track(&document, b);
+    document.remove(b);
+    history.commit(&document);
+
+    // the end stays where it was and moves with the connector now
+    assert_eq!(document.connector(id).unwrap().end.position, Point::new(100.0, 5.0));
+    document.get_mut(id).unwrap().translate(Point::new(0.0, 10.0));
+    let connector = document.connector(id).unwrap();
+    assert_eq!(connector.start.position, Point::new(10.0, 5.0));
+    assert_eq!(connector.end.position, Point::new(100.0, 15.0));
+
+    assert!(history.undo(&mut document));
+    assert_eq!(document.connector(id).unwrap().end.position, Point::new(100.0, 5.0));
+}
+
+#[test]
+fn connectors_survive_saving_and_loading() {
+    let (mut document, _, b, id) = linked();
+    {
+        let mut connector = document.connector_mut(id).unwrap();
+        connector.routing = Routing::Curved;
+        connector.start_arrow = true;
+    }
+    let file = NeoFile { document, ..NeoFile::default() };
+    let mut loaded = format::from_str(&format::to_string(&file)).unwrap();
+    assert_eq!(loaded, file);
+
+    loaded.document.get_mut(b).unwrap().translate(Point::new(50.0, 0.0));
+    assert_eq!(loaded.document.connector(id).unwrap().end.position, Point::new(150.0, 5.0));
+}
@@ -0,0 +1,52 @@
+use neo_scene::{Color, Document, Object, ObjectId, Rect, Shape, ShapeKind, Style};
+
+fn square(x: f64) -> Shape {
+    Shape::new(ShapeKind::Rectangle, Rect::new(x, 0.0, 10.0, 10.0), Style::filled(Color::BLACK))
+}
+
+#[test]
+fn ids_survive_removal_of_earlier_objects() {
+    let mut document = Document::with_site(7);
+    let first = document.push(square(0.0));
+    let second = document.push(square(20.0));
+
+    document.remove(first);
+
+    assert_eq!(document.ids(), &[second]);
+    assert_eq!(document.shape(second).unwrap().bounds.x, 20.0);
+    assert!(document.get(first).is_none());
+}
+
+#[test]
+fn object_id_round_trips_through_its_string_form() {
+    let id = ObjectId::new(0xdead_beef, 42);
+    assert_eq!(id.to_string(), "deadbeef0000002a");
+    assert_eq!("deadbeef0000002a".parse::<ObjectId>(), Ok(id));
+    assert!("not-an-id".parse::<ObjectId>().is_err());
+}
+
+#[test]
+fn paste_keeps_free_ids_and_remaps_taken_ones() {
+    let mut source = Document::with_site(1);
+    let id = source.push(square(0.0));
+    let clipboard: Vec<(ObjectId, Object)> = vec![(id, source.get(id).unwrap().clone())];
+
+    let mut target = Document::with_site(2);
+    let pasted = target.paste(clipboard.clone());
+    assert_eq!(pasted, vec![id]);
+
+    let pasted_again = target.paste(clipboard);
+    assert_ne!(pasted_again[0], id);
+    assert_eq!(pasted_again[0].site(), 2);
+    assert_eq!(target.len(), 2);
+}
+
+#[test]
+fn new_site_does_not_reuse_loaded_ids() {
+    let mut document = Document::with_site(3);
+    let existing = document.push(square(0.0));
+    let mut reloaded = document.clone();
+    reloaded.set_site(3);
+    let fresh = reloaded.push(square(10.0));
+    assert_ne!(fresh, existing);
+}
@@ -0,0 +1,130 @@
+use neo_scene::history::History;
+use neo_scene::{Color, Document, EraseMode, Eraser, Object, Point, Stroke, Style};
+
+/// A horizontal line from (0, 0) to (100, 0) with a point every 10 units.
+fn line() -> Stroke {
+    let mut stroke = Stroke::new(Style::stroked(Color::BLACK, 2.0));
+    for x in 0..=10 {
+        stroke.push(Point::new(x as f64 * 10.0, 0.0), 1.0);
+    }
+    stroke
+}
+
+fn strokes(document: &Document) -> Vec<&Stroke> {
+    document
+        .iter()
+        .filter_map(|(_, object)| match object {
+            Object::Stroke(stroke) => Some(stroke),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn whole_stroke_erase_is_undoable() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let id = document.push(line());
+    let eraser = Eraser::new(EraseMode::Stroke, 5.0);
+
+    let touch = Point::new(50.0, 4.0);
+    assert!(eraser.erase(&mut document, &mut history, touch, touch, 1.0));
+    history.commit(&document);
+    assert!(document.is_empty());
+
+    assert!(history.undo(&mut document));
+    assert_eq!(document.get(id), Some(&Object::Stroke(line())));
+}
+
+#[test]
+fn partial_erase_splits_the_stroke_in_place() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let below = document.push(line());
+    let id = document.push(line());
+    let above = document.push(line());
+    document.get_mut(below).unwrap().translate(Point::new(0.0, 500.0));
+    document.get_mut(above).unwrap().translate(Point::new(0.0, -500.0));
+    history.commit(&document);
+
+    // a vertical swipe through the middle of the line, 10 units wide at zoom 2
+    let eraser = Eraser::new(EraseMode::Partial, 8.0);
+    assert!(eraser.erase(&mut document, &mut history, Point::new(45.0, -20.0), Point::new(45.0, 20.0), 2.0));
+    history.commit(&document);
+
+    let ids = document.ids().to_vec();
+    assert_eq!(ids.len(), 4);
+    assert_eq!((ids[0], ids[1], ids[3]), (below, id, above));
+    let pieces = strokes(&document);
+    assert_eq!(pieces[1].points.last(), Some(&Point::new(40.0, 0.0)));
+    assert_eq!(pieces[2].points.first(), Some(&Point::new(50.0, 0.0)));
+
+    assert!(history.undo(&mut document));
+    assert_eq!(document.ids(), &[below, id, above]);
+    assert_eq!(document.get(id), Some(&Object::Stroke(line())));
+}
+
+#[test]
+fn a_fast_swipe_does_not_skip_strokes() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    document.push(line());
+    let eraser = Eraser::new(EraseMode::Stroke, 1.0);
+
+    // neither end of the swipe is anywhere near the line
+    assert!(eraser.erase(&mut document, &mut history, Point::new(50.0, -300.0), Point::new(50.0, 300.0), 1.0));
+    assert!(document.is_empty());
+}
+
+#[test]
+fn an_eraser_without_a_size_erases_nothing_and_returns() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    document.push(line());
+
+    // these used to step along the swipe practically forever
+    for (radius, zoom) in [(0.0, 1.0), (f64::NAN, 1.0), (8.0, f64::INFINITY), (8.0, f64::NAN)] {
+        let eraser = Eraser::new(EraseMode::Partial, radius);
+        assert!(!eraser.erase(&mut document, &mut history, Point::new(50.0, -300.0), Point::new(50.0, 300.0), zoom));
+    }
+    assert_eq!(document.len(), 1);
+}
+
+#[test]
+fn partial_erase_follows_the_smoothed_curve() {
+    // the curve bulges out to x = 112.5 halfway down the right-hand side
+    let mut stroke = Stroke::new(Style::stroked(Color::BLACK, 2.0));
+    for (x, y) in [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)] {
+        stroke.push(Point::new(x, y), 1.0);
+    }
+    stroke.smooth = true;
+
+    // on the straight line between the points, but nowhere near what is drawn
+    let off_curve = Point::new(100.0, 50.0);
+    assert!(!stroke.hit_test(off_curve, 3.0));
+    assert_eq!(stroke.erase(off_curve, 3.0), None);
+
+    let on_curve = Point::new(112.5, 50.0);
+    assert!(stroke.hit_test(on_curve, 3.0));
+    let pieces = stroke.erase(on_curve, 3.0).unwrap();
+    assert_eq!(pieces.len(), 2);
+    assert_eq!(pieces[0].points.first(), Some(&Point::new(0.0, 0.0)));
+    assert_eq!(pieces[1].points.last(), Some(&Point::new(0.0, 100.0)));
+    assert!(pieces.iter().all(|piece| !piece.smooth && !piece.hit_test(on_curve, 2.0)));
+}
+
+#[test]
+fn partial_erase_reaches_the_edge_of_a_wide_line() {
+    let mut stroke = Stroke::new(Style::stroked(Color::BLACK, 2.0));
+    for x in 0..=10 {
+        stroke.push(Point::new(x as f64 * 10.0, 0.0), 10.0);
+    }
+
+    // 9 units from the middle of a line drawn 20 wide
+    let edge = Point::new(50.0, 9.0);
+    assert!(stroke.hit_test(edge, 1.0));
+    let pieces = stroke.erase(edge, 1.0).unwrap();
+    assert_eq!(pieces.len(), 2);
+    assert!(pieces.iter().all(|piece| piece.width_at(0) == 20.0 && !piece.hit_test(edge, 0.5)));
+    assert_eq!(stroke.erase(Point::new(50.0, 12.0), 1.0), None);
+}
@@ -0,0 +1,114 @@
+use neo_scene::format::{self, NeoFile};
+use neo_scene::group;
+use neo_scene::history::History;
+use neo_scene::order::{self, Reorder};
+use neo_scene::selection::Selection;
+use neo_scene::{Color, Document, ObjectId, Point, Rect, Shape, ShapeKind, Style};
+
+fn squares(document: &mut Document, count: usize) -> Vec<ObjectId> {
+    (0..count)
+        .map(|i| {
+            let bounds = Rect::new(i as f64 * 20.0, 0.0, 10.0, 10.0);
+            document.push(Shape::new(ShapeKind::Rectangle, bounds, Style::filled(Color::BLACK)))
+        })
+        .collect()
+}
+
+#[test]
+fn groups_nest_and_keep_their_members_together() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let [a, b, c, d] = squares(&mut document, 4)[..] else { unreachable!() };
+
+    let inner = group::group(&mut document, &mut history, &[a, c]).unwrap();
+    history.commit(&document);
+    // the group stacks where its topmost member was, and a moves up past b to join it
+    assert_eq!(document.ids(), [b, a, c, inner, d]);
+    assert_eq!(document.children(None), [b, inner, d]);
+
+    let outer = group::group(&mut document, &mut history, &[inner, d, a]).unwrap();
+    history.commit(&document);
+    assert_eq!(document.children(Some(outer)), [inner, d]);
+    assert_eq!(document.parent(a), Some(inner));
+    assert_eq!(document.leaves(outer), [a, c, d]);
+    assert_eq!(document.bounds_of(outer), Some(Rect::new(0.0, 0.0, 70.0, 10.0)));
+    assert_eq!(group::group(&mut document, &mut history, &[outer]), None);
+
+    history.undo(&mut document);
+    history.undo(&mut document);
+    assert_eq!(document.ids(), [a, b, c, d]);
+    assert_eq!(document.parent(a), None);
+}
+
+#[test]
+fn ungrouping_hands_the_children_to_the_group_above() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let [a, b, c] = squares(&mut document, 3)[..] else { unreachable!() };
+    let inner = group::group(&mut document, &mut history, &[a, b]).unwrap();
+    let outer = group::group(&mut document, &mut history, &[inner, c]).unwrap();
+    history.commit(&document);
+
+    assert_eq!(group::ungroup(&mut document, &mut history, inner), [a, b]);
+    history.commit(&document);
+    assert!(!document.contains(inner));
+    assert_eq!(document.children(Some(outer)), [a, b, c]);
+    assert!(group::ungroup(&mut document, &mut history, a).is_empty());
+
+    history.undo(&mut document);
+    assert_eq!(document.children(Some(outer)), [inner, c]);
+    assert_eq!(document.parent(b), Some(inner));
+}
+
+#[test]
+fn restacking_stays_among_siblings_and_undoes_exactly() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let [a, b, c, d, e] = squares(&mut document, 5)[..] else { unreachable!() };
+    let pair = group::group(&mut document, &mut history, &[c, d]).unwrap();
+    history.commit(&document);
+    assert_eq!(document.ids(), [a, b, c, d, pair, e]);
+
+    assert!(order::reorder(&mut document, &mut history, &[a], Reorder::Forward));
+    assert_eq!(document.ids(), [b, a, c, d, pair, e]);
+    // a group moves with what is inside it
+    assert!(order::reorder(&mut document, &mut history, &[pair], Reorder::ToBack));
+    assert_eq!(document.ids(), [c, d, pair, b, a, e]);
+    // and what is inside only moves within it
+    assert!(order::reorder(&mut document, &mut history, &[c], Reorder::ToFront));
+    assert_eq!(document.ids(), [d, c, pair, b, a, e]);
+    assert!(!order::reorder(&mut document, &mut history, &[c], Reorder::Forward));
+    assert!(order::reorder(&mut document, &mut history, &[a, e], Reorder::Backward));
+    assert_eq!(document.ids(), [d, c, pair, a, e, b]);
+    history.commit(&document);
+
+    history.undo(&mut document);
+    assert_eq!(document.ids(), [a, b, c, d, pair, e]);
+    history.redo(&mut document);
+    assert_eq!(document.ids(), [d, c, pair, a, e, b]);
+}
+
+#[test]
+fn clicks_pick_the_outermost_group_until_one_is_entered_and_groups_are_saved() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let [a, b, c] = squares(&mut document, 3)[..] else { unreachable!() };
+    let inner = group::group(&mut document, &mut history, &[a, b]).unwrap();
+    let outer = group::group(&mut document, &mut history, &[inner, c]).unwrap();
+
+    let mut selection = Selection::new();
+    assert_eq!(selection.pick(&document, a), outer);
+    assert_eq!(selection.pick_enclosed(&document, &[a, b]), []);
+    assert_eq!(selection.pick_enclosed(&document, &[a, b, c]), [outer]);
+    selection.enter(Some(outer));
+    assert_eq!(selection.pick(&document, a), inner);
+    assert_eq!(selection.pick_enclosed(&document, &[a, b]), [inner]);
+    selection.set(inner);
+    assert_eq!(selection.leaves(&document), [a, b]);
+
+    let file = NeoFile { document, ..Default::default() };
+    let loaded = format::from_str(&format::to_string(&file)).unwrap();
+    assert_eq!(loaded, file);
+    assert_eq!(loaded.document.parent(a), Some(inner));
+    assert_eq!(loaded.document.topmost_at(Point::new(5.0, 5.0), 0.0), Some(a));
+}
@@ -0,0 +1,72 @@
+use neo_scene::history::History;
+use neo_scene::{Color, Document, Point, Rect, Shape, ShapeKind, Stroke, Style};
+
+fn square() -> Shape {
+    Shape::new(ShapeKind::Rectangle, Rect::new(0.0, 0.0, 10.0, 10.0), Style::filled(Color::BLACK))
+}
+
+#[test]
+fn a_drag_is_a_single_undo_step() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let id = document.push(square());
+    history.track_created(id);
+    history.commit(&document);
+
+    history.track(&document, id);
+    for step in 1..=10 {
+        document.shape_mut(id).unwrap().bounds.x = step as f64;
+    }
+    history.commit(&document);
+
+    assert!(history.undo(&mut document));
+    assert_eq!(document.shape(id).unwrap().bounds.x, 0.0);
+    assert!(history.undo(&mut document));
+    assert!(!document.contains(id));
+
+    assert!(history.redo(&mut document));
+    assert!(history.redo(&mut document));
+    assert_eq!(document.shape(id).unwrap().bounds.x, 10.0);
+}
+
+#[test]
+fn a_stroke_is_created_and_removed_as_one_step() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let below = document.push(square());
+    let id = document.push(Stroke::new(Style::default()));
+    history.track_created(id);
+    for i in 0..5 {
+        document.stroke_mut(id).unwrap().points.push(Point::new(i as f64, 0.0));
+    }
+    history.commit(&document);
+
+    history.track(&document, below);
+    document.remove(below);
+    history.commit(&document);
+
+    history.undo(&mut document);
+    assert_eq!(document.ids(), &[below, id], "undo restores draw order");
+    history.undo(&mut document);
+    assert_eq!(document.ids(), &[below]);
+}
+
+#[test]
+fn depth_is_bounded_and_new_edits_clear_redo() {
+    let mut document = Document::new();
+    let mut history = History::new(2);
+    for _ in 0..3 {
+        let id = document.push(square());
+        history.track_created(id);
+        history.commit(&document);
+    }
+    assert!(history.undo(&mut document));
+    assert!(history.undo(&mut document));
+    assert!(!history.undo(&mut document));
+    assert_eq!(document.len(), 1);
+
+    let id = document.push(square());
+    history.track_created(id);
+    history.commit(&document);
+    assert!(!history.can_redo());
+}
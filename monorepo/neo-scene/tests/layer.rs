@@ -0,0 +1,116 @@
+use neo_scene::format::{self, NeoFile};
+use neo_scene::history::History;
+use neo_scene::selection::{self, Selection};
+use neo_scene::{group, layer, order};
+use neo_scene::{Color, Document, EraseMode, Eraser, ObjectId, Point, Rect, Shape, ShapeKind, Stroke, Style};
+
+fn squares(document: &mut Document, count: usize) -> Vec<ObjectId> {
+    (0..count)
+        .map(|i| {
+            let bounds = Rect::new(i as f64 * 20.0, 0.0, 10.0, 10.0);
+            document.push(Shape::new(ShapeKind::Rectangle, bounds, Style::filled(Color::BLACK)))
+        })
+        .collect()
+}
+
+#[test]
+fn a_hidden_or_locked_group_covers_everything_inside_it() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let [a, b, c] = squares(&mut document, 3)[..] else { unreachable!() };
+    let pair = group::group(&mut document, &mut history, &[a, b]).unwrap();
+
+    document.get_mut(pair).unwrap().layer_mut().hidden = true;
+    document.get_mut(c).unwrap().layer_mut().locked = true;
+    assert!(layer::is_hidden(&document, a));
+    assert!(!layer::is_locked(&document, a));
+    assert!(layer::is_locked(&document, c) && !layer::is_pickable(&document, c));
+    assert!(selection::in_marquee(&document, Rect::new(-5.0, -5.0, 100.0, 20.0)).is_empty());
+
+    let mut selection = Selection::new();
+    selection.extend([a, c]);
+    document.get_mut(pair).unwrap().layer_mut().hidden = false;
+    selection.retain_pickable(&document);
+    assert_eq!(selection.ids(), [a]);
+}
+
+#[test]
+fn the_eraser_leaves_locked_strokes_alone() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let mut stroke = Stroke::new(Style::stroked(Color::BLACK, 2.0));
+    stroke.push(Point::new(0.0, 0.0), 1.0);
+    stroke.push(Point::new(100.0, 0.0), 1.0);
+    stroke.layer.locked = true;
+    let id = document.push(stroke);
+
+    let eraser = Eraser::new(EraseMode::Stroke, 8.0);
+    assert!(!eraser.erase(&mut document, &mut history, Point::new(50.0, -20.0), Point::new(50.0, 20.0), 1.0));
+    document.get_mut(id).unwrap().layer_mut().locked = false;
+    assert!(eraser.erase(&mut document, &mut history, Point::new(50.0, -20.0), Point::new(50.0, 20.0), 1.0));
+    assert!(document.is_empty());
+}
+
+#[test]
+fn dragging_a_row_moves_it_among_siblings_and_between_groups() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let [a, b, c, d] = squares(&mut document, 4)[..] else { unreachable!() };
+    let pair = group::group(&mut document, &mut history, &[b, c]).unwrap();
+    history.commit(&document);
+    assert_eq!(document.children(None), [a, pair, d]);
+
+    assert!(order::move_to(&mut document, &mut history, d, None, 0));
+    assert_eq!(document.ids(), [d, a, b, c, pair]);
+    // into the group, between its two children
+    assert!(order::move_to(&mut document, &mut history, a, Some(pair), 1));
+    assert_eq!(document.children(Some(pair)), [b, a, c]);
+    assert_eq!(document.ids(), [d, b, a, c, pair]);
+    assert!(!order::move_to(&mut document, &mut history, pair, Some(pair), 0));
+    assert!(!order::move_to(&mut document, &mut history, a, Some(pair), 1));
+    history.commit(&document);
+
+    history.undo(&mut document);
+    assert_eq!(document.ids(), [a, b, c, pair, d]);
+    assert_eq!(document.parent(a), None);
+}
+
+#[test]
+fn the_tree_revision_changes_with_the_tree_only() {
+    let mut document = Document::new();
+    let mut history = History::default();
+    let [a, b] = squares(&mut document, 2)[..] else { unreachable!() };
+    let revision = document.tree_revision();
+    assert_eq!(document.clone().tree_revision(), revision);
+    assert_ne!(Document::new().tree_revision(), revision);
+
+    // edits that leave the tree as it was
+    document.get_mut(a).unwrap().translate(Point::new(5.0, 0.0));
+    document.get_mut(a).unwrap().layer_mut().name = "Moved".to_owned();
+    assert_eq!(document.tree_revision(), revision);
+
+    assert!(order::move_to(&mut document, &mut history, a, None, 2));
+    let restacked = document.tree_revision();
+    assert_ne!(restacked, revision);
+    group::group(&mut document, &mut history, &[a, b]).unwrap();
+    assert_ne!(document.tree_revision(), restacked);
+}
+
+#[test]
+fn names_and_flags_are_saved_only_when_set() {
+    let mut document = Document::new();
+    let [a, b] = squares(&mut document, 2)[..] else { unreachable!() };
+    {
+        let mut object = document.get_mut(a).unwrap();
+        object.layer_mut().name = "Background".to_owned();
+        object.layer_mut().locked = true;
+    }
+
+    let file = NeoFile { document, ..Default::default() };
+    let saved = format::to_string(&file);
+    assert_eq!(saved.matches("\"layer\"").count(), 1);
+    let loaded = format::from_str(&saved).unwrap();
+    assert_eq!(loaded, file);
+    assert_eq!(loaded.document.get(a).unwrap().layer().name, "Background");
+    assert!(layer::is_locked(&loaded.document, a) && layer::is_pickable(&loaded.document, b));
+}
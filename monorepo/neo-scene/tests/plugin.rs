@@ -0,0 +1,203 @@
+use std::{cell::RefCell, rc::Rc};
+
+use neo_scene::history::History;
+use neo_scene::plugin::{Draw, Input, Plugin, PluginContext, PluginHost, Viewport};
+use neo_scene::{Color, Document, Point, Rect, Shape, ShapeKind, Style};
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+/// Logs every hook it gets, and adds a square where the canvas is clicked.
+struct Recorder {
+    log: Log,
+}
+
+impl Plugin for Recorder {
+    fn name(&self) -> &str {
+        "recorder"
+    }
+
+    fn init(&mut self, _ctx: &mut PluginContext) {
+        self.log.borrow_mut().push("init".into());
+    }
+
+    fn begin_frame(&mut self, ctx: &mut PluginContext) {
+        self.log.borrow_mut().push("begin_frame".into());
+        if !ctx.input.primary_pressed {
+            return;
+        }
+        let Some(at) = ctx.input.pointer_world(ctx.viewport) else {
+            return;
+        };
+        let square = Shape::new(ShapeKind::Rectangle, Rect::new(at.x, at.y, 10.0, 10.0), Style::filled(Color::BLACK));
+        let id = ctx.document.push(square);
+        ctx.history.track_created(id);
+        ctx.request_redraw();
+    }
+
+    fn render(&mut self, ctx: &mut PluginContext, draw: &mut dyn Draw) {
+        self.log.borrow_mut().push("render".into());
+        let origin = ctx.viewport.world_to_screen(Point::ZERO);
+        draw.line(origin, origin + Point::new(10.0, 0.0), 1.0, Color::BLACK);
+    }
+
+    fn end_frame(&mut self, _ctx: &mut PluginContext) {
+        self.log.borrow_mut().push("end_frame".into());
+    }
+
+    fn resize(&mut self, _ctx: &mut PluginContext, width: f64, height: f64) {
+        self.log.borrow_mut().push(format!("resize {width}x{height}"));
+    }
+
+    fn destroy(&mut self) {
+        self.log.borrow_mut().push("destroy".into());
+    }
+}
+
+/// Records the lines it is asked to draw.
+#[derive(Default)]
+struct Lines(Vec<(Point, Point)>);
+
+impl Draw for Lines {
+    fn line(&mut self, from: Point, to: Point, _width: f64, _color: Color) {
+        self.0.push((from, to));
+    }
+    fn rect(&mut self, _rect: Rect, _style: Style) {}
+    fn ellipse(&mut self, _bounds: Rect, _style: Style) {}
+    fn text(&mut self, _text: &str, _position: Point, _size: f64, _color: Color) {}
+}
+
+fn viewport() -> Viewport {
+    Viewport { center: Point::new(100.0, 50.0), zoom: 2.0, width: 800.0, height: 600.0 }
+}
+
+#[test]
+fn hooks_run_in_lifecycle_order() {
+    let log = Log::default();
+    let (mut document, mut history, mut viewport, input) =
+        (Document::new(), History::default(), viewport(), Input::default());
+    let mut host = PluginHost::new();
+    let mut ctx = PluginContext::new(&mut document, &mut history, &mut viewport, &input);
+    host.add(Box::new(Recorder { log: log.clone() }), &mut ctx);
+
+    host.begin_frame(&mut ctx);
+    host.render(&mut ctx, &mut Lines::default());
+    host.end_frame(&mut ctx);
+    ctx.viewport.width = 1024.0;
+    host.begin_frame(&mut ctx);
+    drop(host);
+
+    assert_eq!(
+        *log.borrow(),
+        [
+            "init", "resize 800x600", "begin_frame", "render", "end_frame",
+            "resize 1024x600", "begin_frame", "destroy",
+        ]
+    );
+}
+
+#[test]
+fn plugins_edit_the_document_through_the_history() {
+    let log = Log::default();
+    let (mut document, mut history, mut viewport) = (Document::new(), History::default(), viewport());
+    let input = Input {
+        pointer: Some(Point::new(400.0, 300.0)),
+        primary_down: true,
+        primary_pressed: true,
+        ..Input::default()
+    };
+    let mut host = PluginHost::new();
+    let mut ctx = PluginContext::new(&mut document, &mut history, &mut viewport, &input);
+    host.add(Box::new(Recorder { log }), &mut ctx);
+    host.begin_frame(&mut ctx);
+    assert!(ctx.redraw_requested());
+
+    let (_, object) = document.iter().next().unwrap();
+    // the click was in the middle of the screen, where the viewport is centred
+    assert_eq!(object.bounds().unwrap().min(), Point::new(100.0, 50.0));
+    assert!(history.commit(&document));
+    assert!(history.undo(&mut document));
+    assert_eq!(document.iter().count(), 0);
+}
+
+#[test]
+fn render_draws_in_screen_space() {
+    let mut lines = Lines::default();
+    let (mut document, mut history, mut viewport, input) =
+        (Document::new(), History::default(), viewport(), Input::default());
+    let mut host = PluginHost::new();
+    let mut ctx = PluginContext::new(&mut document, &mut history, &mut viewport, &input);
+    host.add(Box::new(Recorder { log: Log::default() }), &mut ctx);
+    host.render(&mut ctx, &mut lines);
+
+    assert_eq!(lines.0, [(Point::new(200.0, 200.0), Point::new(210.0, 200.0))]);
+    assert_eq!(viewport.screen_to_world(viewport.world_to_screen(Point::new(3.0, -7.0))), Point::new(3.0, -7.0));
+}
+
+#[test]
+fn removing_a_plugin_destroys_it_and_frame_events_are_forgotten() {
+    let log = Log::default();
+    let (mut document, mut history, mut viewport) = (Document::new(), History::default(), viewport());
+    let mut input = Input {
+        primary_down: true,
+        primary_pressed: true,
+        wheel: Point::new(0.0, 100.0),
+        keys_pressed: vec!["a".into()],
+        ..Input::default()
+    };
+    let mut host = PluginHost::new();
+    host.add(
+        Box::new(Recorder { log: log.clone() }),
+        &mut PluginContext::new(&mut document, &mut history, &mut viewport, &input),
+    );
+    assert!(host.remove("recorder"));
+    assert!(!host.remove("recorder"));
+    assert!(host.is_empty());
+    assert_eq!(log.borrow().last().unwrap(), "destroy");
+
+    assert!(input.key_pressed("a"));
+    input.end_frame();
+    assert!(input.primary_down && !input.primary_pressed);
+    assert_eq!(input.wheel, Point::ZERO);
+    assert!(!input.key_pressed("a"));
+}
+
+/// Owns one tool and records whether it saw it selected.
+struct Toolmaker {
+    name: &'static str,
+    saw: Log,
+}
+
+impl Plugin for Toolmaker {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn begin_frame(&mut self, ctx: &mut PluginContext) {
+        self.saw.borrow_mut().push(format!("{}: {:?}", self.name, ctx.active_tool()));
+    }
+
+    fn tools(&self) -> Vec<String> {
+        vec!["stamp".into()]
+    }
+}
+
+#[test]
+fn only_the_owner_of_the_selected_tool_sees_it() {
+    let saw = Log::default();
+    let (mut document, mut history, mut viewport, input) =
+        (Document::new(), History::default(), viewport(), Input::default());
+    let mut host = PluginHost::new();
+    let mut ctx = PluginContext::new(&mut document, &mut history, &mut viewport, &input);
+    for name in ["a", "b"] {
+        host.add(Box::new(Toolmaker { name, saw: saw.clone() }), &mut ctx);
+    }
+    assert_eq!(host.tools(), [("a".into(), "stamp".into()), ("b".into(), "stamp".into())]);
+
+    host.set_active_tool(Some(("b".into(), "stamp".into())));
+    host.begin_frame(&mut ctx);
+    assert_eq!(*saw.borrow(), ["a: None", "b: Some(\"stamp\")"]);
+    assert_eq!(ctx.active_tool(), None);
+
+    assert!(host.remove("b"));
+    assert_eq!(host.active_tool(), None);
+}
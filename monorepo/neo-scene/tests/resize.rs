@@ -0,0 +1,42 @@
+use neo_scene::resize::{Handle, Resize};
+use neo_scene::{Point, Rect};
+
+fn resize(handle: Handle) -> Resize {
+    Resize { handle, keep_aspect: false, from_center: false, min_size: 10.0 }
+}
+
+fn scaled(resize: Resize, bounds: Rect, to: Point) -> Rect {
+    let scale = resize.scale(bounds, to);
+    Rect::from_corners(scale.apply(bounds.min()), scale.apply(bounds.max()))
+}
+
+#[test]
+fn every_handle_moves_its_own_edges() {
+    let bounds = Rect::new(0.0, 0.0, 100.0, 50.0);
+    assert_eq!(Handle::Top.position(bounds), Point::new(50.0, 0.0));
+    assert_eq!(scaled(resize(Handle::TopLeft), bounds, Point::new(-20.0, 10.0)), Rect::new(-20.0, 10.0, 120.0, 40.0));
+    // an edge handle leaves the other axis alone wherever the mouse goes
+    assert_eq!(scaled(resize(Handle::Right), bounds, Point::new(150.0, 999.0)), Rect::new(0.0, 0.0, 150.0, 50.0));
+}
+
+#[test]
+fn dragging_past_the_opposite_edge_flips() {
+    let bounds = Rect::new(0.0, 0.0, 100.0, 50.0);
+    let scale = resize(Handle::Right).scale(bounds, Point::new(-40.0, 25.0));
+    assert_eq!(scale.x, -0.4);
+    assert_eq!(scaled(resize(Handle::Right), bounds, Point::new(-40.0, 25.0)), Rect::new(-40.0, 0.0, 40.0, 50.0));
+    // on the way through it never gets thinner than the minimum
+    assert_eq!(resize(Handle::Right).scale(bounds, Point::new(2.0, 25.0)).x, 0.1);
+}
+
+#[test]
+fn keeps_the_aspect_ratio_and_resizes_from_the_centre() {
+    let bounds = Rect::new(0.0, 0.0, 100.0, 50.0);
+    let aspect = Resize { keep_aspect: true, ..resize(Handle::BottomRight) };
+    assert_eq!(scaled(aspect, bounds, Point::new(200.0, 60.0)), Rect::new(0.0, 0.0, 200.0, 100.0));
+    let edge = Resize { keep_aspect: true, ..resize(Handle::Bottom) };
+    assert_eq!(scaled(edge, bounds, Point::new(0.0, 100.0)), Rect::new(-50.0, 0.0, 200.0, 100.0));
+
+    let centre = Resize { from_center: true, ..resize(Handle::Right) };
+    assert_eq!(scaled(centre, bounds, Point::new(120.0, 25.0)), Rect::new(-20.0, 0.0, 140.0, 50.0));
+}
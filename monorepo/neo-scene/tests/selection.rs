@@ -0,0 +1,69 @@
+use neo_scene::selection::{self, Selection};
+use neo_scene::{Color, Document, Point, Rect, Shape, ShapeKind, Stroke, Style, Transform};
+
+fn square(x: f64, y: f64) -> Shape {
+    Shape::new(ShapeKind::Rectangle, Rect::new(x, y, 10.0, 10.0), Style::filled(Color::BLACK))
+}
+
+#[test]
+fn shift_click_adds_and_removes() {
+    let mut document = Document::new();
+    let a = document.push(square(0.0, 0.0));
+    let b = document.push(square(20.0, 0.0));
+    let mut selection = Selection::new();
+
+    selection.set(a);
+    assert_eq!(selection.single(), Some(a));
+    selection.toggle(b);
+    assert_eq!(selection.ids(), [a, b]);
+    assert_eq!(selection.single(), None);
+    assert_eq!(selection.bounds(&document), Some(Rect::new(0.0, 0.0, 30.0, 10.0)));
+    selection.toggle(a);
+    assert_eq!(selection.ids(), [b]);
+
+    document.remove(b);
+    selection.retain_existing(&document);
+    assert!(selection.is_empty());
+}
+
+#[test]
+fn a_marquee_picks_objects_entirely_inside_it() {
+    let mut document = Document::new();
+    let inside = document.push(square(0.0, 0.0));
+    let _straddling = document.push(square(25.0, 0.0));
+    let also_inside = document.push(square(10.0, 10.0));
+
+    let picked = selection::in_marquee(&document, Rect::new(-5.0, -5.0, 30.0, 30.0));
+    assert_eq!(picked, [inside, also_inside]);
+}
+
+#[test]
+fn a_lasso_follows_its_outline_rather_than_its_box() {
+    let mut document = Document::new();
+    let mut stroke = Stroke::new(Style::default());
+    stroke.points = vec![Point::new(2.0, 2.0), Point::new(2.0, 18.0)];
+    let left = document.push(stroke);
+    // in the lasso's box, but in the notch cut out of its top right
+    let notched = document.push(square(25.0, 2.0));
+
+    let lasso = [
+        Point::new(0.0, 0.0),
+        Point::new(20.0, 0.0),
+        Point::new(20.0, 20.0),
+        Point::new(40.0, 20.0),
+        Point::new(40.0, 40.0),
+        Point::new(0.0, 40.0),
+    ];
+    let picked = selection::in_lasso(&document, &lasso);
+    assert_eq!(picked, [left]);
+    assert!(!picked.contains(&notched));
+}
+
+#[test]
+fn objects_scale_together_in_world_space() {
+    let mut document = Document::new();
+    let id = document.push(square(10.0, 10.0));
+    // doubling around the origin, then flipping across x = 0
+    document.get_mut(id).unwrap().apply_transform(Transform::scaling(-2.0, 2.0));
+    assert_eq!(document.get(id).unwrap().bounds(), Some(Rect::new(-40.0, 20.0, 20.0, 20.0)));
+}
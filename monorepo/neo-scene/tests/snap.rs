@@ -0,0 +1,48 @@
+use neo_scene::snap::{Guide, Snapper};
+use neo_scene::{Point, Rect};
+
+fn snapper(targets: &[Rect]) -> Snapper<'_> {
+    Snapper { targets, grid: Some(20.0), distance: 5.0 }
+}
+
+#[test]
+fn lines_up_edges_and_centres() {
+    let targets = [Rect::new(0.0, 0.0, 100.0, 50.0)];
+    // left edge 3 away from the target's centre, top 4 away from its bottom
+    let snap = snapper(&targets).snap_rect(Rect::new(53.0, 54.0, 40.0, 40.0));
+    assert_eq!(snap.offset, Point::new(-3.0, -4.0));
+    assert!(snap.guides.contains(&Guide::Alignment { from: Point::new(50.0, 0.0), to: Point::new(50.0, 90.0) }));
+    assert!(snap.guides.contains(&Guide::Alignment { from: Point::new(0.0, 50.0), to: Point::new(100.0, 50.0) }));
+}
+
+#[test]
+fn repeats_and_splits_the_spacing_between_neighbours() {
+    let targets = [Rect::new(0.0, 0.0, 20.0, 20.0), Rect::new(50.0, 0.0, 20.0, 20.0)];
+    // after the pair, the same 30 apart
+    let snap = snapper(&targets).snap_rect(Rect::new(102.0, 0.0, 20.0, 20.0));
+    assert_eq!(snap.offset.x, -2.0);
+    assert!(snap.guides.contains(&Guide::Gap { from: Point::new(70.0, 10.0), to: Point::new(100.0, 10.0) }));
+
+    // a narrower box halfway between them
+    let snap = snapper(&targets).snap_rect(Rect::new(28.0, 5.0, 10.0, 10.0));
+    assert_eq!(snap.offset.x, 2.0);
+    assert!(snap.guides.contains(&Guide::Gap { from: Point::new(20.0, 10.0), to: Point::new(30.0, 10.0) }));
+}
+
+#[test]
+fn falls_back_to_the_grid_within_reach() {
+    let far = [Rect::new(1000.0, 1000.0, 10.0, 10.0)];
+    let snap = snapper(&far).snap_rect(Rect::new(43.0, 29.0, 10.0, 10.0));
+    // x is 3 from a grid line, y is 9 from the nearest and stays
+    assert_eq!(snap.offset, Point::new(-3.0, 0.0));
+    assert!(snap.guides.is_empty());
+    assert_eq!(Snapper { targets: &far, grid: None, distance: 5.0 }.snap_rect(Rect::new(43.0, 29.0, 1.0, 1.0)).offset, Point::ZERO);
+}
+
+#[test]
+fn points_snap_without_spacing() {
+    let targets = [Rect::new(0.0, 0.0, 20.0, 20.0), Rect::new(50.0, 0.0, 20.0, 20.0)];
+    let snap = snapper(&targets).snap_point(Point::new(69.0, 11.0));
+    assert_eq!(snap.offset, Point::new(1.0, -1.0));
+    assert!(snap.guides.iter().all(|guide| matches!(guide, Guide::Alignment { .. })));
+}
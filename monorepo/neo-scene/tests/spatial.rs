@@ -0,0 +1,54 @@
+use neo_scene::{Color, Document, Point, Rect, Shape, ShapeKind, Stroke, Style};
+
+fn shape(kind: ShapeKind, x: f64, y: f64, size: f64) -> Shape {
+    Shape::new(kind, Rect::new(x, y, size, size), Style::filled(Color::BLACK))
+}
+
+#[test]
+fn point_hits_come_back_topmost_first() {
+    let mut document = Document::new();
+    let bottom = document.push(shape(ShapeKind::Rectangle, 0.0, 0.0, 100.0));
+    let top = document.push(shape(ShapeKind::Rectangle, 50.0, 50.0, 100.0));
+
+    assert_eq!(document.hits_at(Point::new(75.0, 75.0), 0.0), vec![top, bottom]);
+    assert_eq!(document.topmost_at(Point::new(10.0, 10.0), 0.0), Some(bottom));
+    assert_eq!(document.topmost_at(Point::new(500.0, 500.0), 0.0), None);
+}
+
+#[test]
+fn hit_tests_use_the_exact_outline() {
+    let mut document = Document::new();
+    let circle = document.push(shape(ShapeKind::Ellipse, 0.0, 0.0, 100.0));
+    let mut stroke = Stroke::new(Style::stroked(Color::BLACK, 2.0));
+    stroke.points = vec![Point::new(200.0, 0.0), Point::new(300.0, 100.0)];
+    let line = document.push(stroke);
+
+    // inside the circle's bounds but outside the circle itself
+    assert_eq!(document.topmost_at(Point::new(5.0, 5.0), 0.0), None);
+    assert_eq!(document.topmost_at(Point::new(50.0, 50.0), 0.0), Some(circle));
+    assert_eq!(document.topmost_at(Point::new(250.0, 50.0), 0.0), Some(line));
+    assert_eq!(document.topmost_at(Point::new(290.0, 10.0), 0.0), None);
+}
+
+#[test]
+fn index_follows_moves_made_through_the_document() {
+    let mut document = Document::new();
+    let id = document.push(shape(ShapeKind::Rectangle, 0.0, 0.0, 10.0));
+    document.shape_mut(id).unwrap().bounds = Rect::new(1000.0, 1000.0, 10.0, 10.0);
+
+    assert!(document.in_rect(Rect::new(-5.0, -5.0, 20.0, 20.0)).is_empty());
+    assert_eq!(document.in_rect(Rect::new(990.0, 990.0, 20.0, 20.0)), vec![id]);
+    assert_eq!(document.nearest(Point::new(900.0, 1005.0), 200.0), Some(id));
+    assert_eq!(document.nearest(Point::new(900.0, 1005.0), 50.0), None);
+
+    document.remove(id);
+    assert!(document.in_rect(Rect::new(990.0, 990.0, 20.0, 20.0)).is_empty());
+}
+
+#[test]
+fn rect_queries_return_draw_order_after_reload() {
+    let mut document = Document::new();
+    let ids: Vec<_> = (0..50).map(|i| document.push(shape(ShapeKind::Rectangle, i as f64 * 5.0, 0.0, 10.0))).collect();
+    let reloaded: Document = serde_json::from_str(&serde_json::to_string(&document).unwrap()).unwrap();
+    assert_eq!(reloaded.in_rect(Rect::new(0.0, 0.0, 1000.0, 10.0)), ids);
+}
@@ -0,0 +1,67 @@
+use neo_scene::{Brush, Color, Point, Stroke, Style};
+
+fn line(points: &[(f64, f64)]) -> Stroke {
+    let mut stroke = Stroke::new(Style::stroked(Color::BLACK, 2.0));
+    for (x, y) in points {
+        stroke.push(Point::new(*x, *y), 1.0);
+    }
+    stroke
+}
+
+#[test]
+fn simplify_drops_collinear_points_but_keeps_corners() {
+    let mut stroke = line(&[(0.0, 0.0), (1.0, 0.01), (2.0, 0.0), (3.0, 0.0), (3.0, 5.0)]);
+    stroke.simplify(0.1);
+
+    assert_eq!(stroke.points, vec![Point::new(0.0, 0.0), Point::new(3.0, 0.0), Point::new(3.0, 5.0)]);
+    assert!(stroke.widths.is_empty());
+}
+
+#[test]
+fn simplify_keeps_pressure_changes_on_a_straight_line() {
+    let mut stroke = Stroke::new(Style::stroked(Color::BLACK, 10.0));
+    for (x, width) in [(0.0, 1.0), (1.0, 1.0), (2.0, 0.2), (3.0, 1.0), (4.0, 1.0)] {
+        stroke.push(Point::new(x, 0.0), width);
+    }
+    stroke.simplify(0.5);
+
+    assert_eq!(stroke.points.len(), stroke.widths.len());
+    assert!(stroke.points.contains(&Point::new(2.0, 0.0)));
+    assert_eq!(stroke.width_at(0), 10.0);
+}
+
+#[test]
+fn smooth_strokes_pass_through_their_points() {
+    let mut stroke = line(&[(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)]);
+    stroke.smooth = true;
+    let samples = stroke.samples();
+
+    assert!(samples.len() > stroke.points.len());
+    for point in &stroke.points {
+        assert!(samples.iter().any(|(sample, _)| sample.distance(*point) < 1e-9));
+    }
+    // the curve rounds the corner, so it is still hit just beside the polyline's apex
+    assert!(stroke.hit_test(Point::new(10.0, 9.5), 0.0));
+}
+
+#[test]
+fn brush_pressure_narrows_the_line() {
+    let brush = Brush { pressure_sensitivity: 1.0, stabilizer: 0.0, ..Brush::new(Color::BLACK, 4.0) };
+    let mut stroke = brush.begin();
+    brush.extend(&mut stroke, Point::new(0.0, 0.0), 1.0, 0.0);
+    brush.extend(&mut stroke, Point::new(10.0, 0.0), 0.5, 0.0);
+
+    assert_eq!(stroke.width_at(0), 4.0);
+    assert_eq!(stroke.width_at(1), 2.0);
+    assert!(stroke.bounds().unwrap().contains(Point::new(-1.9, 0.0)));
+}
+
+#[test]
+fn a_light_first_touch_keeps_its_width() {
+    let mut stroke = line(&[]);
+    stroke.push(Point::new(0.0, 0.0), 0.25);
+    stroke.push(Point::new(10.0, 0.0), 1.0);
+
+    assert_eq!(stroke.widths, [0.25, 1.0]);
+    assert_eq!(stroke.width_at(0), stroke.style.stroke_width * 0.25);
+}
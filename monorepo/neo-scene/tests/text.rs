@@ -0,0 +1,74 @@
+use neo_scene::text::{self, Composition, Line, TextCursor};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Every grapheme is 10 wide.
+fn measure(s: &str) -> f32 {
+    s.graphemes(true).count() as f32 * 10.0
+}
+
+fn lines(text: &str, max_width: f32) -> Vec<&str> {
+    text::wrap(text, max_width, measure).into_iter().map(|line: Line| &text[line.range]).collect()
+}
+
+#[test]
+fn wraps_at_spaces_and_keeps_blank_lines() {
+    assert_eq!(lines("one two three", 60.0), ["one ", "two ", "three"]);
+    assert_eq!(lines("one two three", 80.0), ["one two ", "three"]);
+    assert_eq!(lines("a\n\nb\n", 100.0), ["a", "", "b", ""]);
+    assert_eq!(lines("", 100.0), [""]);
+}
+
+#[test]
+fn long_words_split_between_graphemes() {
+    // an accent written as a combining mark stays with its letter
+    assert_eq!(lines("ne\u{301}e\u{301}abc", 30.0), ["ne\u{301}e\u{301}", "abc"]);
+    // a skin tone modifier stays with its emoji
+    assert_eq!(lines("👍🏽👍🏽", 10.0), ["👍🏽", "👍🏽"]);
+    assert_eq!(lines("abc", 0.0), ["a", "b", "c"]);
+}
+
+#[test]
+fn carets_move_over_graphemes_and_words() {
+    let text = "héllo, 👍🏽 world";
+    let thumb = "héllo, ".len();
+    assert_eq!(text::next_grapheme(text, thumb), thumb + "👍🏽".len());
+    assert_eq!(text::previous_grapheme(text, thumb + "👍🏽".len()), thumb);
+    assert_eq!(text::next_word(text, 0), "héllo".len());
+    assert_eq!(text::previous_word(text, text.len()), text.len() - "world".len());
+
+    // the end of a wrapped line is shown at the start of the next one
+    let text = "abc def";
+    let wrapped = text::wrap(text, 40.0, measure);
+    assert_eq!(text::line_end(text, &wrapped, 0), 3);
+    assert_eq!(text::line_at(&wrapped, 4), 1);
+    assert_eq!(text::offset_at_x(text, &wrapped, 1, 14.0, measure), 5);
+}
+
+#[test]
+fn edits_replace_the_selection() {
+    let mut text = String::from("hello world");
+    let mut cursor = TextCursor::at(5);
+    cursor.move_to(11, true);
+    assert_eq!(cursor.selected(&text), " world");
+    cursor.insert(&mut text, ", you");
+    assert_eq!((text.as_str(), cursor), ("hello, you", TextCursor::at(10)));
+
+    let word_start = text::previous_word(&text, cursor.caret);
+    assert!(cursor.delete_to(&mut text, word_start));
+    assert_eq!((text.as_str(), cursor), ("hello, ", TextCursor::at(7)));
+    assert!(!TextCursor::at(0).delete_to(&mut text, 0));
+}
+
+#[test]
+fn compositions_show_in_place_of_the_selection() {
+    let text = "say hello";
+    let mut cursor = TextCursor::at(4);
+    cursor.move_to(9, true);
+    let composition = Composition { text: String::from("こんにちは"), caret: "こん".len() };
+    let (shown, range) = cursor.preview(text, &composition);
+    assert_eq!(shown, "say こんにちは");
+    assert_eq!(&shown[range.clone()], composition.text);
+    assert_eq!(range.start + composition.caret, "say こん".len());
+    // nothing changes until the input method commits
+    assert_eq!(cursor.selected(text), "hello");
+}
@@ -0,0 +1,67 @@
+use std::f64::consts::FRAC_PI_2;
+
+use neo_scene::format::{self, NeoFile, View};
+use neo_scene::{Anchor, Color, Connector, Document, Endpoint, Point, Rect, Shape, ShapeKind, Style, Transform};
+
+fn close(a: Point, b: Point) -> bool {
+    a.distance(b) < 1e-9
+}
+
+fn bar() -> Shape {
+    Shape::new(ShapeKind::Rectangle, Rect::new(0.0, 0.0, 40.0, 10.0), Style::filled(Color::BLACK))
+}
+
+#[test]
+fn transforms_compose_and_invert() {
+    let turn = Transform::rotation(FRAC_PI_2).about(Point::new(10.0, 0.0));
+    assert!(close(turn.apply(Point::new(20.0, 0.0)), Point::new(10.0, 10.0)));
+    let both = turn.then(Transform::translation(Point::new(5.0, 0.0)));
+    assert!(close(both.apply(Point::new(20.0, 0.0)), Point::new(15.0, 10.0)));
+    let back = both.inverse().unwrap();
+    assert!(close(back.apply(both.apply(Point::new(3.0, -7.0))), Point::new(3.0, -7.0)));
+    assert_eq!(Transform::scaling(0.0, 1.0).inverse(), None);
+}
+
+#[test]
+fn a_turned_shape_is_hit_and_bounded_where_it_is_drawn() {
+    let mut document = Document::new();
+    let mut shape = bar();
+    shape.transform = Transform::rotation(FRAC_PI_2);
+    let id = document.push(shape);
+
+    // standing upright about its centre at (20, 5)
+    let bounds = document.get(id).unwrap().bounds().unwrap();
+    assert!(close(bounds.min(), Point::new(15.0, -15.0)) && close(bounds.max(), Point::new(25.0, 25.0)));
+    assert_eq!(document.topmost_at(Point::new(20.0, 20.0), 0.0), Some(id));
+    assert_eq!(document.topmost_at(Point::new(35.0, 5.0), 0.0), None);
+}
+
+#[test]
+fn resizing_goes_into_the_box_and_turning_into_the_transform() {
+    let mut document = Document::new();
+    let id = document.push(bar());
+    let connector = document.push(Connector::new(
+        Endpoint::bound(id, Anchor::Right, Rect::new(0.0, 0.0, 40.0, 10.0)),
+        Endpoint::free(Point::new(100.0, 100.0)),
+        Style::stroked(Color::BLACK, 2.0),
+    ));
+
+    document.get_mut(id).unwrap().apply_transform(Transform::rotation(FRAC_PI_2).about(Point::new(20.0, 5.0)));
+    document.get_mut(id).unwrap().apply_transform(Transform::scaling(1.0, 2.0).about(Point::new(20.0, 5.0)));
+    let shape = document.shape(id).unwrap();
+    // 40 long, now upright and stretched along its length
+    assert!((shape.bounds.width - 80.0).abs() < 1e-9 && (shape.bounds.height - 10.0).abs() < 1e-9);
+    assert!((shape.transform.rotation_angle() - FRAC_PI_2).abs() < 1e-9);
+    // the right-hand anchor turned to the bottom
+    assert!(close(document.connector(connector).unwrap().start.position, Point::new(20.0, 45.0)));
+}
+
+#[test]
+fn transforms_are_saved() {
+    let mut document = Document::with_site(3);
+    let mut shape = bar();
+    shape.transform = Transform::skewing(0.3, 0.0).then(Transform::scaling(-1.0, 1.0));
+    document.push(shape);
+    let file = NeoFile { document, view: View::default(), history: None };
+    assert_eq!(format::from_str(&format::to_string(&file)).unwrap(), file);
+}
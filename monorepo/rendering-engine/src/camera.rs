@@ -2,6 +2,10 @@ use macroquad::{
     math::{vec2, Vec2},
     window::{screen_height, screen_width},
 };
+use neo_scene::{Point, Transform};
+
+pub const MIN_ZOOM: f32 = 0.1;
+pub const MAX_ZOOM: f32 = 8.0;
 
 pub struct Camera {
     pub position: Vec2,
@@ -16,6 +20,18 @@ impl Camera {
         }
     }
 
+    /// Looks at `position` at `zoom`, as read from a file or set by a plugin. The zoom is kept
+    /// between [`MIN_ZOOM`] and [`MAX_ZOOM`], and a value that isn't a finite number is ignored,
+    /// so a damaged one can't leave the canvas unusable.
+    pub fn set_view(&mut self, position: Vec2, zoom: f32) {
+        if position.is_finite() {
+            self.position = position;
+        }
+        if zoom.is_finite() {
+            self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        }
+    }
+
     pub fn world_to_screen(&self, world_pos: Vec2) -> Vec2 {
         (world_pos - self.position) * self.zoom + vec2(screen_width(), screen_height()) * 0.5
     }
@@ -23,4 +39,19 @@ impl Camera {
     pub fn screen_to_world(&self, screen_pos: Vec2) -> Vec2 {
         (screen_pos - vec2(screen_width(), screen_height()) * 0.5) / self.zoom + self.position
     }
+
+    /// Takes world points to the screen, as [`Camera::world_to_screen`] does.
+    pub fn transform(&self) -> Transform {
+        let center = vec2(screen_width(), screen_height()) * 0.5 - self.position * self.zoom;
+        Transform::scaling(self.zoom as f64, self.zoom as f64)
+            .then(Transform::translation(Point::new(center.x as f64, center.y as f64)))
+    }
+
+    /// World-space corners (top left, bottom right) of what is currently on screen.
+    pub fn visible_world_bounds(&self) -> (Vec2, Vec2) {
+        (
+            self.screen_to_world(Vec2::ZERO),
+            self.screen_to_world(vec2(screen_width(), screen_height())),
+        )
+    }
 }
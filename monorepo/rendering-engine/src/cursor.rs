@@ -1,36 +1,54 @@
-use macroquad::{color::WHITE, input::{mouse_position, show_mouse}, texture::{draw_texture, Texture2D}, window::{screen_height, screen_width}};
-
-use crate::{canvas_state::CanvasState, user_action_mode::UserActionMode};
+use macroquad::{color::{BLACK, WHITE}, input::{mouse_position, show_mouse}, shapes::draw_line, texture::{draw_texture, Texture2D}, window::{screen_height, screen_width}};
 
+/// Pointer shapes the tools can ask for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CursorIcon {
+    /// the system pointer
+    Arrow,
+    Crosshair,
+    IBeam,
+    Hand,
+    Grab,
+}
 
+/// Half the length of the drawn crosshair and I-beam, in pixels.
+const CURSOR_RADIUS: f32 = 8.0;
 
-pub fn handle_cursor(mode: &UserActionMode, state: &CanvasState, cursors: &Cursors) {
+pub fn handle_cursor(icon: CursorIcon, cursors: &Cursors) {
     let (mouse_x, mouse_y) = mouse_position();
     let window_width = screen_width();
     let window_height = screen_height();
 
-    if mouse_x >= 0.0 && mouse_x < window_width && mouse_y >= 0.0 && mouse_y < window_height {
+    if icon != CursorIcon::Arrow && mouse_x >= 0.0 && mouse_x < window_width && mouse_y >= 0.0 && mouse_y < window_height {
         // Mouse is inside the window
         show_mouse(false);
-        draw_cursor(mode, state, cursors);
+        draw_cursor(icon, cursors);
     } else {
-        // Mouse is outside the window
+        // Mouse is outside the window, or the system pointer is wanted
         show_mouse(true);
     }
 }
 
-pub fn draw_cursor(mode: &UserActionMode, state: &CanvasState, cursors: &Cursors) {
+pub fn draw_cursor(icon: CursorIcon, cursors: &Cursors) {
     let (mouse_x, mouse_y) = mouse_position();
-    let cursor_texture = match mode {
-        UserActionMode::DRAG => {
-            if state.is_dragging {
-                &cursors.grab
-            } else {
-                &cursors.hand
-            }
+    let cursor_texture = match icon {
+        CursorIcon::Arrow => return,
+        CursorIcon::Crosshair => {
+            draw_line(mouse_x - CURSOR_RADIUS, mouse_y, mouse_x + CURSOR_RADIUS, mouse_y, 1.0, BLACK);
+            draw_line(mouse_x, mouse_y - CURSOR_RADIUS, mouse_x, mouse_y + CURSOR_RADIUS, 1.0, BLACK);
+            return;
+        }
+        CursorIcon::IBeam => {
+            let (top, bottom) = (mouse_y - CURSOR_RADIUS, mouse_y + CURSOR_RADIUS);
+            draw_line(mouse_x, top, mouse_x, bottom, 1.0, BLACK);
+            draw_line(mouse_x - 3.0, top, mouse_x + 3.0, top, 1.0, BLACK);
+            draw_line(mouse_x - 3.0, bottom, mouse_x + 3.0, bottom, 1.0, BLACK);
+            return;
         }
+        CursorIcon::Hand => &cursors.hand,
+        CursorIcon::Grab => &cursors.grab,
     };
-    
+
     draw_texture(
         *cursor_texture,
         mouse_x - cursor_texture.width() / 2.0,
@@ -42,4 +60,4 @@ pub fn draw_cursor(mode: &UserActionMode, state: &CanvasState, cursors: &Cursors
 pub struct Cursors {
     pub hand: Texture2D,
     pub grab: Texture2D,
-}
\ No newline at end of file
+}
@@ -0,0 +1,185 @@
+//! Fonts for shape text: TTF and OTF files from the fonts directory or embedded in the open
+//! document. Each shape picks its fonts with a [`TextStyle`], and characters a font has no
+//! glyph for fall back to the next font in line, ending with macroquad's built-in font.
+
+use std::{
+    error::Error,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use macroquad::{
+    color::Color,
+    text::{draw_text_ex, load_ttf_font_from_bytes, measure_text, Font, TextParams},
+};
+use neo_scene::font::{EmbeddedFont, TextStyle};
+use owned_ttf_parser::{name_id, AsFaceRef, OwnedFace};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Where font files are loaded from, relative to the working directory.
+pub const FONTS_DIR: &str = "fonts";
+
+/// One loaded font file.
+struct Face {
+    family: String,
+    weight: u16,
+    italic: bool,
+    font: Font,
+    /// The same file parsed again, for the glyphs it covers, which macroquad doesn't tell.
+    parsed: OwnedFace,
+}
+
+impl Face {
+    fn has_glyphs(&self, grapheme: &str) -> bool {
+        let face = self.parsed.as_face_ref();
+        // joiners and variation selectors only matter if the font has the rest
+        grapheme.chars().filter(|c| !matches!(c, '\u{200d}' | '\u{fe00}'..='\u{fe0f}')).all(|c| face.glyph_index(c).is_some())
+    }
+
+    /// How far the face is from the one asked for: the wrong slant counts for more than any
+    /// difference in weight.
+    fn distance(&self, weight: u16, italic: bool) -> u32 {
+        (self.italic != italic) as u32 * 1000 + self.weight.abs_diff(weight) as u32
+    }
+}
+
+/// Every font available to shape text.
+#[derive(Default)]
+pub struct FontLibrary {
+    faces: Vec<Face>,
+}
+
+impl FontLibrary {
+    /// Loads every `.ttf` and `.otf` file in `dir`, in file name order. Files that fail to load
+    /// are left out and described in the returned messages; a missing directory just means no
+    /// fonts.
+    pub fn load_dir(dir: &Path) -> (Self, Vec<String>) {
+        let mut library = FontLibrary::default();
+        let mut errors = Vec::new();
+        let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf")))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+        for path in paths {
+            if let Err(e) = fs::read(&path).map_err(Into::into).and_then(|data| library.add(data)) {
+                errors.push(format!("{}: {e}", path.display()));
+            }
+        }
+        (library, errors)
+    }
+
+    /// Adds a font file under the family, weight and slant it declares. A face that is already
+    /// loaded is kept, so fonts installed locally win over copies embedded in documents.
+    pub fn add(&mut self, data: Vec<u8>) -> Result<(), Box<dyn Error>> {
+        let parsed = OwnedFace::from_vec(data, 0)?;
+        let face = parsed.as_face_ref();
+        let family = [name_id::TYPOGRAPHIC_FAMILY, name_id::FAMILY]
+            .into_iter()
+            .find_map(|id| face.names().into_iter().filter(|name| name.name_id == id).find_map(|name| name.to_string()))
+            .ok_or("the font has no family name")?;
+        let (weight, italic) = (face.weight().to_number(), face.is_italic() || face.is_oblique());
+        if self.faces.iter().any(|face| face.family == family && face.weight == weight && face.italic == italic) {
+            return Ok(());
+        }
+        let font = load_ttf_font_from_bytes(parsed.as_slice())?;
+        self.faces.push(Face { family, weight, italic, font, parsed });
+        Ok(())
+    }
+
+    /// Adds the fonts saved with a document, returning messages for any that couldn't be read.
+    pub fn add_embedded(&mut self, fonts: &[EmbeddedFont]) -> Vec<String> {
+        fonts
+            .iter()
+            .filter_map(|font| self.add(font.data.clone()).err().map(|e| format!("embedded font {}: {e}", font.family)))
+            .collect()
+    }
+
+    /// Names of the loaded families, sorted.
+    pub fn families(&self) -> Vec<&str> {
+        let mut families: Vec<&str> = self.faces.iter().map(|face| face.family.as_str()).collect();
+        families.sort_unstable();
+        families.dedup();
+        families
+    }
+
+    /// The face of `family` closest to the weight and slant asked for.
+    fn closest(&self, family: &str, weight: u16, italic: bool) -> Option<&Face> {
+        self.faces
+            .iter()
+            .filter(|face| face.family.eq_ignore_ascii_case(family))
+            .min_by_key(|face| face.distance(weight, italic))
+    }
+
+    /// The font files text in `style` is drawn with, to save them with a document.
+    pub fn embeddable(&self, style: &TextStyle) -> Vec<EmbeddedFont> {
+        style
+            .families
+            .iter()
+            .filter_map(|family| self.closest(family, style.weight, style.italic))
+            .map(|face| EmbeddedFont {
+                family: face.family.clone(),
+                weight: face.weight,
+                italic: face.italic,
+                data: face.parsed.as_slice().to_vec(),
+            })
+            .collect()
+    }
+
+    /// The fonts to draw `style` with, in fallback order: the style's own families, then every
+    /// other loaded family, then the built-in font.
+    pub fn chain(&self, style: &TextStyle) -> FontChain<'_> {
+        let mut faces: Vec<&Face> = Vec::new();
+        let others = self.families().into_iter().filter(|family| !style.families.iter().any(|own| own.eq_ignore_ascii_case(family)));
+        for family in style.families.iter().map(String::as_str).chain(others) {
+            if let Some(face) = self.closest(family, style.weight, style.italic) {
+                if !faces.iter().any(|other| std::ptr::eq(*other, face)) {
+                    faces.push(face);
+                }
+            }
+        }
+        FontChain { faces }
+    }
+}
+
+/// Fonts in fallback order for one [`TextStyle`]. Text is split into runs that each use the
+/// first font with glyphs for all of it, so measuring and drawing always agree.
+pub struct FontChain<'a> {
+    faces: Vec<&'a Face>,
+}
+
+impl FontChain<'_> {
+    /// Splits `text` into runs drawn with one font each, by grapheme so an accent or emoji
+    /// modifier stays with the character it belongs to.
+    fn runs(&self, text: &str) -> Vec<(Range<usize>, Font)> {
+        let mut runs: Vec<(Range<usize>, Font)> = Vec::new();
+        for (start, grapheme) in text.grapheme_indices(true) {
+            let font = self.faces.iter().find(|face| face.has_glyphs(grapheme)).map_or_else(Font::default, |face| face.font);
+            let end = start + grapheme.len();
+            match runs.last_mut() {
+                Some((range, last)) if *last == font => range.end = end,
+                _ => runs.push((start..end, font)),
+            }
+        }
+        runs
+    }
+
+    /// Width of `text` in pixels at `font_size`.
+    pub fn measure(&self, text: &str, font_size: f32) -> f32 {
+        self.runs(text).into_iter().map(|(range, font)| measure_text(&text[range], Some(font), font_size as u16, 1.0).width).sum()
+    }
+
+    /// Draws `text` with its left end of the baseline at (`x`, `y`).
+    pub fn draw(&self, text: &str, x: f32, y: f32, font_size: f32, color: Color) {
+        let mut x = x;
+        for (range, font) in self.runs(text) {
+            let run = &text[range];
+            draw_text_ex(run, x, y, TextParams { font, font_size: font_size as u16, color, ..Default::default() });
+            x += measure_text(run, Some(font), font_size as u16, 1.0).width;
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use macroquad::{color::Color, math::{vec2, Vec2}, shapes::draw_circle, window::{screen_height, screen_width}};
+use macroquad::{color::Color, math::vec2, shapes::draw_circle};
 
 use crate::camera::Camera;
 
@@ -8,13 +8,17 @@ const NORMAL_DOT_COLOR: Color = Color::new(0.7, 0.9, 1.0, 1.0);
 const EMPHASIZED_DOT_COLOR: Color = Color::new(0.4, 0.7, 0.9, 1.0);
 pub const BACKGROUND_COLOR: Color = Color::new(0.95, 0.96, 0.98, 1.0);
 
+/// World distance between the dots drawn at the current zoom. Zoomed out, dots are spread
+/// further apart so they don't crowd together; this is also the spacing objects snap to.
+pub fn grid_step(camera: &Camera) -> f32 {
+    let zoom_factor = 1.0 / camera.zoom;
+    (GRID_SIZE * zoom_factor.max(1.0)).round()
+}
+
 pub fn draw_grid(camera: &Camera) {
-    let top_left = camera.screen_to_world(Vec2::ZERO);
-    let bottom_right = camera.screen_to_world(vec2(screen_width(), screen_height()));
+    let (top_left, bottom_right) = camera.visible_world_bounds();
 
-    let base_step = GRID_SIZE;
-    let zoom_factor = 1.0 / camera.zoom;
-    let step = (base_step * zoom_factor.max(1.0)).round() as i32;
+    let step = grid_step(camera) as i32;
 
     let start_x = (top_left.x / step as f32).floor() as i32 * step;
     let start_y = (top_left.y / step as f32).floor() as i32 * step;
@@ -3,7 +3,8 @@ use macroquad::{color::BLACK, text::draw_text};
 use crate::camera::Camera;
 
 
-pub fn display_hud(camera: &Camera) {
+/// Camera readout plus the latest status message, such as the result of a save.
+pub fn display_hud(camera: &Camera, status: &str) {
     draw_text(
         &format!(
             "Camera: ({:.2}, {:.2}), Zoom: {:.2}",
@@ -14,4 +15,5 @@ pub fn display_hud(camera: &Camera) {
         20.0,
         BLACK,
     );
+    draw_text(status, 10.0, 40.0, 20.0, BLACK);
 }
\ No newline at end of file
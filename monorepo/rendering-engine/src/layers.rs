@@ -0,0 +1,298 @@
+use std::collections::HashSet;
+
+use egui_macroquad::egui;
+use macroquad::window::screen_width;
+use neo_scene::{layer, order, Document, Group, Layer, Object, ObjectId, Revision, ShapeKind};
+
+use crate::tools::ToolContext;
+
+/// Indent for each level of nesting, in points.
+const INDENT: f32 = 16.0;
+/// Height of every row, in points, tall enough for the rename field.
+const ROW_HEIGHT: f32 = 20.0;
+/// Longest label made from a shape's text before it is cut short, in characters.
+const LABEL_LENGTH: usize = 24;
+const PANEL_WIDTH: f32 = 240.0;
+
+/// Where a dragged row would land, in the panel's front-to-back order.
+#[derive(Clone, Copy, PartialEq)]
+enum Landing {
+    Above(ObjectId),
+    Below(ObjectId),
+    /// In front of everything inside the group.
+    Into(ObjectId),
+}
+
+/// What a row asked for this frame, applied once the row is laid out.
+enum RowAction {
+    Fold,
+    Hide,
+    Lock,
+    Pick { add: bool },
+    Rename,
+    Renamed,
+}
+
+/// A row of the panel: an object and how deeply it is nested in groups.
+#[derive(Clone, Copy)]
+struct Row {
+    id: ObjectId,
+    depth: usize,
+}
+
+/// The document as a tree, front to back, with groups folding open. Rows are picked by
+/// clicking, renamed by double-clicking and restacked by dragging, also into and out of
+/// groups; the eye and lock hide an object or stop it from being picked on the canvas. The
+/// groups at the top level are the layers: a group's flags cover everything inside it.
+///
+/// Only the rows in view are laid out. The list of rows is built again only when the
+/// document's tree or a fold changes, so a long document costs little while nothing happens.
+#[derive(Default)]
+pub struct LayersPanel {
+    /// Groups whose rows are folded away.
+    folded: HashSet<ObjectId>,
+    /// The row being renamed, the name typed so far, and whether the field has had focus yet.
+    renaming: Option<(ObjectId, String, bool)>,
+    dragging: Option<ObjectId>,
+    /// Every row that isn't folded away, front to back.
+    rows: Vec<Row>,
+    /// The document tree `rows` were built from; `None` after a fold changes.
+    built: Option<Revision>,
+}
+
+impl LayersPanel {
+    pub fn new() -> Self {
+        LayersPanel::default()
+    }
+
+    pub fn show(&mut self, egui_ctx: &egui::Context, ctx: &mut ToolContext) {
+        if self.renaming.as_ref().is_some_and(|(id, _, _)| !ctx.document.contains(*id)) {
+            self.renaming = None;
+        }
+        egui::Window::new("Layers").default_pos([screen_width() - PANEL_WIDTH, 20.0]).default_width(PANEL_WIDTH).show(
+            egui_ctx,
+            |ui| {
+                let button = ui.button("New layer").on_hover_text("An empty group to drag objects into");
+                if button.clicked() {
+                    new_layer(ctx);
+                }
+                ui.separator();
+                if self.built != Some(ctx.document.tree_revision()) {
+                    self.rows.clear();
+                    self.build_rows(ctx.document, None, 0);
+                    self.built = Some(ctx.document.tree_revision());
+                }
+                let pitch = ROW_HEIGHT + ui.spacing().item_spacing.y;
+                egui::ScrollArea::vertical().show_rows(ui, ROW_HEIGHT, self.rows.len(), |ui, range| {
+                    // where the first row would be, scrolled out of view or not
+                    let top = ui.max_rect().top() - range.start as f32 * pitch;
+                    for index in range {
+                        self.row(ui, ctx, self.rows[index]);
+                    }
+                    self.drag(ui, ctx, top, pitch);
+                });
+            },
+        );
+    }
+
+    /// Lists what is inside `parent`, or the top level, front to back, skipping folded groups.
+    fn build_rows(&mut self, document: &Document, parent: Option<ObjectId>, depth: usize) {
+        for id in document.children(parent).into_iter().rev() {
+            self.rows.push(Row { id, depth });
+            if document.group(id).is_some() && !self.folded.contains(&id) {
+                self.build_rows(document, Some(id), depth + 1);
+            }
+        }
+    }
+
+    /// Lays out one row and does what was asked of it.
+    fn row(&mut self, ui: &mut egui::Ui, ctx: &mut ToolContext, Row { id, depth }: Row) {
+        // every row takes the same height, so the scroll area can tell where each one is
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), ROW_HEIGHT), egui::Sense::hover());
+        let Some(object) = ctx.document.get(id) else {
+            return;
+        };
+        let mut ui = ui.child_ui(rect, egui::Layout::left_to_right(egui::Align::Center));
+        ui.add_space(depth as f32 * INDENT);
+        let group = matches!(object, Object::Group(_));
+        let folded = self.folded.contains(&id);
+        let mut action = None;
+        if group && ui.small_button(if folded { "+" } else { "-" }).clicked() {
+            action = Some(RowAction::Fold);
+        }
+        let Layer { hidden, locked, .. } = *object.layer();
+        if ui.selectable_label(!hidden, "👁").on_hover_text(if hidden { "Show" } else { "Hide" }).clicked() {
+            action = Some(RowAction::Hide);
+        }
+        if ui.selectable_label(locked, "🔒").on_hover_text(if locked { "Unlock" } else { "Lock" }).clicked() {
+            action = Some(RowAction::Lock);
+        }
+        match &mut self.renaming {
+            Some((renaming, name, focused)) if *renaming == id => {
+                // rows are laid out by where they are in view, so the field is named after its
+                // object to keep its focus while the list scrolls
+                let field = ui.add(egui::TextEdit::singleline(name).id(egui::Id::new(("rename", id))));
+                if !*focused {
+                    field.request_focus();
+                    *focused = true;
+                } else if field.lost_focus() {
+                    action = Some(RowAction::Renamed);
+                }
+            }
+            _ => {
+                // rows that can't be picked on the canvas are greyed out, groups' flags included
+                let mut text = egui::RichText::new(label(object));
+                if !layer::is_pickable(ctx.document, id) {
+                    text = text.weak();
+                }
+                let row = egui::SelectableLabel::new(ctx.selection.contains(id), text);
+                let row = ui.add(row).interact(egui::Sense::drag());
+                if row.double_clicked() {
+                    action = Some(RowAction::Rename);
+                } else if row.clicked() {
+                    action = Some(RowAction::Pick { add: ui.input(|input| input.modifiers.shift) });
+                } else if row.drag_started() {
+                    self.dragging = Some(id);
+                }
+            }
+        }
+        match action {
+            Some(RowAction::Fold) => {
+                if folded {
+                    self.folded.remove(&id);
+                } else {
+                    self.folded.insert(id);
+                }
+                self.built = None;
+            }
+            Some(RowAction::Hide) => edit_layer(ctx, id, |layer| layer.hidden = !layer.hidden),
+            Some(RowAction::Lock) => edit_layer(ctx, id, |layer| layer.locked = !layer.locked),
+            // hidden and locked objects can't be worked on, from here or the canvas
+            Some(RowAction::Pick { .. }) if !layer::is_pickable(ctx.document, id) => {}
+            Some(RowAction::Pick { add }) => {
+                if add {
+                    ctx.selection.toggle(id);
+                } else {
+                    ctx.selection.set(id);
+                }
+                // the canvas goes on picking among its siblings
+                ctx.selection.enter(ctx.document.parent(id));
+            }
+            Some(RowAction::Rename) => {
+                let name = ctx.document.get(id).map_or_else(String::new, |object| object.layer().name.clone());
+                self.renaming = Some((id, name, false));
+            }
+            Some(RowAction::Renamed) => {
+                // Escape leaves the name as it was
+                let cancelled = ui.input(|input| input.key_pressed(egui::Key::Escape));
+                if let Some((_, name, _)) = self.renaming.take().filter(|_| !cancelled) {
+                    edit_layer(ctx, id, |layer| layer.name = name.trim().to_owned());
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Marks where the dragged row would land, and moves it there when the button comes up.
+    /// Rows are `pitch` apart from `top`, so the row under the pointer is found by dividing.
+    fn drag(&mut self, ui: &mut egui::Ui, ctx: &mut ToolContext, top: f32, pitch: f32) {
+        let Some(id) = self.dragging else {
+            return;
+        };
+        let pointer = ui.input(|input| input.pointer.interact_pos()).filter(|pointer| ui.clip_rect().contains(*pointer));
+        let target = pointer.and_then(|pointer| {
+            let rows = (pointer.y - top) / pitch;
+            let row = self.rows.get(rows.floor() as usize).filter(|_| rows >= 0.0)?;
+            if row.id == id {
+                return None;
+            }
+            let fraction = rows.fract();
+            let landing = match fraction {
+                _ if ctx.document.group(row.id).is_some() && (0.25..0.75).contains(&fraction) => Landing::Into(row.id),
+                _ if fraction < 0.5 => Landing::Above(row.id),
+                _ => Landing::Below(row.id),
+            };
+            Some((landing, top + rows.floor() * pitch))
+        });
+        if let Some((landing, row_top)) = target {
+            let rect = egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), row_top..=row_top + ROW_HEIGHT);
+            let (painter, stroke, gap) = (ui.painter(), ui.visuals().selection.stroke, ui.spacing().item_spacing.y / 2.0);
+            match landing {
+                Landing::Above(_) => painter.hline(rect.x_range(), rect.top() - gap, stroke),
+                Landing::Below(_) => painter.hline(rect.x_range(), rect.bottom() + gap, stroke),
+                Landing::Into(_) => painter.rect_stroke(rect, 0.0, stroke),
+            }
+        }
+        if !ui.input(|input| input.pointer.any_released()) {
+            return;
+        }
+        self.dragging = None;
+        let Some((target, _)) = target else {
+            return;
+        };
+        // the panel lists front to back, the document back to front
+        let open_group = |id| ctx.document.group(id).is_some() && !self.folded.contains(&id);
+        let (parent, index) = match target {
+            Landing::Into(group) => (Some(group), ctx.document.children(Some(group)).len()),
+            // below an open group's row is above its first child, which is in front in the group
+            Landing::Below(group) if open_group(group) && !ctx.document.children(Some(group)).is_empty() => {
+                (Some(group), ctx.document.children(Some(group)).len())
+            }
+            Landing::Above(over) | Landing::Below(over) => {
+                let parent = ctx.document.parent(over);
+                let below = ctx.document.children(parent).iter().position(|sibling| *sibling == over).unwrap_or(0);
+                (parent, if matches!(target, Landing::Above(_)) { below + 1 } else { below })
+            }
+        };
+        ctx.history.commit(ctx.document);
+        if order::move_to(ctx.document, ctx.history, id, parent, index) {
+            ctx.history.commit(ctx.document);
+        }
+    }
+}
+
+/// Changes the name or flags of `id` as an undo step of its own.
+fn edit_layer(ctx: &mut ToolContext, id: ObjectId, edit: impl FnOnce(&mut Layer)) {
+    ctx.history.commit(ctx.document);
+    ctx.history.track(ctx.document, id);
+    if let Some(mut object) = ctx.document.get_mut(id) {
+        edit(object.layer_mut());
+    }
+    ctx.history.commit(ctx.document);
+    ctx.selection.retain_pickable(ctx.document);
+}
+
+/// Adds an empty group on top of everything, named after how many layers there are.
+fn new_layer(ctx: &mut ToolContext) {
+    let layers = ctx.document.children(None).into_iter().filter(|id| ctx.document.group(*id).is_some()).count();
+    let layer = Layer { name: format!("Layer {}", layers + 1), ..Layer::default() };
+    ctx.history.commit(ctx.document);
+    let id = ctx.document.push(Group { layer, ..Group::default() });
+    ctx.history.track_created(id);
+    ctx.history.commit(ctx.document);
+}
+
+/// The name the user gave an object, or one made up from what it is.
+fn label(object: &Object) -> String {
+    let name = &object.layer().name;
+    if !name.is_empty() {
+        return name.clone();
+    }
+    match object {
+        Object::Shape(shape) if !shape.text.trim().is_empty() => {
+            let line = shape.text.trim().lines().next().unwrap_or_default();
+            let mut label: String = line.chars().take(LABEL_LENGTH).collect();
+            if line.chars().count() > LABEL_LENGTH {
+                label.push('…');
+            }
+            label
+        }
+        Object::Shape(shape) => match shape.kind {
+            ShapeKind::Rectangle => "Rectangle".to_owned(),
+            ShapeKind::Ellipse => "Ellipse".to_owned(),
+        },
+        Object::Stroke(_) => "Stroke".to_owned(),
+        Object::Connector(_) => "Connector".to_owned(),
+        Object::Group(_) => "Group".to_owned(),
+    }
+}
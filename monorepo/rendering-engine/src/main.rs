@@ -1,6 +1,8 @@
-use canvas_state::CanvasState;
-use cursor::{draw_cursor, handle_cursor, Cursors};
+use cursor::{handle_cursor, CursorIcon, Cursors};
+use std::path::Path;
+
 use macroquad::prelude::*;
+use neo_scene::{history::History, plugin::PluginHost, selection::Selection, Document};
 
 mod camera;
 use camera::Camera;
@@ -8,51 +10,175 @@ use camera::Camera;
 mod grid;
 use grid::draw_grid;
 use scrollbar::{draw_scrollbar, handle_scroll, ScrollBarConfig};
-use user_action_mode::{observe_user_action, UserActionMode};
+use user_action_mode::UserActionMode;
 
 mod scrollbar;
 mod user_action_mode;
-mod canvas_state;
 mod cursor;
 mod info_hud;
 use info_hud::display_hud;
+mod scene_renderer;
+use scene_renderer::draw_document;
+mod persistence;
+mod undo;
+use undo::handle_undo_shortcuts;
+mod text_input;
+use text_input::TextInputs;
+mod fonts;
+use fonts::FontLibrary;
+mod text_layout;
+mod tools;
+use tools::{ToolContext, Toolbox};
+mod toolbar;
+use toolbar::{arrange_command, arrange_shortcut, show_toolbar};
+mod layers;
+use layers::LayersPanel;
+mod plugins;
+use plugins::{collect_input, with_plugin_context, MacroquadDraw};
+mod wasm_plugins;
 
 
 #[macroquad::main(window_conf)]
 async fn main() {
     let mut camera = Camera::new();
     let scroll_bar_config: ScrollBarConfig = ScrollBarConfig::new();
-    let mut canvas_state = CanvasState {
-        is_dragging: false,
-        last_mouse_position: Vec2::ZERO,
-    };
-    let current_user_action_mode = UserActionMode::DRAG;
+    let mut toolbox = Toolbox::new(UserActionMode::Select);
+    let mut selection = Selection::new();
+    let mut layers = LayersPanel::new();
+    // seed from the clock so every session mints ids under a different site
+    rand::srand((macroquad::miniquad::date::now() * 1000.0) as u64);
+    let mut document = Document::with_site(rand::rand());
+    let mut history = History::default();
+    let document_path = persistence::document_path();
+    let mut status = persistence::open(
+        &document_path,
+        &mut document,
+        &mut history,
+        &mut camera,
+        &mut toolbox.mode,
+    );
 
     // Load cursor images, path is given from root Cargo.toml not package level
     let cursors = Cursors {
-        hand: load_texture("rendering-engine/src/assets/hand_cursor.png").await.unwrap(),
-        grab: load_texture("rendering-engine/src/assets/grab_cursor.png").await.unwrap(),
+        hand: load_texture("monorepo/rendering-engine/src/assets/hand_cursor.png").await.unwrap(),
+        grab: load_texture("monorepo/rendering-engine/src/assets/grab_cursor.png").await.unwrap(),
     };
 
-        // Hide the default system cursor
-        show_mouse(false);
+    let (mut fonts, mut font_errors) = FontLibrary::load_dir(Path::new(fonts::FONTS_DIR));
+    font_errors.extend(fonts.add_embedded(document.fonts()));
+    for error in &font_errors {
+        eprintln!("font not loaded: {error}");
+    }
+    if !font_errors.is_empty() {
+        status = format!("{} font(s) failed to load, see the terminal", font_errors.len());
+    }
+
+    let mut text_inputs = TextInputs::new();
+    let mut plugins = PluginHost::new();
+    let input = collect_input(false, false);
+    let (wasm_plugins, plugin_errors) = wasm_plugins::load_plugins(Path::new(wasm_plugins::PLUGINS_DIR));
+    for error in &plugin_errors {
+        eprintln!("plugin not loaded: {error}");
+    }
+    if !plugin_errors.is_empty() {
+        status = format!("{} plugin(s) failed to load, see the terminal", plugin_errors.len());
+    }
+    for plugin in plugins::builtin_plugins().into_iter().chain(wasm_plugins) {
+        with_plugin_context(&mut document, &mut history, &mut camera, &input, |ctx| plugins.add(plugin, ctx));
+    }
+
+    // closing the window leaves the loop, so plugins get to clean up
+    prevent_quit();
+    while !is_quit_requested() {
+        let (ui_has_pointer, ui_has_keyboard) = {
+            let mut ctx = ToolContext::new(&mut document, &mut history, &mut camera, &mut selection, &fonts);
+            egui_macroquad::ui(|egui_ctx| {
+                show_toolbar(egui_ctx, &mut toolbox, &mut plugins, &mut ctx);
+                layers.show(egui_ctx, &mut ctx);
+                ctx.ui_has_pointer = egui_ctx.wants_pointer_input() || egui_ctx.is_pointer_over_area();
+                ctx.ui_has_keyboard = egui_ctx.wants_keyboard_input();
+            });
+            (ctx.ui_has_pointer, ctx.ui_has_keyboard)
+        };
+        let input = collect_input(ui_has_pointer, ui_has_keyboard);
+        with_plugin_context(&mut document, &mut history, &mut camera, &input, |ctx| plugins.begin_frame(ctx));
+
+        let mut ctx = ToolContext::new(&mut document, &mut history, &mut camera, &mut selection, &fonts);
+        ctx.ui_has_pointer = ui_has_pointer;
+        ctx.ui_has_keyboard = ui_has_keyboard;
+        ctx.text_input = text_inputs.take();
+        if !ui_has_pointer {
+            handle_scroll(&mouse_wheel(), ctx.camera);
+        }
+        if plugins.active_tool().is_none() {
+            toolbox.update(&mut ctx);
+            if !ctx.ui_has_keyboard && !toolbox.is_busy() {
+                if let Some(command) = arrange_shortcut() {
+                    arrange_command(command, &mut ctx);
+                }
+            }
+        } else if let Some(mode) = toolbox.pressed_shortcut(&ctx) {
+            // a built-in tool's shortcut takes the canvas back from the plugin tool
+            plugins.set_active_tool(None);
+            toolbox.switch(mode, &mut ctx);
+        }
+
+        // a focused text field, like a layer being renamed, gets Ctrl+Z and the rest itself
+        let (opened, undone) = if ui_has_keyboard {
+            (false, false)
+        } else {
+            let opened = persistence::handle_file_shortcuts(
+                &document_path,
+                &mut document,
+                &mut history,
+                &mut camera,
+                &mut toolbox.mode,
+                &mut status,
+            );
+            (opened, handle_undo_shortcuts(&mut history, &mut document))
+        };
+        if opened {
+            for error in fonts.add_embedded(document.fonts()) {
+                eprintln!("font not loaded: {error}");
+            }
+        }
+        if opened || undone {
+            selection.retain_existing(&document);
+            selection.retain_pickable(&document);
+            toolbox.reset(&mut ToolContext::new(&mut document, &mut history, &mut camera, &mut selection, &fonts));
+        }
+        // a step stays open while the mouse is held or a tool is mid-edit, e.g. typing
+        if !is_mouse_button_down(MouseButton::Left) && !toolbox.is_busy() {
+            history.commit(&document);
+        }
 
-    loop {
         clear_background(grid::BACKGROUND_COLOR);
         draw_grid(&camera);
-        handle_scroll(&mouse_wheel(), &mut camera);
+        draw_document(&document, &camera, &fonts);
+        if plugins.active_tool().is_none() {
+            toolbox.active().draw_preview(&ToolContext::new(&mut document, &mut history, &mut camera, &mut selection, &fonts));
+        }
+        with_plugin_context(&mut document, &mut history, &mut camera, &input, |ctx| {
+            plugins.render(ctx, &mut MacroquadDraw)
+        });
         draw_scrollbar(&scroll_bar_config, &camera);
+        display_hud(&camera, &status);
+        egui_macroquad::draw();
+        with_plugin_context(&mut document, &mut history, &mut camera, &input, |ctx| plugins.end_frame(ctx));
 
-        canvas_state = observe_user_action(&mut camera, &current_user_action_mode, canvas_state);
-
-        // Draw the appropriate cursor
-        draw_cursor(&current_user_action_mode, &canvas_state, &cursors);
-        // Handle cursor visibility and drawing
-        handle_cursor(&current_user_action_mode, &canvas_state, &cursors);
-        display_hud(&camera);
+        // Handle cursor visibility and drawing; panels get the system pointer
+        let cursor = if ui_has_pointer {
+            CursorIcon::Arrow
+        } else if plugins.active_tool().is_some() {
+            CursorIcon::Crosshair
+        } else {
+            toolbox.active().cursor()
+        };
+        handle_cursor(cursor, &cursors);
 
         next_frame().await
     }
+    plugins.destroy();
 }
 
 fn window_conf() -> Conf {
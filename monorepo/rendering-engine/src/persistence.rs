@@ -0,0 +1,112 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use macroquad::{
+    input::{is_key_down, is_key_pressed, KeyCode},
+    math::vec2,
+};
+use neo_scene::{
+    format::{self, NeoFile, View},
+    history::History,
+    Document,
+};
+
+use crate::{camera::Camera, user_action_mode::UserActionMode};
+
+const DEFAULT_DOCUMENT_PATH: &str = "untitled.neo";
+
+/// The `.neo` file to open, taken from the first command line argument.
+pub fn document_path() -> PathBuf {
+    std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DOCUMENT_PATH))
+}
+
+/// Reads a `.neo` file, or `None` when it doesn't exist yet.
+pub fn load(path: &Path) -> Result<Option<NeoFile>, Box<dyn Error>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(format::from_str(&contents)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn save(path: &Path, file: &NeoFile) -> Result<(), Box<dyn Error>> {
+    fs::write(path, format::to_string(file))?;
+    Ok(())
+}
+
+/// Snapshot of the document, its undo history and the current view, ready to be written out.
+pub fn to_file(document: &Document, history: &History, camera: &Camera, mode: &UserActionMode) -> NeoFile {
+    NeoFile {
+        document: document.clone(),
+        view: View {
+            center: neo_scene::Point::new(camera.position.x as f64, camera.position.y as f64),
+            zoom: camera.zoom as f64,
+            mode: mode.name().to_owned(),
+        },
+        history: Some(history.clone()),
+    }
+}
+
+/// Restores the saved view. An unknown mode keeps the current one, and so does a centre or zoom
+/// that isn't a usable number.
+pub fn apply_view(view: &View, camera: &mut Camera, mode: &mut UserActionMode) {
+    camera.set_view(vec2(view.center.x as f32, view.center.y as f32), view.zoom as f32);
+    if let Some(saved_mode) = UserActionMode::from_name(&view.mode) {
+        *mode = saved_mode;
+    }
+}
+
+/// Replaces the document, history and view with the contents of `path`. Returns the status line
+/// for the HUD; on failure the current document is left untouched.
+pub fn open(
+    path: &Path,
+    document: &mut Document,
+    history: &mut History,
+    camera: &mut Camera,
+    mode: &mut UserActionMode,
+) -> String {
+    match load(path) {
+        Ok(Some(file)) => {
+            let site = document.site();
+            *document = file.document;
+            document.set_site(site);
+            *history = file.history.unwrap_or_default();
+            apply_view(&file.view, camera, mode);
+            format!("Opened {}", path.display())
+        }
+        Ok(None) => format!("New file {}", path.display()),
+        Err(e) => format!("Could not open {}: {}", path.display(), e),
+    }
+}
+
+/// Ctrl+S saves the canvas to `path`, Ctrl+O reverts to what is on disk. Returns whether the
+/// document was reloaded.
+pub fn handle_file_shortcuts(
+    path: &Path,
+    document: &mut Document,
+    history: &mut History,
+    camera: &mut Camera,
+    mode: &mut UserActionMode,
+    status: &mut String,
+) -> bool {
+    if !(is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)) {
+        return false;
+    }
+    if is_key_pressed(KeyCode::S) {
+        history.commit(document);
+        *status = match save(path, &to_file(document, history, camera, mode)) {
+            Ok(()) => format!("Saved {}", path.display()),
+            Err(e) => format!("Could not save {}: {}", path.display(), e),
+        };
+    } else if is_key_pressed(KeyCode::O) {
+        *status = open(path, document, history, camera, mode);
+        return true;
+    }
+    false
+}
@@ -0,0 +1,131 @@
+//! Runs [`neo_scene::plugin`] plugins in the native engine: turns macroquad's input and camera
+//! into what plugins expect, and draws for them with macroquad.
+
+use macroquad::{
+    input::{is_key_down, is_key_pressed, is_mouse_button_down, is_mouse_button_pressed, is_mouse_button_released, mouse_position, mouse_wheel, KeyCode, MouseButton},
+    shapes::draw_line,
+    text::draw_text,
+    window::{screen_height, screen_width},
+};
+use neo_scene::{
+    history::History,
+    plugin::{Draw, Input, Plugin, PluginContext, Viewport},
+    Color, Document, Point, Rect, ShapeKind, Style,
+};
+
+use crate::{
+    camera::Camera,
+    scene_renderer::{draw_screen_shape, to_color, to_point, to_vec2},
+};
+
+/// Keys reported to plugins, with their browser `KeyboardEvent.key` names.
+const KEY_NAMES: [(KeyCode, &str); 48] = [
+    (KeyCode::A, "a"), (KeyCode::B, "b"), (KeyCode::C, "c"), (KeyCode::D, "d"),
+    (KeyCode::E, "e"), (KeyCode::F, "f"), (KeyCode::G, "g"), (KeyCode::H, "h"),
+    (KeyCode::I, "i"), (KeyCode::J, "j"), (KeyCode::K, "k"), (KeyCode::L, "l"),
+    (KeyCode::M, "m"), (KeyCode::N, "n"), (KeyCode::O, "o"), (KeyCode::P, "p"),
+    (KeyCode::Q, "q"), (KeyCode::R, "r"), (KeyCode::S, "s"), (KeyCode::T, "t"),
+    (KeyCode::U, "u"), (KeyCode::V, "v"), (KeyCode::W, "w"), (KeyCode::X, "x"),
+    (KeyCode::Y, "y"), (KeyCode::Z, "z"),
+    (KeyCode::Key0, "0"), (KeyCode::Key1, "1"), (KeyCode::Key2, "2"), (KeyCode::Key3, "3"),
+    (KeyCode::Key4, "4"), (KeyCode::Key5, "5"), (KeyCode::Key6, "6"), (KeyCode::Key7, "7"),
+    (KeyCode::Key8, "8"), (KeyCode::Key9, "9"),
+    (KeyCode::Space, " "), (KeyCode::Enter, "Enter"), (KeyCode::Escape, "Escape"),
+    (KeyCode::Backspace, "Backspace"), (KeyCode::Delete, "Delete"), (KeyCode::Tab, "Tab"),
+    (KeyCode::Left, "ArrowLeft"), (KeyCode::Right, "ArrowRight"), (KeyCode::Up, "ArrowUp"),
+    (KeyCode::Down, "ArrowDown"), (KeyCode::Home, "Home"), (KeyCode::End, "End"),
+];
+
+/// Pixels a plugin sees per notch of the wheel, close to what browsers report.
+const WHEEL_NOTCH: f64 = 100.0;
+
+/// Plugins compiled into the engine, installed at startup in this order. Add yours here.
+pub fn builtin_plugins() -> Vec<Box<dyn Plugin>> {
+    Vec::new()
+}
+
+/// This frame's input as plugins see it. Whatever the UI panels are using is left out, so
+/// plugins don't react to clicks on a panel or to typing in a text field.
+pub fn collect_input(ui_has_pointer: bool, ui_has_keyboard: bool) -> Input {
+    let (x, y) = mouse_position();
+    let inside = x >= 0.0 && y >= 0.0 && x < screen_width() && y < screen_height();
+    let canvas_pointer = inside && !ui_has_pointer;
+    let (wheel_x, wheel_y) = mouse_wheel();
+    let keys_pressed = if ui_has_keyboard {
+        Vec::new()
+    } else {
+        KEY_NAMES
+            .iter()
+            .filter(|(key, _)| is_key_pressed(*key))
+            .map(|(_, name)| name.to_string())
+            .collect()
+    };
+    Input {
+        pointer: inside.then(|| Point::new(x as f64, y as f64)),
+        primary_down: is_mouse_button_down(MouseButton::Left),
+        primary_pressed: canvas_pointer && is_mouse_button_pressed(MouseButton::Left),
+        primary_released: is_mouse_button_released(MouseButton::Left),
+        // macroquad reports notches with up being positive
+        wheel: if canvas_pointer {
+            Point::new(-wheel_x as f64, -wheel_y as f64) * WHEEL_NOTCH
+        } else {
+            Point::ZERO
+        },
+        keys_pressed,
+        shift: is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift),
+        ctrl: is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl),
+        alt: is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt),
+    }
+}
+
+/// Builds a plugin context around the engine's state and runs `f` with it, then moves the
+/// camera wherever the plugins moved the viewport, within the zoom range the canvas allows.
+pub fn with_plugin_context<R>(
+    document: &mut Document,
+    history: &mut History,
+    camera: &mut Camera,
+    input: &Input,
+    f: impl FnOnce(&mut PluginContext) -> R,
+) -> R {
+    let mut viewport = Viewport {
+        center: to_point(camera.position),
+        zoom: camera.zoom as f64,
+        width: screen_width() as f64,
+        height: screen_height() as f64,
+    };
+    let result = f(&mut PluginContext::new(document, history, &mut viewport, input));
+    camera.set_view(to_vec2(viewport.center), viewport.zoom as f32);
+    result
+}
+
+/// Plugin drawing straight to the macroquad window.
+pub struct MacroquadDraw;
+
+impl Draw for MacroquadDraw {
+    fn line(&mut self, from: Point, to: Point, width: f64, color: Color) {
+        draw_line(from.x as f32, from.y as f32, to.x as f32, to.y as f32, width as f32, to_color(color));
+    }
+
+    fn rect(&mut self, rect: Rect, style: Style) {
+        draw_styled(ShapeKind::Rectangle, rect, style);
+    }
+
+    fn ellipse(&mut self, bounds: Rect, style: Style) {
+        draw_styled(ShapeKind::Ellipse, bounds, style);
+    }
+
+    fn text(&mut self, text: &str, position: Point, size: f64, color: Color) {
+        draw_text(text, position.x as f32, position.y as f32, size as f32, to_color(color));
+    }
+}
+
+fn draw_styled(kind: ShapeKind, rect: Rect, style: Style) {
+    draw_screen_shape(
+        kind,
+        to_vec2(rect.min()),
+        to_vec2(rect.size()),
+        to_color(style.fill),
+        to_color(style.stroke),
+        style.stroke_width as f32,
+    );
+}
@@ -0,0 +1,165 @@
+use macroquad::{
+    color::{Color, BLACK},
+    math::{vec2, Vec2},
+    shapes::{draw_circle, draw_line, draw_rectangle, draw_rectangle_lines, draw_triangle},
+    window::get_internal_gl,
+};
+use neo_scene::{layer, Connector, Document, Object, Point, Rect, Shape, ShapeKind, Stroke};
+
+use crate::{camera::Camera, fonts::FontLibrary, text_layout::TextLayout};
+
+const ELLIPSE_SEGMENTS: usize = 48;
+/// Screen pixels around the window that are still drawn, so nothing pops in at the edges.
+const CULL_MARGIN: f32 = 64.0;
+/// Screen size below which shape text isn't drawn.
+const MIN_READABLE_TEXT: f32 = 3.0;
+
+pub fn to_vec2(point: Point) -> Vec2 {
+    vec2(point.x as f32, point.y as f32)
+}
+
+pub fn to_point(vec: Vec2) -> Point {
+    Point::new(vec.x as f64, vec.y as f64)
+}
+
+pub fn to_color(color: neo_scene::Color) -> Color {
+    Color::new(color.r, color.g, color.b, color.a)
+}
+
+/// Draws the objects on screen, back to front, through the camera. Everything else is culled
+/// using the document's spatial index, so the cost depends on what is visible rather than on
+/// the size of the document. Hidden objects are skipped.
+pub fn draw_document(document: &Document, camera: &Camera, fonts: &FontLibrary) {
+    let (top_left, bottom_right) = camera.visible_world_bounds();
+    let viewport = Rect::from_corners(
+        Point::new(top_left.x as f64, top_left.y as f64),
+        Point::new(bottom_right.x as f64, bottom_right.y as f64),
+    )
+    .expand((CULL_MARGIN / camera.zoom) as f64);
+
+    for (id, object) in document.iter_in(viewport) {
+        if layer::is_hidden(document, id) {
+            continue;
+        }
+        match object {
+            Object::Shape(shape) => draw_shape(shape, camera, fonts),
+            Object::Stroke(stroke) => draw_stroke(stroke, camera),
+            Object::Connector(connector) => draw_connector(connector, camera),
+            // drawn through its children
+            Object::Group(_) => {}
+        }
+    }
+}
+
+pub fn draw_shape(shape: &Shape, camera: &Camera, fonts: &FontLibrary) {
+    let fill = to_color(shape.style.fill);
+    let stroke = to_color(shape.style.stroke);
+    let stroke_width = shape.style.stroke_width as f32 * camera.zoom;
+    if shape.transform.is_identity() {
+        let top_left = camera.world_to_screen(to_vec2(shape.bounds.min()));
+        let size = to_vec2(shape.bounds.size()) * camera.zoom;
+        draw_screen_shape(shape.kind, top_left, size, fill, stroke, stroke_width);
+    } else {
+        // turned or slanted, so drawn as a polygon mapped straight onto the screen
+        let transform = shape.world_transform().then(camera.transform());
+        let outline: Vec<Vec2> = match shape.kind {
+            ShapeKind::Rectangle => transform.corners(shape.bounds).map(to_vec2).to_vec(),
+            ShapeKind::Ellipse => ellipse_outline(to_vec2(shape.bounds.center()), to_vec2(shape.bounds.size()) / 2.0)
+                .into_iter()
+                .map(|point| to_vec2(transform.apply(to_point(point))))
+                .collect(),
+        };
+        draw_polygon(&outline, fill, stroke, stroke_width);
+    }
+    draw_shape_text(shape, camera, fonts);
+}
+
+/// Draws a rectangle or ellipse given by its top left corner and size in screen pixels.
+pub fn draw_screen_shape(kind: ShapeKind, top_left: Vec2, size: Vec2, fill: Color, stroke: Color, stroke_width: f32) {
+    match kind {
+        ShapeKind::Rectangle => {
+            draw_rectangle(top_left.x, top_left.y, size.x, size.y, fill);
+            if stroke_width > 0.0 {
+                draw_rectangle_lines(top_left.x, top_left.y, size.x, size.y, stroke_width, stroke);
+            }
+        }
+        ShapeKind::Ellipse => draw_polygon(&ellipse_outline(top_left + size / 2.0, size / 2.0), fill, stroke, stroke_width),
+    }
+}
+
+/// Fills a convex polygon given in screen pixels as a fan from its centre, and outlines it.
+fn draw_polygon(outline: &[Vec2], fill: Color, stroke: Color, stroke_width: f32) {
+    let center = outline.iter().fold(Vec2::ZERO, |sum, point| sum + *point) / outline.len() as f32;
+    for i in 0..outline.len() {
+        let (a, b) = (outline[i], outline[(i + 1) % outline.len()]);
+        draw_triangle(center, a, b, fill);
+        if stroke_width > 0.0 {
+            draw_line(a.x, a.y, b.x, b.y, stroke_width, stroke);
+        }
+    }
+}
+
+fn draw_shape_text(shape: &Shape, camera: &Camera, fonts: &FontLibrary) {
+    // too small to read, and macroquad can't rasterize glyphs this size anyway
+    if shape.text.is_empty() || shape.text_style.size as f32 * camera.zoom < MIN_READABLE_TEXT {
+        return;
+    }
+    let layout = TextLayout::for_shape(shape, camera, fonts);
+    // the glyphs are laid out upright and drawn through the shape's full transform, so text in
+    // a skewed or flipped shape is skewed or flipped with it
+    unsafe { get_internal_gl() }.quad_gl.push_model_matrix(layout.model_matrix());
+    for row in 0..layout.lines.len() {
+        let start = layout.origin + vec2(0.0, row as f32 * layout.line_height);
+        layout.fonts.draw(layout.line_text(row), start.x, start.y, layout.font_size, BLACK);
+    }
+    unsafe { get_internal_gl() }.quad_gl.pop_model_matrix();
+}
+
+pub fn draw_stroke(stroke: &Stroke, camera: &Camera) {
+    let color = to_color(stroke.style.stroke);
+    let samples: Vec<(Vec2, f32)> = stroke
+        .samples()
+        .into_iter()
+        .map(|(point, width)| (camera.world_to_screen(to_vec2(point)), width as f32 * camera.zoom))
+        .collect();
+    for segment in samples.windows(2) {
+        let ((a, wa), (b, wb)) = (segment[0], segment[1]);
+        draw_line(a.x, a.y, b.x, b.y, (wa + wb) / 2.0, color);
+    }
+    // macroquad lines have square ends, so round off the joints of anything thick enough to
+    // show the gaps
+    for (point, width) in &samples {
+        if *width > 2.0 || samples.len() == 1 {
+            draw_circle(point.x, point.y, width / 2.0, color);
+        }
+    }
+}
+
+pub fn draw_connector(connector: &Connector, camera: &Camera) {
+    let color = to_color(connector.style.stroke);
+    let width = connector.style.stroke_width as f32 * camera.zoom;
+    let screen = |point: Point| camera.world_to_screen(to_vec2(point));
+    let path: Vec<Vec2> = connector.path().into_iter().map(screen).collect();
+    for segment in path.windows(2) {
+        draw_line(segment[0].x, segment[0].y, segment[1].x, segment[1].y, width, color);
+    }
+    // fill the notches square line ends leave at the bends
+    if width > 2.0 {
+        for point in path.iter().skip(1).take(path.len().saturating_sub(2)) {
+            draw_circle(point.x, point.y, width / 2.0, color);
+        }
+    }
+    for [tip, left, right] in connector.arrowheads() {
+        draw_triangle(screen(tip), screen(left), screen(right), color);
+    }
+}
+
+/// Points around an ellipse, used as a triangle fan since macroquad 0.3 has no ellipse primitive.
+fn ellipse_outline(center: Vec2, radii: Vec2) -> Vec<Vec2> {
+    (0..ELLIPSE_SEGMENTS)
+        .map(|i| {
+            let angle = i as f32 / ELLIPSE_SEGMENTS as f32 * std::f32::consts::TAU;
+            center + vec2(angle.cos() * radii.x, angle.sin() * radii.y)
+        })
+        .collect()
+}
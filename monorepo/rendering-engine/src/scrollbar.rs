@@ -2,10 +2,8 @@ use macroquad::{
     color::Color, input::{is_key_down, mouse_position, KeyCode}, math::Vec2, shapes::draw_rectangle, window::{screen_height, screen_width}
 };
 
-use crate::camera::Camera;
+use crate::camera::{Camera, MAX_ZOOM, MIN_ZOOM};
 
-const MIN_ZOOM: f32 = 0.1;
-const MAX_ZOOM: f32 = 8.0;
 const ZOOM_SPEED: f32 = 0.05;
 const SCROLL_SPEED: f32 = 2.0;
 
@@ -0,0 +1,83 @@
+//! Keyboard input for text editing. macroquad's `is_key_pressed` ignores key repeat and
+//! `get_char_pressed` drops modifiers, so typing listens to the raw miniquad events instead.
+//!
+//! TODO: composition on native. miniquad 0.3 has no input method hooks: its X11 backend reads
+//! keys without an input context, so neither the IME's preedit nor dead keys reach us. Where
+//! the platform turns what the IME commits into ordinary character events (Windows does) it is
+//! typed like anything else, but there is no [`neo_scene::text::Composition`] to draw at the
+//! caret as the browser build does. Drawing one needs a window backend that reports preedit
+//! and commit events; until then only the browser build handles composed text fully.
+
+use macroquad::{
+    input::{
+        get_char_pressed,
+        utils::{register_input_subscriber, repeat_all_miniquad_input},
+    },
+    miniquad::{self, EventHandler, KeyCode, KeyMods},
+    window::get_internal_gl,
+};
+
+/// One thing typed this frame.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextInput {
+    /// A printable character.
+    Char(char),
+    /// A key went down or repeated. `command` is Ctrl, or Cmd on a Mac, and `word` is the
+    /// modifier that moves by whole words.
+    Key { key: KeyCode, shift: bool, command: bool, word: bool },
+}
+
+/// Collects [`TextInput`] from the window's events. Call [`TextInputs::take`] every frame.
+pub struct TextInputs {
+    subscriber: usize,
+    typed: Vec<TextInput>,
+}
+
+impl TextInputs {
+    pub fn new() -> Self {
+        TextInputs { subscriber: register_input_subscriber(), typed: Vec::new() }
+    }
+
+    /// Everything typed since the last call.
+    pub fn take(&mut self) -> Vec<TextInput> {
+        let subscriber = self.subscriber;
+        repeat_all_miniquad_input(self, subscriber);
+        // macroquad queues the same characters until someone reads them
+        while get_char_pressed().is_some() {}
+        std::mem::take(&mut self.typed)
+    }
+}
+
+impl EventHandler for TextInputs {
+    fn update(&mut self, _ctx: &mut miniquad::Context) {}
+
+    fn draw(&mut self, _ctx: &mut miniquad::Context) {}
+
+    fn char_event(&mut self, _ctx: &mut miniquad::Context, character: char, modifiers: KeyMods, _repeat: bool) {
+        // Ctrl shortcuts arrive as characters too
+        if !character.is_control() && !modifiers.ctrl && !modifiers.logo {
+            self.typed.push(TextInput::Char(character));
+        }
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut miniquad::Context, key: KeyCode, modifiers: KeyMods, _repeat: bool) {
+        let mac = cfg!(target_os = "macos");
+        self.typed.push(TextInput::Key {
+            key,
+            shift: modifiers.shift,
+            command: if mac { modifiers.logo } else { modifiers.ctrl },
+            word: if mac { modifiers.alt } else { modifiers.ctrl },
+        });
+    }
+}
+
+/// The text on the system clipboard.
+pub fn clipboard_get() -> Option<String> {
+    // SAFETY: only the clipboard is touched, not the GL state macroquad batches draws into
+    unsafe { get_internal_gl() }.quad_context.clipboard_get()
+}
+
+pub fn clipboard_set(text: &str) {
+    // SAFETY: as in `clipboard_get`
+    unsafe { get_internal_gl() }.quad_context.clipboard_set(text);
+}
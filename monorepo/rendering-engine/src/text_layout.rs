@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use macroquad::math::{vec2, vec4, Mat4, Vec2, Vec4};
+use neo_scene::{
+    text::{self, Line},
+    Shape, Transform,
+};
+
+use crate::{
+    camera::Camera,
+    fonts::{FontChain, FontLibrary},
+    scene_renderer::{to_point, to_vec2},
+};
+
+/// Gap between a shape's edge and its text, in world units.
+const TEXT_PADDING: f32 = 10.0;
+const LINE_SPACING: f32 = 1.25;
+/// Part of the font size above the baseline, for carets and selection highlights.
+const ASCENT: f32 = 0.8;
+
+/// Shape text broken into lines and placed on screen, measured with the fonts it is drawn with.
+/// Positions are laid out in screen pixels as if the shape were upright; [`TextLayout::to_screen`]
+/// turns them with the shape to where they are drawn.
+pub struct TextLayout<'a> {
+    pub text: &'a str,
+    pub fonts: FontChain<'a>,
+    pub lines: Vec<Line>,
+    /// Position of the first line's baseline, at its left end.
+    pub origin: Vec2,
+    pub font_size: f32,
+    pub line_height: f32,
+    /// The shape's own transform as seen through the camera.
+    pub transform: Transform,
+}
+
+impl<'a> TextLayout<'a> {
+    /// Lays the text of `shape` out inside its bounds as seen through `camera`.
+    pub fn for_shape(shape: &'a Shape, camera: &Camera, fonts: &'a FontLibrary) -> Self {
+        let fonts = fonts.chain(&shape.text_style);
+        let font_size = shape.text_style.size as f32 * camera.zoom;
+        let padding = TEXT_PADDING * camera.zoom;
+        let max_width = shape.bounds.width as f32 * camera.zoom - 2.0 * padding;
+        let top_left = camera.world_to_screen(to_vec2(shape.bounds.min()));
+        let to_screen = camera.transform();
+        let transform = to_screen
+            .inverse()
+            .map_or(Transform::IDENTITY, |from_screen| from_screen.then(shape.world_transform()).then(to_screen));
+        TextLayout {
+            text: &shape.text,
+            lines: text::wrap(&shape.text, max_width, |s| fonts.measure(s, font_size)),
+            fonts,
+            origin: top_left + vec2(padding, padding + font_size),
+            font_size,
+            line_height: font_size * LINE_SPACING,
+            transform,
+        }
+    }
+
+    /// Where a position in the layout is drawn on screen.
+    pub fn to_screen(&self, point: Vec2) -> Vec2 {
+        to_vec2(self.transform.apply(to_point(point)))
+    }
+
+    /// [`TextLayout::to_screen`] as a model matrix, so glyphs drawn at their upright positions
+    /// come out turned, skewed or flipped with the shape.
+    pub fn model_matrix(&self) -> Mat4 {
+        let [a, b, c, d, e, f] = {
+            let Transform { a, b, c, d, e, f } = self.transform;
+            [a, b, c, d, e, f].map(|value| value as f32)
+        };
+        Mat4::from_cols(vec4(a, b, 0.0, 0.0), vec4(c, d, 0.0, 0.0), Vec4::Z, vec4(e, f, 0.0, 1.0))
+    }
+
+    pub fn line_text(&self, row: usize) -> &'a str {
+        &self.text[self.lines[row].range.clone()]
+    }
+
+    fn width(&self, range: Range<usize>) -> f32 {
+        self.fonts.measure(&self.text[range], self.font_size)
+    }
+
+    /// Position of the baseline at a caret offset.
+    pub fn caret(&self, offset: usize) -> Vec2 {
+        let row = text::line_at(&self.lines, offset);
+        let start = self.lines[row].range.start;
+        self.origin + vec2(self.width(start..offset), row as f32 * self.line_height)
+    }
+
+    /// Top of the caret or of a highlight, above a baseline.
+    pub fn ascent(&self) -> f32 {
+        self.font_size * ASCENT
+    }
+
+    /// The caret offset closest to a screen position.
+    pub fn offset_at(&self, point: Vec2) -> usize {
+        let point = self.transform.inverse().map_or(point, |from_screen| to_vec2(from_screen.apply(to_point(point))));
+        let top = self.origin.y - self.ascent();
+        let row = ((point.y - top) / self.line_height).floor().clamp(0.0, (self.lines.len() - 1) as f32) as usize;
+        self.offset_in_row(row, point.x)
+    }
+
+    fn offset_in_row(&self, row: usize, x: f32) -> usize {
+        text::offset_at_x(self.text, &self.lines, row, x - self.origin.x, |s| self.fonts.measure(s, self.font_size))
+    }
+
+    /// The offset `rows` lines above or below `offset`, as close to layout x `goal_x` as the
+    /// line allows. Past the first or last line it goes to the start or end of the text.
+    pub fn vertical(&self, offset: usize, rows: isize, goal_x: f32) -> usize {
+        let row = text::line_at(&self.lines, offset) as isize + rows;
+        if row < 0 {
+            0
+        } else if row as usize >= self.lines.len() {
+            self.text.len()
+        } else {
+            self.offset_in_row(row as usize, goal_x)
+        }
+    }
+
+    /// Where the line holding `offset` starts and where its caret can go last, for Home and End.
+    pub fn line_bounds(&self, offset: usize) -> (usize, usize) {
+        let row = text::line_at(&self.lines, offset);
+        (self.lines[row].range.start, text::line_end(self.text, &self.lines, row))
+    }
+
+    /// Rectangles in the layout, as top left corner and size, covering `range` line by line.
+    pub fn highlight(&self, range: Range<usize>) -> Vec<(Vec2, Vec2)> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.range.start <= range.end && range.start <= line.range.end)
+            .filter_map(|(row, line)| {
+                let start = range.start.max(line.range.start);
+                let end = range.end.min(line.range.end);
+                let left = self.width(line.range.start..start);
+                // a selected newline shows as a sliver, so selected blank lines are visible
+                let mut width = self.width(start..end);
+                if range.end > line.range.end && self.lines.get(row + 1).is_some_and(|next| next.range.start > line.range.end) {
+                    width += self.font_size * 0.25;
+                }
+                let top = self.origin + vec2(left, row as f32 * self.line_height - self.ascent());
+                (width > 0.0).then(|| (top, vec2(width, self.line_height)))
+            })
+            .collect()
+    }
+}
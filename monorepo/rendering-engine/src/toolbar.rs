@@ -0,0 +1,314 @@
+use egui_macroquad::egui;
+use macroquad::input::{is_key_down, is_key_pressed, KeyCode};
+use neo_scene::{
+    arrange::{self, Alignment, Axis},
+    font::{TextStyle, BOLD, REGULAR},
+    group,
+    order::{self, Reorder},
+    plugin::PluginHost,
+    Color, Object, Routing,
+};
+
+use crate::{
+    fonts::FontLibrary,
+    tools::{ToolContext, Toolbox},
+    user_action_mode::UserActionMode,
+};
+
+/// Space left between objects tidied into a grid, in world units.
+const TIDY_GAP: f64 = 20.0;
+
+/// The tool picker, with the tools plugins added after the built-in ones, plus a panel for
+/// editing the brush or the selected object.
+pub fn show_toolbar(egui_ctx: &egui::Context, toolbox: &mut Toolbox, plugins: &mut PluginHost, ctx: &mut ToolContext) {
+    egui::Window::new("Tools").show(egui_ctx, |ui| {
+        let plugin_tool_active = plugins.active_tool().is_some();
+        ui.horizontal(|ui| {
+            for mode in UserActionMode::ALL {
+                let button = ui
+                    .selectable_label(!plugin_tool_active && toolbox.mode == mode, mode.label())
+                    .on_hover_text(format!("{:?}", mode.shortcut()));
+                if button.clicked() {
+                    plugins.set_active_tool(None);
+                    toolbox.switch(mode, ctx);
+                }
+            }
+        });
+        let plugin_tools = plugins.tools();
+        if !plugin_tools.is_empty() {
+            ui.horizontal(|ui| {
+                for (plugin, tool) in plugin_tools {
+                    let active = plugins.active_tool() == Some((&plugin, &tool));
+                    let button = ui.selectable_label(active, &tool).on_hover_text(format!("from {plugin}"));
+                    if button.clicked() {
+                        // the built-in tool wraps up as if another one had been picked
+                        toolbox.reset(ctx);
+                        plugins.set_active_tool(Some((plugin, tool)));
+                    }
+                }
+            });
+        }
+
+        if !plugin_tool_active && toolbox.mode == UserActionMode::Draw {
+            let brush = toolbox.brush_mut();
+            ui.separator();
+            ui.label("Brush");
+            edit_color(ui, &mut brush.color);
+            ui.add(egui::Slider::new(&mut brush.width, 0.5..=50.0).text("Width"));
+            ui.add(egui::Slider::new(&mut brush.stabilizer, 0.0..=0.9).text("Stabilizer"));
+            ui.checkbox(&mut brush.smooth, "Smooth");
+        }
+        if !plugin_tool_active && toolbox.mode == UserActionMode::Connector {
+            let connector = toolbox.connector_mut();
+            ui.separator();
+            ui.label("Connector");
+            edit_routing(ui, &mut connector.routing);
+            edit_arrows(ui, &mut connector.start_arrow, &mut connector.end_arrow);
+        }
+        if !plugin_tool_active && toolbox.mode == UserActionMode::Select {
+            ui.separator();
+            ui.label("Select");
+            // what the modifiers do mid-drag, since nothing on the canvas says so
+            ui.weak("Shift: add to the selection, keep proportions, turn in steps");
+            ui.weak("Alt: draw a lasso, resize from the centre");
+            ui.weak("Ctrl: move or resize without snapping");
+        }
+        if !plugin_tool_active && toolbox.mode == UserActionMode::Text {
+            ui.separator();
+            ui.label("Text");
+            edit_text_style(ui, &mut toolbox.text_mut().style, ctx.fonts);
+            // the window gives no composition events, see `text_input`
+            ui.weak("Input methods and dead keys only work in the web version for now");
+        }
+
+        arrange_selection(ui, ctx);
+
+        // panel edits join the open undo step, so dragging a slider is a single step
+        let Some(id) = ctx.selection.single() else {
+            return;
+        };
+        ctx.history.track(ctx.document, id);
+        let Some(mut object) = ctx.document.get_mut(id) else {
+            return;
+        };
+        ui.separator();
+        let mut embed = None;
+        match &mut *object {
+            Object::Shape(shape) => {
+                ui.label("Edit Shape");
+                edit_color(ui, &mut shape.style.fill);
+                edit_text_style(ui, &mut shape.text_style, ctx.fonts);
+                let button = ui.button("Embed fonts").on_hover_text("Save the font files with the document");
+                if button.clicked() {
+                    embed = Some(ctx.fonts.embeddable(&shape.text_style));
+                }
+            }
+            Object::Stroke(stroke) => {
+                ui.label("Edit Stroke");
+                edit_color(ui, &mut stroke.style.stroke);
+                ui.add(egui::Slider::new(&mut stroke.style.stroke_width, 0.5..=50.0).text("Width"));
+            }
+            Object::Connector(connector) => {
+                ui.label("Edit Connector");
+                edit_routing(ui, &mut connector.routing);
+                edit_arrows(ui, &mut connector.start_arrow, &mut connector.end_arrow);
+                edit_color(ui, &mut connector.style.stroke);
+                ui.add(egui::Slider::new(&mut connector.style.stroke_width, 0.5..=20.0).text("Width"));
+            }
+            Object::Group(_) => {
+                ui.label("Group");
+                ui.label("Double-click to edit what is inside");
+            }
+        }
+        drop(object);
+        for font in embed.into_iter().flatten() {
+            ctx.document.embed_font(font);
+        }
+    });
+}
+
+/// A command on the selected objects.
+pub enum Arrange {
+    Align(Alignment),
+    Distribute(Axis),
+    Tidy,
+    Group,
+    Ungroup,
+    Reorder(Reorder),
+}
+
+/// Align, distribute, tidy, grouping and stacking buttons for the selected objects.
+fn arrange_selection(ui: &mut egui::Ui, ctx: &mut ToolContext) {
+    let count = ctx.selection.len();
+    let has_group = ctx.selection.ids().iter().any(|id| ctx.document.group(*id).is_some());
+    ui.separator();
+    ui.label("Arrange");
+    let mut command = None;
+    ui.add_enabled_ui(count >= 2, |ui| {
+        ui.horizontal(|ui| {
+            for (alignment, label) in [
+                (Alignment::Left, "Left"),
+                (Alignment::Center, "Centre"),
+                (Alignment::Right, "Right"),
+                (Alignment::Top, "Top"),
+                (Alignment::Middle, "Middle"),
+                (Alignment::Bottom, "Bottom"),
+            ] {
+                if ui.button(label).clicked() {
+                    command = Some(Arrange::Align(alignment));
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            let distribute = count >= 3;
+            for (axis, label) in [(Axis::Horizontal, "Distribute horizontally"), (Axis::Vertical, "Distribute vertically")] {
+                if ui.add_enabled(distribute, egui::Button::new(label)).clicked() {
+                    command = Some(Arrange::Distribute(axis));
+                }
+            }
+            if ui.button("Tidy").on_hover_text("Arrange in a grid").clicked() {
+                command = Some(Arrange::Tidy);
+            }
+        });
+    });
+    ui.horizontal(|ui| {
+        if ui.add_enabled(count >= 2, egui::Button::new("Group")).on_hover_text("Ctrl+G").clicked() {
+            command = Some(Arrange::Group);
+        }
+        if ui.add_enabled(has_group, egui::Button::new("Ungroup")).on_hover_text("Ctrl+Shift+G").clicked() {
+            command = Some(Arrange::Ungroup);
+        }
+    });
+    ui.add_enabled_ui(count >= 1, |ui| {
+        ui.horizontal(|ui| {
+            for (reorder, label, shortcut) in [
+                (Reorder::ToFront, "To front", "Ctrl+Shift+]"),
+                (Reorder::Forward, "Forward", "Ctrl+]"),
+                (Reorder::Backward, "Backward", "Ctrl+["),
+                (Reorder::ToBack, "To back", "Ctrl+Shift+["),
+            ] {
+                if ui.button(label).on_hover_text(shortcut).clicked() {
+                    command = Some(Arrange::Reorder(reorder));
+                }
+            }
+        });
+    });
+    if let Some(command) = command {
+        arrange_command(command, ctx);
+    }
+}
+
+/// Ctrl+G groups the selection and Ctrl+Shift+G ungroups it; Ctrl+] brings it forward and
+/// Ctrl+[ sends it backward, all the way with Shift.
+pub fn arrange_shortcut() -> Option<Arrange> {
+    if !(is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)) {
+        return None;
+    }
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    if is_key_pressed(KeyCode::G) {
+        Some(if shift { Arrange::Ungroup } else { Arrange::Group })
+    } else if is_key_pressed(KeyCode::RightBracket) {
+        Some(Arrange::Reorder(if shift { Reorder::ToFront } else { Reorder::Forward }))
+    } else if is_key_pressed(KeyCode::LeftBracket) {
+        Some(Arrange::Reorder(if shift { Reorder::ToBack } else { Reorder::Backward }))
+    } else {
+        None
+    }
+}
+
+/// Runs `command` on the selection as an undo step of its own, selecting a new group or what
+/// an ungrouped one held.
+pub fn arrange_command(command: Arrange, ctx: &mut ToolContext) {
+    let ids = ctx.selection.ids().to_vec();
+    // closes whatever was open first, so the command is a step of its own
+    ctx.history.commit(ctx.document);
+    let changed = match command {
+        Arrange::Align(alignment) => arrange::align(ctx.document, ctx.history, &ids, alignment),
+        Arrange::Distribute(axis) => arrange::distribute(ctx.document, ctx.history, &ids, axis),
+        Arrange::Tidy => arrange::tidy(ctx.document, ctx.history, &ids, TIDY_GAP),
+        Arrange::Group => group::group(ctx.document, ctx.history, &ids).map(|group| ctx.selection.set(group)).is_some(),
+        Arrange::Ungroup => {
+            let mut ungrouped = false;
+            ctx.selection.clear();
+            for id in ids {
+                let children = group::ungroup(ctx.document, ctx.history, id);
+                ungrouped |= !children.is_empty();
+                if children.is_empty() {
+                    ctx.selection.add(id);
+                }
+                ctx.selection.extend(children);
+            }
+            ungrouped
+        }
+        Arrange::Reorder(reorder) => order::reorder(ctx.document, ctx.history, &ids, reorder),
+    };
+    if changed {
+        ctx.history.commit(ctx.document);
+        ctx.selection.retain_existing(ctx.document);
+    }
+}
+
+/// The font families in fallback order, with the size, weight and slant.
+fn edit_text_style(ui: &mut egui::Ui, style: &mut TextStyle, fonts: &FontLibrary) {
+    if style.families.is_empty() {
+        ui.label("Default font");
+    }
+    let mut removed = None;
+    for (i, family) in style.families.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(if i == 0 { family.clone() } else { format!("then {family}") });
+            if ui.small_button("x").on_hover_text("Stop using this font").clicked() {
+                removed = Some(i);
+            }
+        });
+    }
+    if let Some(i) = removed {
+        style.families.remove(i);
+    }
+    let unused: Vec<&str> = fonts.families().into_iter().filter(|family| !style.families.iter().any(|used| used == family)).collect();
+    if !unused.is_empty() {
+        let label = if style.families.is_empty() { "Use font" } else { "Add fallback" };
+        egui::ComboBox::from_id_source("add font").selected_text(label).show_ui(ui, |ui| {
+            for family in unused {
+                if ui.selectable_label(false, family).clicked() {
+                    style.families.push(family.to_owned());
+                }
+            }
+        });
+    }
+    ui.add(egui::Slider::new(&mut style.size, 4.0..=200.0).logarithmic(true).text("Size"));
+    ui.add(egui::Slider::new(&mut style.weight, 100..=900).step_by(100.0).text("Weight"));
+    ui.horizontal(|ui| {
+        let mut bold = style.weight >= BOLD;
+        if ui.checkbox(&mut bold, "Bold").changed() {
+            style.weight = if bold { BOLD } else { REGULAR };
+        }
+        ui.checkbox(&mut style.italic, "Italic");
+    });
+}
+
+fn edit_routing(ui: &mut egui::Ui, routing: &mut Routing) {
+    ui.horizontal(|ui| {
+        ui.selectable_value(routing, Routing::Straight, "Straight");
+        ui.selectable_value(routing, Routing::Elbow, "Elbow");
+        ui.selectable_value(routing, Routing::Curved, "Curved");
+    });
+}
+
+fn edit_arrows(ui: &mut egui::Ui, start: &mut bool, end: &mut bool) {
+    ui.horizontal(|ui| {
+        ui.checkbox(start, "Start arrow");
+        ui.checkbox(end, "End arrow");
+    });
+}
+
+/// Color picker with a separate opacity slider.
+fn edit_color(ui: &mut egui::Ui, color: &mut Color) {
+    let mut rgb = [color.r, color.g, color.b];
+    if ui.color_edit_button_rgb(&mut rgb).changed() {
+        color.r = rgb[0];
+        color.g = rgb[1];
+        color.b = rgb[2];
+    }
+    ui.add(egui::Slider::new(&mut color.a, 0.0..=1.0).text("Opacity"));
+}
@@ -0,0 +1,109 @@
+use macroquad::{color::RED, shapes::draw_circle};
+use neo_scene::{layer, Anchor, Binding, Color, Connector, Endpoint, ObjectId, Routing, Style};
+
+use crate::{
+    cursor::CursorIcon,
+    scene_renderer::{draw_connector, to_vec2},
+};
+
+use super::{shape::CLICK_DISTANCE, Tool, ToolContext};
+
+const CONNECTOR_WIDTH: f64 = 2.0;
+/// Radius of the anchor dots shown on the shape under the mouse, in screen pixels.
+const ANCHOR_DOT_RADIUS: f32 = 4.0;
+/// How far outside a shape the mouse may be and still attach to it, in screen pixels.
+const ATTACH_DISTANCE: f32 = 8.0;
+
+/// Drags connectors between shapes. An end dropped on a shape attaches to the middle of its
+/// nearest side and follows the shape from then on; one dropped on empty canvas stays put.
+pub struct ConnectorTool {
+    /// Routing and arrowheads given to new connectors.
+    pub routing: Routing,
+    pub start_arrow: bool,
+    pub end_arrow: bool,
+    /// Where the drag started.
+    start: Option<Endpoint>,
+}
+
+impl ConnectorTool {
+    pub fn new() -> Self {
+        ConnectorTool { routing: Routing::Straight, start_arrow: false, end_arrow: true, start: None }
+    }
+
+    fn connector(&self, start: Endpoint, end: Endpoint) -> Connector {
+        let mut connector = Connector::new(start, end, Style::stroked(Color::BLACK, CONNECTOR_WIDTH));
+        connector.routing = self.routing;
+        connector.start_arrow = self.start_arrow;
+        connector.end_arrow = self.end_arrow;
+        connector
+    }
+}
+
+/// The topmost shape under the mouse, skipping connectors and strokes drawn over it and
+/// anything hidden.
+fn shape_under_mouse(ctx: &ToolContext, except: Option<ObjectId>) -> Option<ObjectId> {
+    let tolerance = ctx.screen_to_world_distance(ATTACH_DISTANCE);
+    ctx.document
+        .hits_at(ctx.mouse_world(), tolerance)
+        .into_iter()
+        .find(|id| Some(*id) != except && ctx.document.shape(*id).is_some() && !layer::is_hidden(ctx.document, *id))
+}
+
+/// The end a drop at the mouse would make: attached to a shape under it, or free.
+pub(super) fn endpoint_under_mouse(ctx: &ToolContext, except: Option<ObjectId>) -> Endpoint {
+    let point = ctx.mouse_world();
+    match shape_under_mouse(ctx, except).and_then(|id| Some((id, ctx.document.shape(id)?))) {
+        Some((id, shape)) => {
+            let anchor = shape.nearest_anchor(point);
+            Endpoint { position: shape.anchor(anchor), binding: Some(Binding { shape: id, anchor }) }
+        }
+        None => Endpoint::free(point),
+    }
+}
+
+/// The shape an end is attached to, which the other end shouldn't loop back onto.
+pub(super) fn bound_shape(end: Option<Endpoint>) -> Option<ObjectId> {
+    end.and_then(|end| end.binding).map(|binding| binding.shape)
+}
+
+impl Tool for ConnectorTool {
+    fn update(&mut self, ctx: &mut ToolContext) {
+        if ctx.pressed() {
+            self.start = Some(endpoint_under_mouse(ctx, None));
+        }
+        let Some(start) = self.start else {
+            return;
+        };
+        if ctx.released() {
+            let end = endpoint_under_mouse(ctx, bound_shape(self.start));
+            // a click without a drag doesn't leave a stub behind
+            if start.position.distance(end.position) >= ctx.screen_to_world_distance(CLICK_DISTANCE) {
+                let id = ctx.document.push(self.connector(start, end));
+                ctx.history.track_created(id);
+                ctx.selection.set(id);
+            }
+            self.start = None;
+        }
+    }
+
+    fn draw_preview(&self, ctx: &ToolContext) {
+        if let Some(shape) = shape_under_mouse(ctx, bound_shape(self.start)).and_then(|id| ctx.document.shape(id)) {
+            for anchor in Anchor::SIDES {
+                let dot = ctx.camera.world_to_screen(to_vec2(shape.anchor(anchor)));
+                draw_circle(dot.x, dot.y, ANCHOR_DOT_RADIUS, RED);
+            }
+        }
+        if let Some(start) = self.start {
+            let end = endpoint_under_mouse(ctx, bound_shape(self.start));
+            draw_connector(&self.connector(start, end), ctx.camera);
+        }
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::Crosshair
+    }
+
+    fn reset(&mut self, _ctx: &mut ToolContext) {
+        self.start = None;
+    }
+}
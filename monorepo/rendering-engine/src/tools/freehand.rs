@@ -0,0 +1,54 @@
+use neo_scene::{Brush, Stroke};
+
+use crate::{cursor::CursorIcon, scene_renderer::draw_stroke};
+
+use super::{Tool, ToolContext};
+
+/// Freehand drawing with a [`Brush`]. The stroke stays out of the document until the button
+/// is released, then goes in as one undo step.
+pub struct FreehandTool {
+    pub brush: Brush,
+    current: Option<Stroke>,
+}
+
+impl FreehandTool {
+    pub fn new() -> Self {
+        FreehandTool {
+            brush: Brush::default(),
+            current: None,
+        }
+    }
+}
+
+impl Tool for FreehandTool {
+    fn update(&mut self, ctx: &mut ToolContext) {
+        if ctx.pressed() {
+            self.current = Some(self.brush.begin());
+        }
+        let Some(stroke) = self.current.as_mut() else {
+            return;
+        };
+        // the mouse has no pressure or tilt
+        self.brush.extend(stroke, ctx.mouse_world(), 1.0, 0.0);
+        if ctx.released() {
+            let mut stroke = self.current.take().unwrap();
+            self.brush.finish(&mut stroke, ctx.camera.zoom as f64);
+            let id = ctx.document.push(stroke);
+            ctx.history.track_created(id);
+        }
+    }
+
+    fn draw_preview(&self, ctx: &ToolContext) {
+        if let Some(stroke) = &self.current {
+            draw_stroke(stroke, ctx.camera);
+        }
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::Crosshair
+    }
+
+    fn reset(&mut self, _ctx: &mut ToolContext) {
+        self.current = None;
+    }
+}
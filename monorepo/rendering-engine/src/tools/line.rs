@@ -0,0 +1,60 @@
+use neo_scene::{Color, Point, Stroke, Style};
+
+use crate::{cursor::CursorIcon, scene_renderer::draw_stroke};
+
+use super::{shape::CLICK_DISTANCE, Tool, ToolContext};
+
+const LINE_WIDTH: f64 = 2.0;
+
+/// Drags out straight lines, stored as two-point strokes.
+pub struct LineTool {
+    /// World position where the drag started.
+    start: Option<Point>,
+}
+
+impl LineTool {
+    pub fn new() -> Self {
+        LineTool { start: None }
+    }
+
+    fn line(start: Point, end: Point) -> Stroke {
+        let mut stroke = Stroke::new(Style::stroked(Color::BLACK, LINE_WIDTH));
+        stroke.points = vec![start, end];
+        stroke
+    }
+}
+
+impl Tool for LineTool {
+    fn update(&mut self, ctx: &mut ToolContext) {
+        if ctx.pressed() {
+            self.start = Some(ctx.mouse_world());
+        }
+        let Some(start) = self.start else {
+            return;
+        };
+        if ctx.released() {
+            let end = ctx.mouse_world();
+            // a click without a drag doesn't leave a dot behind
+            if start.distance(end) >= ctx.screen_to_world_distance(CLICK_DISTANCE) {
+                let id = ctx.document.push(LineTool::line(start, end));
+                ctx.history.track_created(id);
+                ctx.selection.set(id);
+            }
+            self.start = None;
+        }
+    }
+
+    fn draw_preview(&self, ctx: &ToolContext) {
+        if let Some(start) = self.start {
+            draw_stroke(&LineTool::line(start, ctx.mouse_world()), ctx.camera);
+        }
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::Crosshair
+    }
+
+    fn reset(&mut self, _ctx: &mut ToolContext) {
+        self.start = None;
+    }
+}
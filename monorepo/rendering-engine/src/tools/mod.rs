@@ -0,0 +1,227 @@
+//! Canvas tools. Each tool owns its input handling, the preview it draws over the document and
+//! the cursor shown while it is active; [`Toolbox`] holds one of each and routes input to the
+//! active one.
+
+use macroquad::{
+    input::{
+        is_key_down, is_key_pressed, is_mouse_button_down, is_mouse_button_pressed,
+        is_mouse_button_released, mouse_position, KeyCode, MouseButton,
+    },
+    math::Vec2,
+};
+use neo_scene::{history::History, selection::Selection, Brush, Document, Point};
+
+use crate::{
+    camera::Camera, cursor::CursorIcon, fonts::FontLibrary, scene_renderer::to_point, text_input::TextInput,
+    user_action_mode::UserActionMode,
+};
+
+mod connector;
+mod freehand;
+mod line;
+mod pan;
+mod select;
+mod shape;
+mod text;
+
+use connector::ConnectorTool;
+use freehand::FreehandTool;
+use line::LineTool;
+use pan::PanTool;
+use select::SelectTool;
+use shape::ShapeTool;
+use text::TextTool;
+
+/// Everything a tool may look at or change.
+pub struct ToolContext<'a> {
+    pub document: &'a mut Document,
+    pub history: &'a mut History,
+    pub camera: &'a mut Camera,
+    pub selection: &'a mut Selection,
+    pub fonts: &'a FontLibrary,
+    /// The pointer is over a UI panel, so clicks aren't meant for the canvas.
+    pub ui_has_pointer: bool,
+    /// A UI text field has keyboard focus.
+    pub ui_has_keyboard: bool,
+    /// Characters and keys typed this frame, for editing text.
+    pub text_input: Vec<TextInput>,
+}
+
+impl<'a> ToolContext<'a> {
+    pub fn new(
+        document: &'a mut Document,
+        history: &'a mut History,
+        camera: &'a mut Camera,
+        selection: &'a mut Selection,
+        fonts: &'a FontLibrary,
+    ) -> Self {
+        ToolContext {
+            document,
+            history,
+            camera,
+            selection,
+            fonts,
+            ui_has_pointer: false,
+            ui_has_keyboard: false,
+            text_input: Vec::new(),
+        }
+    }
+
+    pub fn mouse_screen(&self) -> Vec2 {
+        mouse_position().into()
+    }
+
+    pub fn mouse_world(&self) -> Point {
+        to_point(self.camera.screen_to_world(self.mouse_screen()))
+    }
+
+    /// Left button went down on the canvas this frame.
+    pub fn pressed(&self) -> bool {
+        !self.ui_has_pointer && is_mouse_button_pressed(MouseButton::Left)
+    }
+
+    pub fn down(&self) -> bool {
+        is_mouse_button_down(MouseButton::Left)
+    }
+
+    /// Left button came up this frame, wherever the pointer is, so drags always end.
+    pub fn released(&self) -> bool {
+        is_mouse_button_released(MouseButton::Left)
+    }
+
+    /// Whether the keyboard is free for tool shortcuts: no UI field has focus and no Ctrl
+    /// shortcut is being typed.
+    pub fn keys_free(&self) -> bool {
+        !(self.ui_has_keyboard || is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl))
+    }
+
+    /// World distance covering `pixels` on screen at the current zoom.
+    pub fn screen_to_world_distance(&self, pixels: f32) -> f64 {
+        (pixels / self.camera.zoom) as f64
+    }
+}
+
+pub trait Tool {
+    /// Reacts to this frame's input.
+    fn update(&mut self, ctx: &mut ToolContext);
+
+    /// Draws whatever the tool shows on top of the document, such as the shape being dragged
+    /// out or the selection outline.
+    fn draw_preview(&self, _ctx: &ToolContext) {}
+
+    fn cursor(&self) -> CursorIcon;
+
+    /// Whether the tool is in the middle of something that must keep the undo step open even
+    /// with the mouse released, like typing.
+    fn is_busy(&self) -> bool {
+        false
+    }
+
+    /// Finishes or drops whatever is in progress, when switching away from the tool or after
+    /// the document was replaced underneath it.
+    fn reset(&mut self, _ctx: &mut ToolContext) {}
+}
+
+/// One of every tool, and which one is active.
+pub struct Toolbox {
+    pub mode: UserActionMode,
+    select: SelectTool,
+    rectangle: ShapeTool,
+    ellipse: ShapeTool,
+    line: LineTool,
+    connector: ConnectorTool,
+    freehand: FreehandTool,
+    text: TextTool,
+    pan: PanTool,
+}
+
+impl Toolbox {
+    pub fn new(mode: UserActionMode) -> Self {
+        Toolbox {
+            mode,
+            select: SelectTool::new(),
+            rectangle: ShapeTool::new(neo_scene::ShapeKind::Rectangle),
+            ellipse: ShapeTool::new(neo_scene::ShapeKind::Ellipse),
+            line: LineTool::new(),
+            connector: ConnectorTool::new(),
+            freehand: FreehandTool::new(),
+            text: TextTool::new(),
+            pan: PanTool::new(),
+        }
+    }
+
+    pub fn active(&self) -> &dyn Tool {
+        match self.mode {
+            UserActionMode::Select => &self.select,
+            UserActionMode::Rectangle => &self.rectangle,
+            UserActionMode::Ellipse => &self.ellipse,
+            UserActionMode::Line => &self.line,
+            UserActionMode::Connector => &self.connector,
+            UserActionMode::Draw => &self.freehand,
+            UserActionMode::Text => &self.text,
+            UserActionMode::Pan => &self.pan,
+        }
+    }
+
+    pub fn active_mut(&mut self) -> &mut dyn Tool {
+        match self.mode {
+            UserActionMode::Select => &mut self.select,
+            UserActionMode::Rectangle => &mut self.rectangle,
+            UserActionMode::Ellipse => &mut self.ellipse,
+            UserActionMode::Line => &mut self.line,
+            UserActionMode::Connector => &mut self.connector,
+            UserActionMode::Draw => &mut self.freehand,
+            UserActionMode::Text => &mut self.text,
+            UserActionMode::Pan => &mut self.pan,
+        }
+    }
+
+    /// Settings of the freehand tool.
+    pub fn brush_mut(&mut self) -> &mut Brush {
+        &mut self.freehand.brush
+    }
+
+    /// The connector tool, whose routing and arrowheads apply to new connectors.
+    pub fn connector_mut(&mut self) -> &mut ConnectorTool {
+        &mut self.connector
+    }
+
+    pub fn text_mut(&mut self) -> &mut TextTool {
+        &mut self.text
+    }
+
+    /// Makes `mode` the active tool, letting the previous one wrap up first.
+    pub fn switch(&mut self, mode: UserActionMode, ctx: &mut ToolContext) {
+        if mode != self.mode {
+            self.active_mut().reset(ctx);
+            self.mode = mode;
+        }
+    }
+
+    /// Handles tool shortcuts, then passes the frame's input to the active tool.
+    pub fn update(&mut self, ctx: &mut ToolContext) {
+        if let Some(mode) = self.pressed_shortcut(ctx) {
+            self.switch(mode, ctx);
+        }
+        self.active_mut().update(ctx);
+    }
+
+    /// The tool whose shortcut was pressed this frame, unless the keys are meant for something
+    /// else.
+    pub fn pressed_shortcut(&self, ctx: &ToolContext) -> Option<UserActionMode> {
+        if !ctx.keys_free() || self.active().is_busy() {
+            return None;
+        }
+        UserActionMode::ALL.into_iter().find(|mode| is_key_pressed(mode.shortcut()))
+    }
+
+    /// Whether the open undo step has to stay open this frame.
+    pub fn is_busy(&self) -> bool {
+        self.active().is_busy()
+    }
+
+    /// Drops in-progress work, e.g. after an undo or a file was opened.
+    pub fn reset(&mut self, ctx: &mut ToolContext) {
+        self.active_mut().reset(ctx);
+    }
+}
@@ -0,0 +1,50 @@
+use macroquad::math::Vec2;
+
+use crate::cursor::CursorIcon;
+
+use super::{Tool, ToolContext};
+
+/// Drags the canvas around.
+pub struct PanTool {
+    is_dragging: bool,
+    last_mouse_position: Vec2,
+}
+
+impl PanTool {
+    pub fn new() -> Self {
+        PanTool {
+            is_dragging: false,
+            last_mouse_position: Vec2::ZERO,
+        }
+    }
+}
+
+impl Tool for PanTool {
+    fn update(&mut self, ctx: &mut ToolContext) {
+        if ctx.pressed() {
+            self.is_dragging = true;
+            self.last_mouse_position = ctx.mouse_screen();
+        }
+        if !ctx.down() {
+            self.is_dragging = false;
+        }
+        if self.is_dragging {
+            let current_mouse_position = ctx.mouse_screen();
+            let delta = (current_mouse_position - self.last_mouse_position) / ctx.camera.zoom;
+            ctx.camera.position -= delta;
+            self.last_mouse_position = current_mouse_position;
+        }
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        if self.is_dragging {
+            CursorIcon::Grab
+        } else {
+            CursorIcon::Hand
+        }
+    }
+
+    fn reset(&mut self, _ctx: &mut ToolContext) {
+        self.is_dragging = false;
+    }
+}
@@ -0,0 +1,446 @@
+use macroquad::{
+    color::{Color, RED, WHITE},
+    input::{is_key_down, is_key_pressed, KeyCode},
+    math::{vec2, Vec2},
+    shapes::{draw_circle, draw_circle_lines, draw_line, draw_rectangle, draw_rectangle_lines},
+    time::get_time,
+};
+use neo_scene::{
+    group, layer,
+    resize::{Handle, Resize},
+    selection::{self, Selection},
+    snap::{Guide, Snap, Snapper},
+    Object, ObjectId, Point, Rect, Transform,
+};
+
+use crate::{
+    camera::Camera,
+    cursor::CursorIcon,
+    grid::grid_step,
+    scene_renderer::{to_point, to_vec2},
+};
+
+use super::{
+    connector::{bound_shape, endpoint_under_mouse},
+    Tool, ToolContext,
+};
+
+/// Side of the resize handles in screen pixels; they stay the same size at any zoom.
+const RESIZE_HANDLE_SIZE: f32 = 8.0;
+/// How far around a handle a press still grabs it, in screen pixels.
+const HANDLE_REACH: f32 = 6.0;
+/// How far above the box the rotation handle sits, in screen pixels.
+const ROTATE_HANDLE_OFFSET: f32 = 20.0;
+/// What rotation snaps to with Shift held: 15°.
+const ROTATE_STEP: f64 = std::f64::consts::PI / 12.0;
+/// How far from a thin line a click still picks it, in screen pixels.
+const HIT_TOLERANCE: f32 = 4.0;
+const MIN_SHAPE_SIZE: f64 = 10.0;
+/// How far a drag may jump to snap, in screen pixels, so it feels the same at any zoom.
+const SNAP_DISTANCE: f32 = 6.0;
+const GUIDE_COLOR: Color = Color::new(0.9, 0.2, 0.6, 1.0);
+/// Half the length of the ticks at the ends of a spacing guide, in screen pixels.
+const GAP_TICK: f32 = 4.0;
+/// Longest gap between the clicks of a double-click, in seconds.
+const DOUBLE_CLICK_TIME: f64 = 0.4;
+/// How far apart the clicks of a double-click may be, in screen pixels.
+const DOUBLE_CLICK_DISTANCE: f32 = 4.0;
+const ENTERED_GROUP_COLOR: Color = Color::new(0.5, 0.5, 0.5, 1.0);
+const MARQUEE_COLOR: Color = Color::new(0.2, 0.45, 1.0, 1.0);
+const MARQUEE_FILL: Color = Color::new(0.2, 0.45, 1.0, 0.1);
+
+enum DragState {
+    None,
+    /// Moving the selection: where the drag started in world space, the selection's bounds
+    /// then, and how far it has been moved so far.
+    Moving { start: Point, origin: Rect, moved: Point },
+    /// Resizing the selection by a handle on its box: the box and the selected objects as they
+    /// were when the drag started, so every frame scales them afresh.
+    Resizing { handle: Handle, origin: Rect, objects: Vec<(ObjectId, Object)> },
+    /// Turning the selection about the centre of its box: the angle of the mouse from the centre
+    /// and the selected objects when the drag started, and how far the one selected shape was
+    /// already turned, so Shift snaps to whole steps of its own angle.
+    Rotating { center: Point, start_angle: f64, turned: f64, objects: Vec<(ObjectId, Object)> },
+    /// Dragging one end of a connector, `true` for its start, to attach it elsewhere.
+    MovingEnd(ObjectId, bool),
+    /// Dragging a rectangle over empty canvas from `start`; `before` is what stays selected
+    /// alongside whatever ends up inside.
+    Marquee { start: Point, before: Selection },
+    /// Drawing a free-form outline over empty canvas.
+    Lasso { points: Vec<Point>, before: Selection },
+}
+
+/// Picks objects and moves, resizes or deletes them together. A click picks the topmost object
+/// under the mouse and Shift-click adds or removes one; dragging over empty canvas picks
+/// everything inside a rectangle, or inside a free-form outline with Alt held, again adding to
+/// the selection with Shift. Clicking an object in a group picks the whole group; double-click
+/// goes inside it to pick what it holds, and Escape or clicking outside comes back out.
+///
+/// The selected objects share one box, moved by dragging any of them and resized by the
+/// handles on its corners and edges: Shift keeps the aspect ratio, Alt resizes from the centre
+/// and dragging past the opposite edge flips them. The handle above the box turns them about
+/// its centre, in 15° steps with Shift. A lone connector's ends are reattached by dragging
+/// them. Moving and resizing snap to other objects on screen and to the grid, unless Ctrl is
+/// held: Alt and Shift already mean something mid-drag, so Ctrl is the one left to turn
+/// snapping off. Delete or Backspace removes the selection.
+pub struct SelectTool {
+    drag_state: DragState,
+    /// What the current drag snapped to, drawn until it ends.
+    guides: Vec<Guide>,
+    /// When and where on screen the last click went down, to tell a double-click.
+    last_click: Option<(f64, Vec2)>,
+}
+
+impl SelectTool {
+    pub fn new() -> Self {
+        SelectTool { drag_state: DragState::None, guides: Vec::new(), last_click: None }
+    }
+
+    /// Whether the click that just went down is the second of a double-click.
+    fn double_clicked(&mut self, ctx: &ToolContext) -> bool {
+        let (now, mouse) = (get_time(), ctx.mouse_screen());
+        let double = self.last_click.is_some_and(|(time, at)| {
+            now - time <= DOUBLE_CLICK_TIME && at.distance(mouse) <= DOUBLE_CLICK_DISTANCE
+        });
+        // a third click starts over rather than making another double-click
+        self.last_click = if double { None } else { Some((now, mouse)) };
+        double
+    }
+}
+
+fn shift_down() -> bool {
+    is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
+}
+
+fn alt_down() -> bool {
+    is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt)
+}
+
+fn ctrl_down() -> bool {
+    is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)
+}
+
+/// The handle of the selection box `bounds` within reach of the mouse, the closest if several
+/// are, as on a small box.
+fn handle_under_mouse(ctx: &ToolContext, bounds: Rect) -> Option<Handle> {
+    let mouse = ctx.mouse_screen();
+    let reach = RESIZE_HANDLE_SIZE / 2.0 + HANDLE_REACH;
+    Handle::ALL
+        .into_iter()
+        .map(|handle| (handle, ctx.camera.world_to_screen(to_vec2(handle.position(bounds))) - mouse))
+        .filter(|(_, offset)| offset.abs().max_element() <= reach)
+        .min_by(|(_, a), (_, b)| a.length_squared().total_cmp(&b.length_squared()))
+        .map(|(handle, _)| handle)
+}
+
+/// Where the rotation handle of the selection box `bounds` is drawn, on screen.
+fn rotate_handle(camera: &Camera, bounds: Rect) -> Vec2 {
+    camera.world_to_screen(to_vec2(Handle::Top.position(bounds))) - vec2(0.0, ROTATE_HANDLE_OFFSET)
+}
+
+/// The angle of `point` seen from `center`.
+fn angle_from(center: Point, point: Point) -> f64 {
+    (point.y - center.y).atan2(point.x - center.x)
+}
+
+/// The box the selection is moved and resized by. A lone connector has none, as it is edited
+/// by its ends instead.
+fn selection_box(ctx: &ToolContext) -> Option<Rect> {
+    let lone_connector = ctx.selection.single().and_then(|id| ctx.document.connector(id)).is_some();
+    if lone_connector {
+        return None;
+    }
+    ctx.selection.bounds(ctx.document)
+}
+
+/// The selected objects as they are now, groups opened up, for a resize or rotation to start
+/// again from every frame.
+fn originals(ctx: &ToolContext) -> Vec<(ObjectId, Object)> {
+    ctx.selection.leaves(ctx.document).into_iter().filter_map(|id| Some((id, ctx.document.get(id)?.clone()))).collect()
+}
+
+/// The topmost object at `point` that a click can pick, passing over hidden and locked ones as
+/// if they weren't there.
+fn topmost_pickable(ctx: &ToolContext, point: Point, tolerance: f64) -> Option<ObjectId> {
+    ctx.document.hits_at(point, tolerance).into_iter().find(|id| layer::is_pickable(ctx.document, *id))
+}
+
+/// Snaps what `snap` is given against the visible objects other than the `dragged` ones, or
+/// returns no snap while Ctrl is held.
+fn snap(ctx: &ToolContext, dragged: &[ObjectId], snap: impl FnOnce(&Snapper) -> Snap) -> Snap {
+    if ctrl_down() {
+        return Snap::default();
+    }
+    let (top_left, bottom_right) = ctx.camera.visible_world_bounds();
+    let viewport = Rect::from_corners(to_point(top_left), to_point(bottom_right));
+    // connectors follow the shapes they join, so lining up with them means little
+    let targets: Vec<Rect> = ctx
+        .document
+        .iter_in(viewport)
+        .filter(|(id, object)| !dragged.contains(id) && !matches!(object, Object::Connector(_)))
+        .filter(|(id, _)| !layer::is_hidden(ctx.document, *id))
+        .filter_map(|(_, object)| object.bounds())
+        .collect();
+    snap(&Snapper {
+        targets: &targets,
+        grid: Some(grid_step(ctx.camera) as f64),
+        distance: ctx.screen_to_world_distance(SNAP_DISTANCE),
+    })
+}
+
+impl Tool for SelectTool {
+    fn update(&mut self, ctx: &mut ToolContext) {
+        let point = ctx.mouse_world();
+        if ctx.pressed() {
+            let on_handle = selection_box(ctx).and_then(|bounds| Some((handle_under_mouse(ctx, bounds)?, bounds)));
+            let on_rotate = selection_box(ctx).filter(|bounds| {
+                let reach = RESIZE_HANDLE_SIZE / 2.0 + HANDLE_REACH;
+                rotate_handle(ctx.camera, *bounds).distance(ctx.mouse_screen()) <= reach
+            });
+            // the spatial index returns the object drawn on top, not the oldest one
+            let tolerance = ctx.screen_to_world_distance(HIT_TOLERANCE);
+            let grab = ctx.screen_to_world_distance(RESIZE_HANDLE_SIZE / 2.0) + tolerance;
+            let on_end = ctx.selection.single().and_then(|id| {
+                let connector = ctx.document.connector(id)?;
+                [(connector.start, true), (connector.end, false)]
+                    .into_iter()
+                    .find(|(end, _)| end.position.distance(point) <= grab)
+                    .map(|(_, start)| (id, start))
+            });
+            let double_click = self.double_clicked(ctx);
+            if let Some((handle, origin)) = on_handle {
+                self.drag_state = DragState::Resizing { handle, origin, objects: originals(ctx) };
+            } else if let Some(bounds) = on_rotate {
+                let center = bounds.center();
+                let turned = ctx.selection.single().and_then(|id| ctx.document.shape(id)).map_or(0.0, |shape| shape.transform.rotation_angle());
+                self.drag_state = DragState::Rotating { center, start_angle: angle_from(center, point), turned, objects: originals(ctx) };
+            } else if let Some((id, start)) = on_end {
+                self.drag_state = DragState::MovingEnd(id, start);
+            } else if let Some(leaf) = topmost_pickable(ctx, point, tolerance) {
+                // clicking outside the group being edited leaves it
+                let entered = ctx.selection.entered();
+                if entered.is_some_and(|entered| !group::ancestors(ctx.document, leaf).any(|group| group == entered)) {
+                    ctx.selection.enter(None);
+                }
+                let mut id = ctx.selection.pick(ctx.document, leaf);
+                if double_click && ctx.document.group(id).is_some() {
+                    ctx.selection.enter(Some(id));
+                    id = ctx.selection.pick(ctx.document, leaf);
+                    ctx.selection.set(id);
+                }
+                if shift_down() {
+                    ctx.selection.toggle(id);
+                } else if !ctx.selection.contains(id) {
+                    ctx.selection.set(id);
+                }
+                // Shift-clicking an object away leaves nothing of it to drag
+                if let Some(origin) = ctx.selection.bounds(ctx.document).filter(|_| ctx.selection.contains(id)) {
+                    self.drag_state = DragState::Moving { start: point, origin, moved: Point::ZERO };
+                }
+            } else {
+                if !shift_down() {
+                    ctx.selection.clear();
+                    ctx.selection.enter(None);
+                }
+                let before = ctx.selection.clone();
+                self.drag_state = if alt_down() {
+                    DragState::Lasso { points: vec![point], before }
+                } else {
+                    DragState::Marquee { start: point, before }
+                };
+            }
+            // the whole drag becomes one undo step, committed on release
+            for id in ctx.selection.leaves(ctx.document) {
+                ctx.history.track(ctx.document, id);
+            }
+        }
+
+        match &mut self.drag_state {
+            DragState::Moving { start, origin, moved } => {
+                // snapping works from where the drag started, so a snapped object can be pulled
+                // free again by moving further
+                let mut offset = point - *start;
+                let dragged = ctx.selection.leaves(ctx.document);
+                let snapped = snap(ctx, &dragged, |snapper| snapper.snap_rect(origin.translate(offset)));
+                offset += snapped.offset;
+                self.guides = snapped.guides;
+                for id in dragged {
+                    if let Some(mut object) = ctx.document.get_mut(id) {
+                        object.translate(offset - *moved);
+                    }
+                }
+                *moved = offset;
+            }
+            DragState::Resizing { handle, origin, objects } => {
+                let dragged: Vec<ObjectId> = objects.iter().map(|(id, _)| *id).collect();
+                let snapped = snap(ctx, &dragged, |snapper| snapper.snap_point(point));
+                // an edge handle only moves along one axis, so guides along the other mean nothing
+                let (side_x, side_y) = handle.sides();
+                self.guides = snapped
+                    .guides
+                    .into_iter()
+                    .filter(|guide| {
+                        let (Guide::Alignment { from, to } | Guide::Gap { from, to }) = *guide;
+                        if from.x == to.x { side_x != 0.0 } else { side_y != 0.0 }
+                    })
+                    .collect();
+                let resize = Resize { handle: *handle, keep_aspect: shift_down(), from_center: alt_down(), min_size: MIN_SHAPE_SIZE };
+                let scale = resize.scale(*origin, point + snapped.offset);
+                for (id, original) in objects.iter() {
+                    if let Some(mut object) = ctx.document.get_mut(*id) {
+                        *object = original.clone();
+                        object.apply_transform(scale.transform());
+                    }
+                }
+            }
+            DragState::Rotating { center, start_angle, turned, objects } => {
+                let mut angle = angle_from(*center, point) - *start_angle;
+                if shift_down() {
+                    angle = ((*turned + angle) / ROTATE_STEP).round() * ROTATE_STEP - *turned;
+                }
+                let rotation = Transform::rotation(angle).about(*center);
+                for (id, original) in objects.iter() {
+                    if let Some(mut object) = ctx.document.get_mut(*id) {
+                        *object = original.clone();
+                        object.apply_transform(rotation);
+                    }
+                }
+            }
+            DragState::MovingEnd(id, start) => {
+                let (id, start) = (*id, *start);
+                let other = ctx.document.connector(id).map(|c| if start { c.end } else { c.start });
+                let end = endpoint_under_mouse(ctx, bound_shape(other));
+                if let Some(mut connector) = ctx.document.connector_mut(id) {
+                    *(if start { &mut connector.start } else { &mut connector.end }) = end;
+                }
+            }
+            DragState::Marquee { start, before } => {
+                let picked = selection::in_marquee(ctx.document, Rect::from_corners(*start, point));
+                *ctx.selection = before.clone();
+                ctx.selection.extend(before.pick_enclosed(ctx.document, &picked));
+            }
+            DragState::Lasso { points, before } => {
+                if points.last().is_none_or(|last| *last != point) {
+                    points.push(point);
+                }
+                let picked = selection::in_lasso(ctx.document, points);
+                *ctx.selection = before.clone();
+                ctx.selection.extend(before.pick_enclosed(ctx.document, &picked));
+            }
+            DragState::None => {}
+        }
+        if ctx.released() {
+            self.drag_state = DragState::None;
+            self.guides.clear();
+        }
+
+        let idle = ctx.keys_free() && matches!(self.drag_state, DragState::None);
+        let delete = is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace);
+        if delete && idle {
+            // a group goes with everything in it
+            for id in ctx.selection.ids().iter().flat_map(|id| ctx.document.subtree(*id)).collect::<Vec<_>>() {
+                ctx.history.track(ctx.document, id);
+                ctx.document.remove(id);
+            }
+            ctx.selection.clear();
+        }
+        // Escape steps out of the group being edited, picking it
+        if let Some(entered) = ctx.selection.entered().filter(|_| idle && is_key_pressed(KeyCode::Escape)) {
+            ctx.selection.set(entered);
+            ctx.selection.enter(ctx.document.parent(entered));
+        }
+    }
+
+    fn draw_preview(&self, ctx: &ToolContext) {
+        for guide in &self.guides {
+            draw_guide(ctx.camera, guide);
+        }
+        match &self.drag_state {
+            DragState::Marquee { start, .. } => {
+                let start = ctx.camera.world_to_screen(to_vec2(*start));
+                let (min, max) = (start.min(ctx.mouse_screen()), start.max(ctx.mouse_screen()));
+                draw_rectangle(min.x, min.y, max.x - min.x, max.y - min.y, MARQUEE_FILL);
+                draw_rectangle_lines(min.x, min.y, max.x - min.x, max.y - min.y, 1.0, MARQUEE_COLOR);
+            }
+            DragState::Lasso { points, .. } => {
+                let screen: Vec<Vec2> = points.iter().map(|point| ctx.camera.world_to_screen(to_vec2(*point))).collect();
+                // closed back to the start, as it will be when picking
+                for (i, a) in screen.iter().enumerate() {
+                    let b = screen[(i + 1) % screen.len()];
+                    draw_line(a.x, a.y, b.x, b.y, 1.0, MARQUEE_COLOR);
+                }
+            }
+            _ => {}
+        }
+
+        let outline = |corners: [Point; 4], thickness: f32| {
+            let corners = corners.map(|corner| ctx.camera.world_to_screen(to_vec2(corner)));
+            for i in 0..corners.len() {
+                let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+                draw_line(a.x, a.y, b.x, b.y, thickness, RED);
+            }
+        };
+        if let Some(bounds) = ctx.selection.entered().and_then(|group| ctx.document.bounds_of(group)) {
+            let top_left = ctx.camera.world_to_screen(to_vec2(bounds.min()));
+            let size = to_vec2(bounds.size()) * ctx.camera.zoom;
+            draw_rectangle_lines(top_left.x, top_left.y, size.x, size.y, 1.0, ENTERED_GROUP_COLOR);
+        }
+        // each object is outlined as drawn, turned shapes included, when the box alone doesn't show it
+        for id in ctx.selection.ids() {
+            match ctx.document.get(*id) {
+                Some(Object::Shape(shape)) if ctx.selection.len() > 1 || !shape.transform.is_identity() => {
+                    outline(shape.world_transform().corners(shape.bounds), 1.0);
+                }
+                Some(_) if ctx.selection.len() > 1 => {
+                    if let Some(bounds) = ctx.document.bounds_of(*id) {
+                        outline(Transform::IDENTITY.corners(bounds), 1.0);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(bounds) = ctx.selection.bounds(ctx.document) {
+            outline(Transform::IDENTITY.corners(bounds), 2.0);
+        }
+        if let Some(bounds) = selection_box(ctx) {
+            let (top, handle) = (ctx.camera.world_to_screen(to_vec2(Handle::Top.position(bounds))), rotate_handle(ctx.camera, bounds));
+            draw_line(top.x, top.y, handle.x, handle.y, 1.0, RED);
+            draw_circle(handle.x, handle.y, RESIZE_HANDLE_SIZE / 2.0, WHITE);
+            draw_circle_lines(handle.x, handle.y, RESIZE_HANDLE_SIZE / 2.0, 2.0, RED);
+            for handle in Handle::ALL {
+                let center = ctx.camera.world_to_screen(to_vec2(handle.position(bounds)));
+                let half = RESIZE_HANDLE_SIZE / 2.0;
+                draw_rectangle(center.x - half, center.y - half, RESIZE_HANDLE_SIZE, RESIZE_HANDLE_SIZE, WHITE);
+                draw_rectangle_lines(center.x - half, center.y - half, RESIZE_HANDLE_SIZE, RESIZE_HANDLE_SIZE, 2.0, RED);
+            }
+        }
+        if let Some(connector) = ctx.selection.single().and_then(|id| ctx.document.connector(id)) {
+            for end in [connector.start, connector.end] {
+                let handle = ctx.camera.world_to_screen(to_vec2(end.position));
+                draw_circle(handle.x, handle.y, RESIZE_HANDLE_SIZE / 2.0, RED);
+            }
+        }
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::Arrow
+    }
+
+    fn reset(&mut self, _ctx: &mut ToolContext) {
+        self.drag_state = DragState::None;
+        self.guides.clear();
+    }
+}
+
+fn draw_guide(camera: &Camera, guide: &Guide) {
+    let (Guide::Alignment { from, to } | Guide::Gap { from, to }) = *guide;
+    let (from, to) = (camera.world_to_screen(to_vec2(from)), camera.world_to_screen(to_vec2(to)));
+    draw_line(from.x, from.y, to.x, to.y, 1.0, GUIDE_COLOR);
+    if let Guide::Gap { .. } = guide {
+        // gaps are measured along one axis, so the ticks are across it
+        let tick = (to - from).normalize_or_zero().perp() * GAP_TICK;
+        for end in [from, to] {
+            draw_line(end.x - tick.x, end.y - tick.y, end.x + tick.x, end.y + tick.y, 1.0, GUIDE_COLOR);
+        }
+    }
+}
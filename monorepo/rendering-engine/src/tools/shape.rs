@@ -0,0 +1,77 @@
+use macroquad::rand;
+use neo_scene::{Color, Point, Rect, Shape, ShapeKind, Style};
+
+use crate::{cursor::CursorIcon, scene_renderer::draw_shape};
+
+use super::{Tool, ToolContext};
+
+/// Size of a shape placed with a click instead of a drag, in world units.
+const DEFAULT_SHAPE_SIZE: f64 = 60.0;
+/// Screen pixels the mouse has to move while pressed for a click to count as a drag.
+pub const CLICK_DISTANCE: f32 = 4.0;
+
+/// Drags out rectangles or ellipses from corner to corner. A plain click places one at the
+/// default size.
+pub struct ShapeTool {
+    kind: ShapeKind,
+    /// World position where the drag started.
+    anchor: Option<Point>,
+}
+
+impl ShapeTool {
+    pub fn new(kind: ShapeKind) -> Self {
+        ShapeTool { kind, anchor: None }
+    }
+
+    fn bounds(&self, anchor: Point, ctx: &ToolContext) -> Rect {
+        let corner = ctx.mouse_world();
+        if anchor.distance(corner) < ctx.screen_to_world_distance(CLICK_DISTANCE) {
+            Rect::new(anchor.x, anchor.y, DEFAULT_SHAPE_SIZE, DEFAULT_SHAPE_SIZE)
+        } else {
+            Rect::from_corners(anchor, corner)
+        }
+    }
+}
+
+impl Tool for ShapeTool {
+    fn update(&mut self, ctx: &mut ToolContext) {
+        if ctx.pressed() {
+            self.anchor = Some(ctx.mouse_world());
+        }
+        let Some(anchor) = self.anchor else {
+            return;
+        };
+        if ctx.released() {
+            let id = ctx.document.push(new_shape(self.kind, self.bounds(anchor, ctx)));
+            ctx.history.track_created(id);
+            ctx.selection.set(id);
+            self.anchor = None;
+        }
+    }
+
+    fn draw_preview(&self, ctx: &ToolContext) {
+        if let Some(anchor) = self.anchor {
+            let style = Style { fill: Color::new(0.5, 0.5, 0.5, 0.2), ..Style::stroked(Color::BLACK, 1.0 / ctx.camera.zoom as f64) };
+            draw_shape(&Shape::new(self.kind, self.bounds(anchor, ctx), style), ctx.camera, ctx.fonts);
+        }
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::Crosshair
+    }
+
+    fn reset(&mut self, _ctx: &mut ToolContext) {
+        self.anchor = None;
+    }
+}
+
+/// A shape with a random translucent fill.
+fn new_shape(kind: ShapeKind, bounds: Rect) -> Shape {
+    let fill = Color::new(
+        rand::gen_range(0.0, 1.0),
+        rand::gen_range(0.0, 1.0),
+        rand::gen_range(0.0, 1.0),
+        0.5,
+    );
+    Shape::new(kind, bounds, Style::filled(fill))
+}
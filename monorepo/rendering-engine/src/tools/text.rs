@@ -0,0 +1,264 @@
+use macroquad::{
+    color::{Color, GRAY},
+    input::{is_key_down, KeyCode},
+    math::vec2,
+    shapes::{draw_line, draw_triangle},
+    time::get_time,
+};
+use neo_scene::{
+    font::TextStyle,
+    layer,
+    text::{self, TextCursor},
+    Object, ObjectId, Rect, Shape, ShapeKind, Style,
+};
+
+use crate::{
+    cursor::CursorIcon,
+    scene_renderer::to_vec2,
+    text_input::{clipboard_get, clipboard_set, TextInput},
+    text_layout::TextLayout,
+};
+
+use super::{Tool, ToolContext};
+
+/// Size of a new text box in world units.
+const TEXT_BOX_WIDTH: f64 = 200.0;
+const TEXT_BOX_HEIGHT: f64 = 40.0;
+const SELECTION_COLOR: Color = Color::new(0.2, 0.45, 1.0, 0.3);
+
+/// Types into shapes. Clicking a shape edits its text; clicking empty canvas makes a new,
+/// unfilled text box. Clicking or dragging inside the text places the caret or selects, the
+/// arrow keys move by grapheme, word (with Ctrl) or line, and Ctrl+C/X/V use the clipboard.
+/// Escape or clicking elsewhere finishes the edit as one undo step.
+pub struct TextTool {
+    /// How the text of new boxes is set.
+    pub style: TextStyle,
+    editing: Option<ObjectId>,
+    /// The box being edited was made by this tool and is thrown away if left empty.
+    created: bool,
+    cursor: TextCursor,
+    /// Screen x the caret keeps to while moving up and down across shorter lines.
+    goal_x: Option<f32>,
+    /// The mouse went down in the text and is still held, selecting.
+    selecting: bool,
+}
+
+impl TextTool {
+    pub fn new() -> Self {
+        TextTool { style: TextStyle::default(), editing: None, created: false, cursor: TextCursor::default(), goal_x: None, selecting: false }
+    }
+
+    fn finish(&mut self, ctx: &mut ToolContext) {
+        self.selecting = false;
+        let Some(id) = self.editing.take() else {
+            return;
+        };
+        if self.created && ctx.document.shape(id).is_some_and(|shape| shape.text.is_empty()) {
+            ctx.document.remove(id);
+            ctx.selection.remove(id);
+        }
+        ctx.history.commit(ctx.document);
+    }
+
+    /// Starts editing whatever is under the mouse, or a new text box there.
+    fn begin(&mut self, ctx: &mut ToolContext) {
+        let point = ctx.mouse_world();
+        let hit = ctx
+            .document
+            .hits_at(point, 0.0)
+            .into_iter()
+            .find(|id| layer::is_pickable(ctx.document, *id))
+            .filter(|id| matches!(ctx.document.get(*id), Some(Object::Shape(_))));
+        let id = match hit {
+            Some(id) => {
+                ctx.history.track(ctx.document, id);
+                id
+            }
+            None => {
+                let bounds = Rect::new(point.x, point.y, TEXT_BOX_WIDTH, TEXT_BOX_HEIGHT);
+                let mut shape = Shape::new(ShapeKind::Rectangle, bounds, Style::filled(neo_scene::Color::TRANSPARENT));
+                shape.text_style = self.style.clone();
+                let id = ctx.document.push(shape);
+                ctx.history.track_created(id);
+                id
+            }
+        };
+        self.created = hit.is_none();
+        self.editing = Some(id);
+        self.cursor = TextCursor::default();
+        ctx.selection.set(id);
+    }
+
+    /// Reacts to one key. Returns `false` once the edit is over.
+    fn key(&mut self, ctx: &mut ToolContext, id: ObjectId, key: KeyCode, shift: bool, command: bool, word: bool) -> bool {
+        let Some(shape) = ctx.document.shape(id) else {
+            return false;
+        };
+        let content = shape.text.as_str();
+        let caret = self.cursor.caret;
+        let layout = TextLayout::for_shape(shape, ctx.camera, ctx.fonts);
+        let mut goal_x = None;
+        // moving without Shift collapses a selection to the side it moves towards
+        let collapse = self.cursor.has_selection() && !shift;
+        let target = match key {
+            KeyCode::Escape => return false,
+            KeyCode::Left if collapse => Some(self.cursor.range().start),
+            KeyCode::Right if collapse => Some(self.cursor.range().end),
+            KeyCode::Left if word => Some(text::previous_word(content, caret)),
+            KeyCode::Left => Some(text::previous_grapheme(content, caret)),
+            KeyCode::Right if word => Some(text::next_word(content, caret)),
+            KeyCode::Right => Some(text::next_grapheme(content, caret)),
+            KeyCode::Up | KeyCode::Down => {
+                let x = self.goal_x.unwrap_or(layout.caret(caret).x);
+                goal_x = Some(x);
+                Some(layout.vertical(caret, if key == KeyCode::Up { -1 } else { 1 }, x))
+            }
+            KeyCode::Home if command => Some(0),
+            KeyCode::End if command => Some(content.len()),
+            KeyCode::Home => Some(layout.line_bounds(caret).0),
+            KeyCode::End => Some(layout.line_bounds(caret).1),
+            KeyCode::A if command => {
+                self.cursor.select_all(content);
+                None
+            }
+            KeyCode::C | KeyCode::X if command && self.cursor.has_selection() => {
+                clipboard_set(self.cursor.selected(content));
+                if key == KeyCode::X {
+                    self.edit(ctx, id, |cursor, text| cursor.insert(text, ""));
+                }
+                None
+            }
+            KeyCode::V if command => {
+                if let Some(pasted) = clipboard_get() {
+                    let pasted = pasted.replace("\r\n", "\n");
+                    self.edit(ctx, id, |cursor, text| cursor.insert(text, &pasted));
+                }
+                None
+            }
+            KeyCode::Backspace => {
+                let to = if word { text::previous_word(content, caret) } else { text::previous_grapheme(content, caret) };
+                self.edit(ctx, id, |cursor, text| {
+                    cursor.delete_to(text, to);
+                });
+                None
+            }
+            KeyCode::Delete => {
+                let to = if word { text::next_word(content, caret) } else { text::next_grapheme(content, caret) };
+                self.edit(ctx, id, |cursor, text| {
+                    cursor.delete_to(text, to);
+                });
+                None
+            }
+            KeyCode::Enter | KeyCode::KpEnter => {
+                self.edit(ctx, id, |cursor, text| cursor.insert(text, "\n"));
+                None
+            }
+            _ => None,
+        };
+        if let Some(target) = target {
+            self.cursor.move_to(target, shift);
+        }
+        self.goal_x = goal_x;
+        true
+    }
+
+    fn edit(&mut self, ctx: &mut ToolContext, id: ObjectId, edit: impl FnOnce(&mut TextCursor, &mut String)) {
+        if let Some(mut shape) = ctx.document.shape_mut(id) {
+            edit(&mut self.cursor, &mut shape.text);
+        }
+    }
+}
+
+impl Tool for TextTool {
+    fn update(&mut self, ctx: &mut ToolContext) {
+        // dragging extends the selection from where the mouse went down
+        let mut extend = true;
+        if ctx.pressed() {
+            let point = ctx.mouse_world();
+            let inside = self.editing.and_then(|id| ctx.document.shape(id)).is_some_and(|shape| shape.hit_test(point, 0.0));
+            if !inside {
+                self.finish(ctx);
+                self.begin(ctx);
+            }
+            // Shift-click extends the selection in the text being edited
+            extend = inside && (is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift));
+            self.selecting = true;
+        }
+
+        let Some(id) = self.editing else {
+            return;
+        };
+        let Some(shape) = ctx.document.shape(id) else {
+            // undone or deleted underneath us
+            self.editing = None;
+            return;
+        };
+        // the text may have been changed from elsewhere, e.g. by a plugin
+        self.cursor.clamp(&shape.text);
+
+        if self.selecting {
+            let offset = TextLayout::for_shape(shape, ctx.camera, ctx.fonts).offset_at(ctx.mouse_screen());
+            self.cursor.move_to(offset, extend);
+            self.goal_x = None;
+            if ctx.released() {
+                self.selecting = false;
+            }
+        }
+
+        if ctx.ui_has_keyboard {
+            return;
+        }
+        for input in std::mem::take(&mut ctx.text_input) {
+            match input {
+                TextInput::Char(c) => {
+                    let mut buffer = [0; 4];
+                    self.edit(ctx, id, |cursor, text| cursor.insert(text, c.encode_utf8(&mut buffer)));
+                    self.goal_x = None;
+                }
+                TextInput::Key { key, shift, command, word } => {
+                    if !self.key(ctx, id, key, shift, command, word) {
+                        self.finish(ctx);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_preview(&self, ctx: &ToolContext) {
+        let Some(shape) = self.editing.and_then(|id| ctx.document.shape(id)) else {
+            return;
+        };
+        let corners = shape.world_transform().corners(shape.bounds).map(|corner| ctx.camera.world_to_screen(to_vec2(corner)));
+        for i in 0..corners.len() {
+            let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+            draw_line(a.x, a.y, b.x, b.y, 1.0, GRAY);
+        }
+
+        let layout = TextLayout::for_shape(shape, ctx.camera, ctx.fonts);
+        for (top_left, size) in layout.highlight(self.cursor.range()) {
+            let [a, b, c, d] = [top_left, top_left + vec2(size.x, 0.0), top_left + size, top_left + vec2(0.0, size.y)].map(|p| layout.to_screen(p));
+            draw_triangle(a, b, c, SELECTION_COLOR);
+            draw_triangle(a, c, d, SELECTION_COLOR);
+        }
+        // blinking caret
+        if get_time().fract() < 0.5 {
+            let caret = layout.caret(self.cursor.caret) + vec2(1.0, 0.0);
+            let top = layout.to_screen(caret - vec2(0.0, layout.ascent()));
+            let bottom = layout.to_screen(caret + vec2(0.0, layout.font_size * 0.2));
+            draw_line(top.x, top.y, bottom.x, bottom.y, 1.5, Color::new(0.0, 0.0, 0.0, 1.0));
+        }
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::IBeam
+    }
+
+    fn is_busy(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn reset(&mut self, ctx: &mut ToolContext) {
+        self.finish(ctx);
+    }
+}
@@ -0,0 +1,17 @@
+use macroquad::input::{is_key_down, is_key_pressed, KeyCode};
+use neo_scene::{history::History, Document};
+
+/// Ctrl+Z undoes, Ctrl+Shift+Z or Ctrl+Y redoes. Returns whether the document changed.
+pub fn handle_undo_shortcuts(history: &mut History, document: &mut Document) -> bool {
+    if !(is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)) {
+        return false;
+    }
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    if is_key_pressed(KeyCode::Z) && !shift {
+        history.undo(document)
+    } else if is_key_pressed(KeyCode::Y) || (is_key_pressed(KeyCode::Z) && shift) {
+        history.redo(document)
+    } else {
+        false
+    }
+}
@@ -1,36 +1,83 @@
-use macroquad::{input::{is_mouse_button_down, mouse_position, MouseButton}, math::Vec2};
-
-use crate::{camera::Camera, canvas_state::CanvasState};
-
+use macroquad::input::KeyCode;
 
+/// The tool the user is working with. Each one has a [`crate::tools::Tool`] implementation that
+/// handles its input, preview and cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum UserActionMode {
+    /// pick, move and resize objects
+    Select,
+    /// drag out a rectangle
+    Rectangle,
+    /// drag out an ellipse
+    Ellipse,
+    /// drag out a straight line
+    Line,
+    /// drag a connector between two shapes
+    Connector,
+    /// freehand drawing
+    Draw,
+    /// type into a new text box or an existing shape
+    Text,
     /// user is dragging canvas background
-    DRAG
+    Pan,
 }
 
-pub fn observe_user_action(camera: &mut Camera, mode: &UserActionMode, mut state: CanvasState) -> CanvasState {
-    match mode {
-        UserActionMode::DRAG => {
-            let (is_dragging, last_mouse_position) = handle_dragging(camera, state.is_dragging, state.last_mouse_position);
-            state.is_dragging = is_dragging;
-            state.last_mouse_position = last_mouse_position;
+impl UserActionMode {
+    /// Toolbar order.
+    pub const ALL: [UserActionMode; 8] = [
+        UserActionMode::Select,
+        UserActionMode::Rectangle,
+        UserActionMode::Ellipse,
+        UserActionMode::Line,
+        UserActionMode::Connector,
+        UserActionMode::Draw,
+        UserActionMode::Text,
+        UserActionMode::Pan,
+    ];
+
+    /// Stable name used when saving the view to a `.neo` file. The wasm builds use the same
+    /// names for the modes they share.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserActionMode::Select => "select",
+            UserActionMode::Rectangle => "rectangle",
+            UserActionMode::Ellipse => "ellipse",
+            UserActionMode::Line => "line",
+            UserActionMode::Connector => "connector",
+            UserActionMode::Draw => "draw",
+            UserActionMode::Text => "text",
+            UserActionMode::Pan => "drag",
         }
     }
-    state
-}
 
-pub fn handle_dragging(camera: &mut Camera, mut is_dragging: bool, mut last_mouse_position: Vec2) -> (bool, Vec2) {
-    if is_mouse_button_down(MouseButton::Left) {
-        if !is_dragging {
-            is_dragging = true;
-            last_mouse_position = mouse_position().into();
+    pub fn from_name(name: &str) -> Option<Self> {
+        UserActionMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UserActionMode::Select => "Select",
+            UserActionMode::Rectangle => "Rectangle",
+            UserActionMode::Ellipse => "Ellipse",
+            UserActionMode::Line => "Line",
+            UserActionMode::Connector => "Connector",
+            UserActionMode::Draw => "Draw",
+            UserActionMode::Text => "Text",
+            UserActionMode::Pan => "Pan",
         }
-        let current_mouse_position: Vec2 = mouse_position().into();
-        let delta = (current_mouse_position - last_mouse_position) / camera.zoom;
-        camera.position -= delta;
-        last_mouse_position = current_mouse_position;
-    } else {
-        is_dragging = false;
     }
-    (is_dragging, last_mouse_position)
-}
\ No newline at end of file
+
+    /// Key that switches to the tool.
+    pub fn shortcut(&self) -> KeyCode {
+        match self {
+            UserActionMode::Select => KeyCode::V,
+            UserActionMode::Rectangle => KeyCode::R,
+            UserActionMode::Ellipse => KeyCode::O,
+            UserActionMode::Line => KeyCode::L,
+            UserActionMode::Connector => KeyCode::C,
+            UserActionMode::Draw => KeyCode::P,
+            UserActionMode::Text => KeyCode::T,
+            UserActionMode::Pan => KeyCode::H,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::{collections::HashMap, error::Error, fs, io, path::Path};
+
+use serde::Deserialize;
+
+/// What a WebAssembly plugin may do beyond reading the canvas and drawing overlays, which every
+/// plugin can. Each one has to be granted to the plugin by name in the grants file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// Add, change and remove objects.
+    Edit,
+    /// Move and zoom the camera.
+    Camera,
+    /// Read and write files in the plugin's own data directory.
+    Files,
+}
+
+/// Capabilities per plugin name, as written by the user in `grants.json` in the plugins
+/// directory, e.g. `{ "stamp": ["edit"], "exporter": ["files"] }`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Grants(HashMap<String, Vec<Capability>>);
+
+impl Grants {
+    pub const FILE_NAME: &'static str = "grants.json";
+
+    /// Reads the grants file in `dir`. Without one, no plugin gets any capability.
+    pub fn load(dir: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(dir.join(Self::FILE_NAME)) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Grants::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn for_plugin(&self, name: &str) -> Vec<Capability> {
+        self.0.get(name).cloned().unwrap_or_default()
+    }
+}
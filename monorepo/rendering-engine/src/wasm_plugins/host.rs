@@ -0,0 +1,481 @@
+//! The `neo` import module: everything a WebAssembly plugin can ask of the engine. The ABI is
+//! described in `PLUGINS.md`.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use neo_scene::{
+    history::History,
+    layer,
+    plugin::{Input, Viewport},
+    Color, Document, Object, ObjectId, Point, Rect, Shape, ShapeKind, Stroke, Style,
+};
+use wasmi::{Caller, Error, Extern, Linker, Memory, StoreLimits};
+
+use super::capabilities::Capability;
+
+/// Results of host functions that report whether they did something.
+const DONE: i32 = 1;
+const NOTHING: i32 = 0;
+const FAILED: i32 = -1;
+/// The plugin lacks the capability the call needs, or the object it would change is locked.
+const DENIED: i32 = -2;
+/// Object ids are handed to plugins as `i64`; this stands for no object.
+const NO_ID: i64 = -1;
+
+/// Most bytes a plugin can pass through memory in one call.
+const MAX_TRANSFER: usize = 16 << 20;
+/// Screen pixels of slack when a plugin asks what is under a point.
+const HIT_TOLERANCE: f64 = 4.0;
+
+/// A plugin's overlay, kept until the `render` call returns and then drawn.
+pub enum DrawCommand {
+    Line { from: Point, to: Point, width: f64, color: Color },
+    Rect { rect: Rect, style: Style },
+    Ellipse { bounds: Rect, style: Style },
+    Text { text: String, position: Point, size: f64, color: Color },
+}
+
+/// Everything a plugin instance can reach. The document, history, viewport and input belong to
+/// the canvas and are only lent to the plugin for the length of each call.
+pub struct HostState {
+    pub name: String,
+    capabilities: Vec<Capability>,
+    /// Where the plugin's files go, if it may use them.
+    data_dir: PathBuf,
+    pub limits: StoreLimits,
+    pub document: Document,
+    pub history: History,
+    pub viewport: Viewport,
+    pub input: Input,
+    /// Tools the plugin registered, in toolbar order.
+    pub tools: Vec<String>,
+    pub active_tool: Option<usize>,
+    /// Only true during `render`; drawing at any other time is ignored.
+    pub drawing: bool,
+    pub draw_commands: Vec<DrawCommand>,
+    pub redraw: bool,
+}
+
+impl HostState {
+    pub fn new(name: String, capabilities: Vec<Capability>, data_dir: PathBuf, limits: StoreLimits) -> Self {
+        HostState {
+            name,
+            capabilities,
+            data_dir,
+            limits,
+            document: Document::default(),
+            history: History::default(),
+            viewport: Viewport { center: Point::ZERO, zoom: 1.0, width: 0.0, height: 0.0 },
+            input: Input::default(),
+            tools: Vec::new(),
+            active_tool: None,
+            drawing: false,
+            draw_commands: Vec::new(),
+            redraw: false,
+        }
+    }
+
+    fn allows(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Resolves a path the plugin gave inside its data directory, refusing anything that could
+    /// lead out of it.
+    fn data_file(&self, path: &str) -> Option<PathBuf> {
+        let path = Path::new(path);
+        let inside = path.components().all(|component| matches!(component, Component::Normal(_)));
+        (inside && !path.as_os_str().is_empty()).then(|| self.data_dir.join(path))
+    }
+
+    /// Runs an edit if the plugin may make it, asking for a repaint when something changed.
+    fn edit(&mut self, edit: impl FnOnce(&mut Document, &mut History) -> i32) -> i32 {
+        if !self.allows(Capability::Edit) {
+            return DENIED;
+        }
+        let result = edit(&mut self.document, &mut self.history);
+        if result == DONE {
+            self.redraw = true;
+        }
+        result
+    }
+
+    fn draw(&mut self, command: DrawCommand) {
+        if self.drawing {
+            self.draw_commands.push(command);
+        }
+    }
+}
+
+type Host<'a> = Caller<'a, HostState>;
+
+fn memory(caller: &Host) -> Result<Memory, Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Error::new("the plugin exports no memory"))
+}
+
+fn read_bytes(caller: &Host, ptr: i32, len: i32) -> Result<Vec<u8>, Error> {
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|len| *len <= MAX_TRANSFER)
+        .ok_or_else(|| Error::new(format!("invalid length {len}")))?;
+    let mut bytes = vec![0; len];
+    memory(caller)?
+        .read(caller, ptr as u32 as usize, &mut bytes)
+        .map_err(|e| Error::new(e.to_string()))?;
+    Ok(bytes)
+}
+
+fn read_string(caller: &Host, ptr: i32, len: i32) -> Result<String, Error> {
+    String::from_utf8(read_bytes(caller, ptr, len)?).map_err(|_| Error::new("string is not UTF-8"))
+}
+
+fn write_bytes(caller: &mut Host, ptr: i32, bytes: &[u8]) -> Result<(), Error> {
+    memory(caller)?
+        .write(caller, ptr as u32 as usize, bytes)
+        .map_err(|e| Error::new(e.to_string()))
+}
+
+/// Writes numbers as consecutive little-endian `f64`s.
+fn write_f64s(caller: &mut Host, ptr: i32, values: &[f64]) -> Result<(), Error> {
+    let bytes: Vec<u8> = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+    write_bytes(caller, ptr, &bytes)
+}
+
+fn to_wasm_id(id: ObjectId) -> i64 {
+    ((id.site() as u64) << 32 | id.seq() as u64) as i64
+}
+
+fn from_wasm_id(id: i64) -> ObjectId {
+    ObjectId::new((id as u64 >> 32) as u32, id as u32)
+}
+
+/// Colors are passed as `0xRRGGBBAA`.
+fn from_wasm_color(rgba: i32) -> Color {
+    let channel = |shift: u32| ((rgba as u32 >> shift) & 0xff) as f32 / 255.0;
+    Color::new(channel(24), channel(16), channel(8), channel(0))
+}
+
+fn shape_kind(kind: i32) -> Option<ShapeKind> {
+    match kind {
+        0 => Some(ShapeKind::Rectangle),
+        1 => Some(ShapeKind::Ellipse),
+        _ => None,
+    }
+}
+
+fn style(fill: i32, stroke: i32, stroke_width: f64) -> Style {
+    Style {
+        fill: from_wasm_color(fill),
+        stroke: from_wasm_color(stroke),
+        stroke_width,
+    }
+}
+
+/// Adds the `neo` functions to `linker`.
+pub fn link(linker: &mut Linker<HostState>) -> Result<(), Error> {
+    // the canvas, the input and the plugin itself
+    linker
+        .func_wrap("neo", "log", |caller: Host, ptr: i32, len: i32| -> Result<(), Error> {
+            eprintln!("[{}] {}", caller.data().name, read_string(&caller, ptr, len)?);
+            Ok(())
+        })?
+        .func_wrap("neo", "viewport", |mut caller: Host, out: i32| -> Result<(), Error> {
+            let v = caller.data().viewport;
+            write_f64s(&mut caller, out, &[v.center.x, v.center.y, v.zoom, v.width, v.height])
+        })?
+        .func_wrap("neo", "set_camera", |mut caller: Host, x: f64, y: f64, zoom: f64| -> i32 {
+            let state = caller.data_mut();
+            if !state.allows(Capability::Camera) {
+                return DENIED;
+            }
+            if !(x.is_finite() && y.is_finite() && zoom.is_finite() && zoom > 0.0) {
+                return FAILED;
+            }
+            state.viewport.center = Point::new(x, y);
+            state.viewport.zoom = zoom;
+            DONE
+        })?
+        .func_wrap("neo", "pointer", |mut caller: Host, out: i32| -> Result<i32, Error> {
+            let Some(pointer) = caller.data().input.pointer else {
+                return Ok(NOTHING);
+            };
+            write_f64s(&mut caller, out, &[pointer.x, pointer.y])?;
+            Ok(DONE)
+        })?
+        .func_wrap("neo", "buttons", |caller: Host| -> i32 {
+            let input = &caller.data().input;
+            [input.primary_down, input.primary_pressed, input.primary_released, input.shift, input.ctrl, input.alt]
+                .into_iter()
+                .enumerate()
+                .map(|(bit, set)| (set as i32) << bit)
+                .sum()
+        })?
+        .func_wrap("neo", "wheel", |mut caller: Host, out: i32| -> Result<(), Error> {
+            let wheel = caller.data().input.wheel;
+            write_f64s(&mut caller, out, &[wheel.x, wheel.y])
+        })?
+        .func_wrap("neo", "key_pressed", |caller: Host, ptr: i32, len: i32| -> Result<i32, Error> {
+            let key = read_string(&caller, ptr, len)?;
+            Ok(caller.data().input.key_pressed(&key) as i32)
+        })?
+        .func_wrap("neo", "register_tool", |mut caller: Host, ptr: i32, len: i32| -> Result<i32, Error> {
+            let name = read_string(&caller, ptr, len)?;
+            let tools = &mut caller.data_mut().tools;
+            tools.push(name);
+            Ok(tools.len() as i32 - 1)
+        })?
+        .func_wrap("neo", "active_tool", |caller: Host| -> i32 {
+            caller.data().active_tool.map_or(-1, |index| index as i32)
+        })?
+        .func_wrap("neo", "request_redraw", |mut caller: Host| {
+            caller.data_mut().redraw = true;
+        })?;
+
+    // reading the document
+    linker
+        .func_wrap("neo", "object_count", |caller: Host| -> i32 { caller.data().document.len() as i32 })?
+        .func_wrap("neo", "object_id", |caller: Host, index: i32| -> i64 {
+            let ids = caller.data().document.ids();
+            usize::try_from(index).ok().and_then(|index| ids.get(index)).map_or(NO_ID, |id| to_wasm_id(*id))
+        })?
+        .func_wrap("neo", "object_kind", |caller: Host, id: i64| -> i32 {
+            match caller.data().document.get(from_wasm_id(id)) {
+                Some(Object::Shape(shape)) if shape.kind == ShapeKind::Rectangle => 0,
+                Some(Object::Shape(_)) => 1,
+                Some(Object::Stroke(_)) => 2,
+                Some(Object::Connector(_)) => 3,
+                Some(Object::Group(_)) => 4,
+                None => FAILED,
+            }
+        })?
+        .func_wrap("neo", "object_bounds", |mut caller: Host, id: i64, out: i32| -> Result<i32, Error> {
+            let bounds = caller.data().document.bounds_of(from_wasm_id(id));
+            let Some(b) = bounds else {
+                return Ok(NOTHING);
+            };
+            write_f64s(&mut caller, out, &[b.x, b.y, b.width, b.height])?;
+            Ok(DONE)
+        })?
+        .func_wrap("neo", "object_at", |caller: Host, x: f64, y: f64| -> i64 {
+            let state = caller.data();
+            let tolerance = HIT_TOLERANCE / state.viewport.zoom;
+            let hits = state.document.hits_at(Point::new(x, y), tolerance);
+            hits.into_iter().find(|id| !layer::is_hidden(&state.document, *id)).map_or(NO_ID, to_wasm_id)
+        })?
+        .func_wrap("neo", "shape_text", |mut caller: Host, id: i64, out: i32, capacity: i32| -> Result<i32, Error> {
+            let Some(shape) = caller.data().document.shape(from_wasm_id(id)) else {
+                return Ok(FAILED);
+            };
+            let text = shape.text.clone().into_bytes();
+            let copied = text.len().min(capacity.max(0) as usize);
+            write_bytes(&mut caller, out, &text[..copied])?;
+            Ok(text.len() as i32)
+        })?;
+
+    // changing the document, which needs `edit`
+    linker
+        .func_wrap(
+            "neo",
+            "add_shape",
+            |mut caller: Host, kind: i32, x: f64, y: f64, width: f64, height: f64, fill: i32| -> i64 {
+                let state = caller.data_mut();
+                let Some(kind) = shape_kind(kind) else {
+                    return FAILED as i64;
+                };
+                let mut id = NO_ID;
+                let result = state.edit(|document, history| {
+                    let shape = Shape::new(kind, Rect::new(x, y, width, height), Style::filled(from_wasm_color(fill)));
+                    let created = document.push(shape);
+                    history.track_created(created);
+                    id = to_wasm_id(created);
+                    DONE
+                });
+                if result == DONE { id } else { result as i64 }
+            },
+        )?
+        .func_wrap(
+            "neo",
+            "add_stroke",
+            |mut caller: Host, points: i32, count: i32, width: f64, color: i32| -> Result<i64, Error> {
+                let bytes = read_bytes(&caller, points, count.saturating_mul(16))?;
+                let coords: Vec<f64> = bytes
+                    .chunks_exact(8)
+                    .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap_or_default()))
+                    .collect();
+                let mut id = NO_ID;
+                let result = caller.data_mut().edit(|document, history| {
+                    let mut stroke = Stroke::new(Style::stroked(from_wasm_color(color), width));
+                    stroke.points = coords.chunks_exact(2).map(|xy| Point::new(xy[0], xy[1])).collect();
+                    let created = document.push(stroke);
+                    history.track_created(created);
+                    id = to_wasm_id(created);
+                    DONE
+                });
+                Ok(if result == DONE { id } else { result as i64 })
+            },
+        )?
+        .func_wrap("neo", "move_object", |mut caller: Host, id: i64, dx: f64, dy: f64| -> i32 {
+            let id = from_wasm_id(id);
+            caller.data_mut().edit(|document, history| {
+                // a group moves everything in it, so one locked object inside stops the move
+                let leaves = document.leaves(id);
+                if leaves.iter().any(|leaf| layer::is_locked(document, *leaf)) {
+                    return DENIED;
+                }
+                for &leaf in &leaves {
+                    history.track(document, leaf);
+                    if let Some(mut object) = document.get_mut(leaf) {
+                        object.translate(Point::new(dx, dy));
+                    }
+                }
+                if leaves.is_empty() { NOTHING } else { DONE }
+            })
+        })?
+        .func_wrap(
+            "neo",
+            "set_shape_bounds",
+            |mut caller: Host, id: i64, x: f64, y: f64, width: f64, height: f64| -> i32 {
+                let id = from_wasm_id(id);
+                caller.data_mut().edit(|document, history| {
+                    if layer::is_locked(document, id) {
+                        return DENIED;
+                    }
+                    history.track(document, id);
+                    match document.shape_mut(id) {
+                        Some(mut shape) => {
+                            shape.bounds = Rect::new(x, y, width, height);
+                            DONE
+                        }
+                        None => NOTHING,
+                    }
+                })
+            },
+        )?
+        .func_wrap("neo", "set_shape_text", |mut caller: Host, id: i64, ptr: i32, len: i32| -> Result<i32, Error> {
+            let text = read_string(&caller, ptr, len)?;
+            let id = from_wasm_id(id);
+            Ok(caller.data_mut().edit(|document, history| {
+                if layer::is_locked(document, id) {
+                    return DENIED;
+                }
+                history.track(document, id);
+                match document.shape_mut(id) {
+                    Some(mut shape) => {
+                        shape.text = text;
+                        DONE
+                    }
+                    None => NOTHING,
+                }
+            }))
+        })?
+        .func_wrap("neo", "remove_object", |mut caller: Host, id: i64| -> i32 {
+            let id = from_wasm_id(id);
+            caller.data_mut().edit(|document, history| {
+                let removed = document.subtree(id);
+                if removed.iter().any(|id| layer::is_locked(document, *id)) {
+                    return DENIED;
+                }
+                for &id in &removed {
+                    history.track(document, id);
+                    document.remove(id);
+                }
+                if removed.is_empty() { NOTHING } else { DONE }
+            })
+        })?;
+
+    // overlays, in screen pixels, during `render` only
+    linker
+        .func_wrap(
+            "neo",
+            "draw_line",
+            |mut caller: Host, x1: f64, y1: f64, x2: f64, y2: f64, width: f64, color: i32| {
+                caller.data_mut().draw(DrawCommand::Line {
+                    from: Point::new(x1, y1),
+                    to: Point::new(x2, y2),
+                    width,
+                    color: from_wasm_color(color),
+                });
+            },
+        )?
+        .func_wrap(
+            "neo",
+            "draw_rect",
+            |mut caller: Host, x: f64, y: f64, width: f64, height: f64, fill: i32, stroke: i32, stroke_width: f64| {
+                caller.data_mut().draw(DrawCommand::Rect {
+                    rect: Rect::new(x, y, width, height),
+                    style: style(fill, stroke, stroke_width),
+                });
+            },
+        )?
+        .func_wrap(
+            "neo",
+            "draw_ellipse",
+            |mut caller: Host, x: f64, y: f64, width: f64, height: f64, fill: i32, stroke: i32, stroke_width: f64| {
+                caller.data_mut().draw(DrawCommand::Ellipse {
+                    bounds: Rect::new(x, y, width, height),
+                    style: style(fill, stroke, stroke_width),
+                });
+            },
+        )?
+        .func_wrap(
+            "neo",
+            "draw_text",
+            |mut caller: Host, ptr: i32, len: i32, x: f64, y: f64, size: f64, color: i32| -> Result<(), Error> {
+                let text = read_string(&caller, ptr, len)?;
+                caller.data_mut().draw(DrawCommand::Text {
+                    text,
+                    position: Point::new(x, y),
+                    size,
+                    color: from_wasm_color(color),
+                });
+                Ok(())
+            },
+        )?;
+
+    // the plugin's data directory, which needs `files`
+    linker
+        .func_wrap(
+            "neo",
+            "read_file",
+            |mut caller: Host, path: i32, path_len: i32, out: i32, capacity: i32| -> Result<i32, Error> {
+                let path = read_string(&caller, path, path_len)?;
+                let state = caller.data();
+                if !state.allows(Capability::Files) {
+                    return Ok(DENIED);
+                }
+                let Some(path) = state.data_file(&path) else {
+                    return Ok(DENIED);
+                };
+                let contents = match fs::read(&path) {
+                    Ok(contents) if contents.len() <= MAX_TRANSFER => contents,
+                    _ => return Ok(FAILED),
+                };
+                let copied = contents.len().min(capacity.max(0) as usize);
+                write_bytes(&mut caller, out, &contents[..copied])?;
+                Ok(contents.len() as i32)
+            },
+        )?
+        .func_wrap(
+            "neo",
+            "write_file",
+            |caller: Host, path: i32, path_len: i32, data: i32, len: i32| -> Result<i32, Error> {
+                let path = read_string(&caller, path, path_len)?;
+                let state = caller.data();
+                if !state.allows(Capability::Files) {
+                    return Ok(DENIED);
+                }
+                let Some(path) = state.data_file(&path) else {
+                    return Ok(DENIED);
+                };
+                let contents = read_bytes(&caller, data, len)?;
+                let written = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, contents));
+                Ok(if written.is_ok() { DONE } else { FAILED })
+            },
+        )?;
+    Ok(())
+}
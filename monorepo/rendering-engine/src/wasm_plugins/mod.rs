@@ -0,0 +1,218 @@
+//! Third-party plugins shipped as WebAssembly modules and run in the wasmi interpreter.
+//!
+//! Every `.wasm` file in the plugins directory becomes a plugin named after the file. Plugins
+//! only see the canvas through the narrow `neo` import module in [`host`], get the
+//! [`Capability`]s the user granted them in `grants.json`, run each hook on a fuel budget and
+//! with capped memory, and are switched off rather than taking the canvas down when they trap.
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use neo_scene::plugin::{Draw, Plugin, PluginContext};
+use wasmi::{Config, Engine, Instance, Linker, Module, Store, StoreLimitsBuilder, TypedFunc, WasmParams};
+
+mod capabilities;
+mod host;
+
+use capabilities::{Capability, Grants};
+use host::{DrawCommand, HostState};
+
+/// Where plugins are loaded from, relative to the working directory.
+pub const PLUGINS_DIR: &str = "plugins";
+
+/// Instructions a plugin may run per hook before it is stopped, so an endless loop can't
+/// freeze the canvas.
+const FUEL_PER_CALL: u64 = 20_000_000;
+/// Linear memory a plugin may grow to.
+const MAX_MEMORY: usize = 64 << 20;
+
+/// Loads every plugin in `dir`, in file name order. Plugins that fail to load are left out and
+/// described in the returned messages; a missing directory just means no plugins.
+pub fn load_plugins(dir: &Path) -> (Vec<Box<dyn Plugin>>, Vec<String>) {
+    let mut errors = Vec::new();
+    let grants = Grants::load(dir).unwrap_or_else(|e| {
+        errors.push(format!("{}: {e}", dir.join(Grants::FILE_NAME).display()));
+        Grants::default()
+    });
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    paths.sort();
+
+    let engine = Engine::new(Config::default().consume_fuel(true));
+    let mut plugins: Vec<Box<dyn Plugin>> = Vec::new();
+    for path in paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let capabilities = grants.for_plugin(&name);
+        match fs::read(&path).map_err(Into::into).and_then(|wasm| WasmPlugin::new(&engine, &name, &wasm, capabilities, dir)) {
+            Ok(plugin) => plugins.push(Box::new(plugin)),
+            Err(e) => errors.push(format!("{}: {e}", path.display())),
+        }
+    }
+    (plugins, errors)
+}
+
+/// The hooks a plugin module exports. All of them are optional.
+struct Hooks {
+    init: Option<TypedFunc<(), ()>>,
+    begin_frame: Option<TypedFunc<(), ()>>,
+    render: Option<TypedFunc<(), ()>>,
+    end_frame: Option<TypedFunc<(), ()>>,
+    resize: Option<TypedFunc<(f64, f64), ()>>,
+    destroy: Option<TypedFunc<(), ()>>,
+}
+
+/// One WebAssembly plugin instance.
+pub struct WasmPlugin {
+    name: String,
+    store: Store<HostState>,
+    hooks: Hooks,
+    /// Set once the plugin trapped; from then on its hooks are skipped.
+    stopped: bool,
+}
+
+impl WasmPlugin {
+    pub fn new(
+        engine: &Engine,
+        name: &str,
+        wasm: &[u8],
+        capabilities: Vec<Capability>,
+        plugins_dir: &Path,
+    ) -> Result<Self, Box<dyn Error>> {
+        let module = Module::new(engine, wasm)?;
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY).instances(1).build();
+        let data_dir = plugins_dir.join(name);
+        let mut store = Store::new(engine, HostState::new(name.to_owned(), capabilities, data_dir, limits));
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_CALL).map_err(wasmi::Error::from)?;
+
+        let mut linker = Linker::new(engine);
+        host::link(&mut linker)?;
+        let instance = linker.instantiate(&mut store, &module)?.start(&mut store)?;
+
+        // an export with the wrong signature is a mistake worth reporting, not skipping
+        fn hook<P: WasmParams>(
+            store: &Store<HostState>,
+            instance: &Instance,
+            export: &str,
+        ) -> Result<Option<TypedFunc<P, ()>>, String> {
+            if instance.get_export(store, export).is_none() {
+                return Ok(None);
+            }
+            let func = instance.get_typed_func(store, export).map_err(|e| format!("export {export:?}: {e}"))?;
+            Ok(Some(func))
+        }
+        let hooks = Hooks {
+            init: hook(&store, &instance, "init")?,
+            begin_frame: hook(&store, &instance, "begin_frame")?,
+            render: hook(&store, &instance, "render")?,
+            end_frame: hook(&store, &instance, "end_frame")?,
+            resize: hook(&store, &instance, "resize")?,
+            destroy: hook(&store, &instance, "destroy")?,
+        };
+        Ok(WasmPlugin { name: name.to_owned(), store, hooks, stopped: false })
+    }
+
+    /// Calls a hook with the context's document, history, viewport and input lent to the
+    /// plugin, and takes them back afterwards even if the plugin trapped.
+    fn call<P: WasmParams>(
+        &mut self,
+        ctx: &mut PluginContext,
+        hook: impl FnOnce(&Hooks) -> Option<TypedFunc<P, ()>>,
+        params: P,
+    ) {
+        if self.stopped {
+            return;
+        }
+        let Some(func) = hook(&self.hooks) else {
+            return;
+        };
+        let state = self.store.data_mut();
+        std::mem::swap(&mut state.document, ctx.document);
+        std::mem::swap(&mut state.history, ctx.history);
+        state.viewport = *ctx.viewport;
+        state.input = ctx.input.clone();
+        state.active_tool = ctx.active_tool().and_then(|tool| state.tools.iter().position(|own| own == tool));
+
+        let result = self.store.set_fuel(FUEL_PER_CALL).map_err(Into::into).and_then(|_| func.call(&mut self.store, params));
+
+        let state = self.store.data_mut();
+        std::mem::swap(&mut state.document, ctx.document);
+        std::mem::swap(&mut state.history, ctx.history);
+        *ctx.viewport = state.viewport;
+        if std::mem::take(&mut state.redraw) {
+            ctx.request_redraw();
+        }
+        if let Err(e) = result {
+            self.stop(&e.to_string());
+        }
+    }
+
+    fn stop(&mut self, reason: &str) {
+        eprintln!("plugin {} stopped: {reason}", self.name);
+        self.stopped = true;
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn init(&mut self, ctx: &mut PluginContext) {
+        self.call(ctx, |hooks| hooks.init, ());
+    }
+
+    fn begin_frame(&mut self, ctx: &mut PluginContext) {
+        self.call(ctx, |hooks| hooks.begin_frame, ());
+    }
+
+    fn render(&mut self, ctx: &mut PluginContext, draw: &mut dyn Draw) {
+        self.store.data_mut().drawing = true;
+        self.call(ctx, |hooks| hooks.render, ());
+        let state = self.store.data_mut();
+        state.drawing = false;
+        // whatever was drawn before a trap is still shown this once
+        for command in state.draw_commands.drain(..) {
+            match command {
+                DrawCommand::Line { from, to, width, color } => draw.line(from, to, width, color),
+                DrawCommand::Rect { rect, style } => draw.rect(rect, style),
+                DrawCommand::Ellipse { bounds, style } => draw.ellipse(bounds, style),
+                DrawCommand::Text { text, position, size, color } => draw.text(&text, position, size, color),
+            }
+        }
+    }
+
+    fn end_frame(&mut self, ctx: &mut PluginContext) {
+        self.call(ctx, |hooks| hooks.end_frame, ());
+    }
+
+    fn resize(&mut self, ctx: &mut PluginContext, width: f64, height: f64) {
+        self.call(ctx, |hooks| hooks.resize, (width, height));
+    }
+
+    /// Runs without a context, so the plugin sees an empty document while it shuts down.
+    fn destroy(&mut self) {
+        let Some(destroy) = self.hooks.destroy.filter(|_| !self.stopped) else {
+            return;
+        };
+        let result = self.store.set_fuel(FUEL_PER_CALL).map_err(Into::into).and_then(|_| destroy.call(&mut self.store, ()));
+        if let Err(e) = result {
+            self.stop(&e.to_string());
+        }
+    }
+
+    fn tools(&self) -> Vec<String> {
+        if self.stopped {
+            return Vec::new();
+        }
+        self.store.data().tools.clone()
+    }
+}